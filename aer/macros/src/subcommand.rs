@@ -276,9 +276,9 @@ impl Command<'_> {
             method.client_streaming(),
             method.server_streaming(),
         ) {
-            (true, true) => {
-                todo!("bidirectional streaming")
-            }
+            (true, true) => quote! {
+                crate::execute_bidirectional_streaming!(::client::#module::#client_mod::#client_ident::#function, req);
+            },
             (true, false) => {
                 todo!("client streaming")
             }
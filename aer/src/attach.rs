@@ -0,0 +1,217 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+//! Attaches the terminal of aer to bidirectional streaming rpc calls.
+
+use futures_util::{Stream, StreamExt};
+use nix::libc;
+use nix::sys::termios::{self, SetArg, Termios};
+use std::io::{IsTerminal, Read, Write};
+use std::os::fd::{AsFd, AsRawFd};
+use std::pin::Pin;
+use tokio::signal::unix::{SignalKind, signal};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+nix::ioctl_read_bad!(get_window_size, libc::TIOCGWINSZ, libc::winsize);
+
+/// Implemented by the requests of a bidirectional streaming rpc call, so
+/// [execute_bidirectional_streaming](crate::execute_bidirectional_streaming)
+/// can send stdin and terminal resizes with them.
+pub trait Attach: Sized + Send + 'static {
+    type Response;
+
+    /// Whether the remote process has a terminal, in which case the local
+    /// terminal is put in raw mode.
+    fn tty(&self) -> bool;
+
+    /// Whether stdin is sent to the remote process.
+    fn interactive(&self) -> bool;
+
+    fn set_terminal_size(&mut self, size: TerminalSize);
+
+    fn stdin(bytes: Vec<u8>) -> Self;
+
+    fn close_stdin() -> Self;
+
+    fn resize(size: TerminalSize) -> Self;
+
+    fn output(response: Self::Response) -> Output;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub rows: u16,
+    pub columns: u16,
+}
+
+/// What a response of the remote process carries.
+#[derive(Debug, Default)]
+pub struct Output {
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+    /// Only set once the remote process exited
+    pub exit_code: Option<i32>,
+}
+
+/// The terminal of aer, attached to a bidirectional streaming rpc call.
+/// The terminal is restored when dropped.
+#[derive(Debug)]
+pub struct Attached<Req> {
+    first: Option<Req>,
+    raw_mode: Option<RawMode>,
+    resizes: Option<JoinHandle<()>>,
+}
+
+impl<Req: Attach> Attached<Req> {
+    pub fn new(mut first: Req) -> anyhow::Result<Self> {
+        let raw_mode = if first.tty() && std::io::stdin().is_terminal() {
+            if let Some(size) = terminal_size() {
+                first.set_terminal_size(size);
+            }
+            Some(RawMode::enter()?)
+        } else {
+            None
+        };
+
+        Ok(Self { first: Some(first), raw_mode, resizes: None })
+    }
+
+    /// The requests of the call: the first one, followed by the ones sending
+    /// stdin and terminal resizes.
+    pub fn requests(
+        &mut self,
+    ) -> anyhow::Result<Pin<Box<dyn Stream<Item = Req> + Send + 'static>>> {
+        let first = self.first.take().expect("requests are only taken once");
+
+        let (tx, rx) = mpsc::channel(16);
+        if first.interactive() {
+            forward_stdin(tx.clone());
+        }
+        if self.raw_mode.is_some() {
+            self.resizes = Some(forward_resizes(tx)?);
+        }
+
+        let rest = futures_util::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|req| (req, rx))
+        });
+
+        Ok(Box::pin(futures_util::stream::once(async { first }).chain(rest)))
+    }
+
+    /// Writes the output of the responses to stdout and stderr, until the
+    /// last one. Returns the exit code of the remote process, if it exited.
+    pub async fn run<S, E>(self, responses: S) -> anyhow::Result<Option<i32>>
+    where
+        S: Stream<Item = Result<Req::Response, E>>,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let mut responses = std::pin::pin!(responses);
+        let mut stdout = std::io::stdout();
+        let mut stderr = std::io::stderr();
+
+        let mut exit_code = None;
+        while let Some(res) = responses.next().await {
+            let output = Req::output(res?);
+
+            stdout.write_all(&output.stdout)?;
+            stdout.flush()?;
+            stderr.write_all(&output.stderr)?;
+            stderr.flush()?;
+
+            exit_code = output.exit_code.or(exit_code);
+        }
+
+        Ok(exit_code)
+    }
+}
+
+impl<Req> Drop for Attached<Req> {
+    fn drop(&mut self) {
+        if let Some(resizes) = &self.resizes {
+            resizes.abort();
+        }
+    }
+}
+
+/// Restores the original mode of the terminal when dropped.
+#[derive(Debug)]
+struct RawMode(Termios);
+
+impl RawMode {
+    fn enter() -> nix::Result<Self> {
+        let stdin = std::io::stdin();
+        let original = termios::tcgetattr(stdin.as_fd())?;
+
+        let mut raw = original.clone();
+        termios::cfmakeraw(&mut raw);
+        termios::tcsetattr(stdin.as_fd(), SetArg::TCSANOW, &raw)?;
+
+        Ok(Self(original))
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let stdin = std::io::stdin();
+        let _ = termios::tcsetattr(stdin.as_fd(), SetArg::TCSANOW, &self.0);
+    }
+}
+
+fn terminal_size() -> Option<TerminalSize> {
+    let mut size =
+        libc::winsize { ws_row: 0, ws_col: 0, ws_xpixel: 0, ws_ypixel: 0 };
+    let _ = unsafe { get_window_size(std::io::stdin().as_raw_fd(), &mut size) }
+        .ok()?;
+
+    Some(TerminalSize { rows: size.ws_row, columns: size.ws_col })
+}
+
+/// Reads stdin on a thread of its own, as reading it blocks until there is
+/// input, and sends it until the end of file.
+fn forward_stdin<Req: Attach>(tx: mpsc::Sender<Req>) {
+    let _ = std::thread::spawn(move || {
+        let mut stdin = std::io::stdin().lock();
+        let mut buf = [0u8; 4096];
+        loop {
+            let (req, eof) = match stdin.read(&mut buf) {
+                Ok(0) | Err(_) => (Req::close_stdin(), true),
+                Ok(n) => (Req::stdin(buf[..n].to_vec()), false),
+            };
+
+            if tx.blocking_send(req).is_err() || eof {
+                break;
+            }
+        }
+    });
+}
+
+/// Sends the size of the terminal every time it is resized.
+fn forward_resizes<Req: Attach>(
+    tx: mpsc::Sender<Req>,
+) -> std::io::Result<JoinHandle<()>> {
+    let mut resized = signal(SignalKind::window_change())?;
+
+    Ok(tokio::spawn(async move {
+        while resized.recv().await.is_some() {
+            let Some(size) = terminal_size() else {
+                continue;
+            };
+            if tx.send(Req::resize(size)).await.is_err() {
+                break;
+            }
+        }
+    }))
+}
@@ -41,6 +41,7 @@ unused_results
 #![warn(clippy::unwrap_used)]
 // #![warn(missing_docs)] // TODO: We want the docs from the proto
 
+pub mod attach;
 pub mod cri;
 pub mod discovery;
 pub mod grpc;
@@ -72,3 +73,21 @@ macro_rules! execute_server_streaming {
         }
     }};
 }
+
+/// Executes a bidirectional streaming rpc call with the default `Client`.
+/// The first request is made from the command line arguments, and the
+/// terminal is attached to the rest of the call (see [attach::Attach]).
+/// Exits with the exit code of the remote process, if it failed.
+#[macro_export]
+macro_rules! execute_bidirectional_streaming {
+    ($call:path, $req:ident) => {{
+        let client = ::client::Client::default().await?;
+        let mut attached = $crate::attach::Attached::new($req)?;
+        let res = $call(&client, attached.requests()?).await?.into_inner();
+        if let Some(code) = attached.run(res).await?
+            && code != 0
+        {
+            ::std::process::exit(code);
+        }
+    }};
+}
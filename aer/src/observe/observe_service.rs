@@ -46,5 +46,25 @@ macros::subcommand!("../api/v0/observe/observe.proto", observe, ObserveService,
     GetSubProcessStream {
         process_id[required = true, alias = "pid"],
         channel_type[default_value = "1"],  // default to stdout
+        from_beginning[long, default_value = "false"],
+        tail[long],
+        since_timestamp[long, alias = "since"],
+        since_timestamp_nanos[long, alias = "since-nanos"],
+    },
+    GetExecutableLogStream {
+        cell_name[long],
+        executable_name[required = true],
+        channel_type[long, default_value = "0"],  // default to stdout and stderr
+        tail[long],
+        since_timestamp[long, alias = "since"],
+        since_timestamp_nanos[long, alias = "since-nanos"],
+    },
+    GetCellEventsStream {
+        workload_workload_type[long, default_value = "0"],
+        workload_id[long, alias = "cell-name", default_value = ""],
+    },
+    GetAuditEventsStream {
+        workload_workload_type[long, default_value = "0"],
+        workload_id[long, alias = "cell-name", default_value = ""],
     },
 );
@@ -42,6 +42,11 @@
  *                                                                            *
 \* -------------------------------------------------------------------------- */
 
+use crate::attach::{self, Attach, Output};
+use proto::cells::{
+    CellServiceExecRequest, CellServiceExecResponse, TerminalSize,
+};
+
 macros::subcommand!(
     "../api/v0/cells/cells.proto",
     cells,
@@ -52,20 +57,215 @@ macros::subcommand!(
         cell_cpu_max[long, alias = "cpu-max"],
         cell_cpuset_cpus[long, alias = "cpuset-cpus"],
         cell_cpuset_mems[long, alias = "cpuset-mems"],
+        cell_memory_min[long, alias = "memory-min"],
+        cell_memory_low[long, alias = "memory-low"],
+        cell_memory_high[long, alias = "memory-high"],
+        cell_memory_max[long, alias = "memory-max"],
+        cell_memory_swap_max[long, alias = "memory-swap-max"],
+        cell_memory_swap_high[long, alias = "memory-swap-high"],
+        cell_io_devices_device[long, alias = "io-device", default_value = ""],
+        cell_io_devices_weight[long, alias = "io-weight"],
+        cell_io_devices_rbps[long, alias = "io-rbps"],
+        cell_io_devices_wbps[long, alias = "io-wbps"],
+        cell_io_devices_riops[long, alias = "io-riops"],
+        cell_io_devices_wiops[long, alias = "io-wiops"],
+        cell_pids_max[long, alias = "pids-max"],
         cell_isolate_process[long, default_value = "false"],
         cell_isolate_network[long, default_value = "false"],
+        cell_isolate_user[long, default_value = "false"],
+        cell_uid_mappings_container_id[long, alias = "uid-container-id", default_value = "0"],
+        cell_uid_mappings_host_id[long, alias = "uid-host-id", default_value = "0"],
+        cell_uid_mappings_size[long, alias = "uid-size", default_value = "0"],
+        cell_gid_mappings_container_id[long, alias = "gid-container-id", default_value = "0"],
+        cell_gid_mappings_host_id[long, alias = "gid-host-id", default_value = "0"],
+        cell_gid_mappings_size[long, alias = "gid-size", default_value = "0"],
+        cell_rootfs_path[long, alias = "rootfs", default_value = ""],
+        cell_rootfs_upper_dir[long, alias = "rootfs-upper-dir"],
+        cell_mounts_target[long, alias = "mount-target", default_value = ""],
+        cell_mounts_kind[long, alias = "mount-kind", default_value = "1"],
+        cell_mounts_source[long, alias = "mount-source", default_value = ""],
+        cell_mounts_read_only[long, alias = "mount-read-only", default_value = "false"],
+        cell_mounts_propagation[long, alias = "mount-propagation", default_value = "0"],
+        cell_mounts_size_bytes[long, alias = "mount-size-bytes", default_value = "0"],
+        cell_seccomp_profile[long, alias = "seccomp-profile"],
     },
     Free {
         cell_name[required = true],
     },
+    Update {
+        cell_name[required = true],
+        cell_cpu_weight[long, alias = "cpu-weight"],
+        cell_cpu_max[long, alias = "cpu-max"],
+        cell_cpuset_cpus[long, alias = "cpuset-cpus"],
+        cell_cpuset_mems[long, alias = "cpuset-mems"],
+        cell_memory_min[long, alias = "memory-min"],
+        cell_memory_low[long, alias = "memory-low"],
+        cell_memory_high[long, alias = "memory-high"],
+        cell_memory_max[long, alias = "memory-max"],
+        cell_memory_swap_max[long, alias = "memory-swap-max"],
+        cell_memory_swap_high[long, alias = "memory-swap-high"],
+        cell_io_devices_device[long, alias = "io-device", default_value = ""],
+        cell_io_devices_weight[long, alias = "io-weight"],
+        cell_io_devices_rbps[long, alias = "io-rbps"],
+        cell_io_devices_wbps[long, alias = "io-wbps"],
+        cell_io_devices_riops[long, alias = "io-riops"],
+        cell_io_devices_wiops[long, alias = "io-wiops"],
+        cell_pids_max[long, alias = "pids-max"],
+        cell_isolate_process[long, default_value = "false"],
+        cell_isolate_network[long, default_value = "false"],
+        cell_isolate_user[long, default_value = "false"],
+        cell_uid_mappings_container_id[long, alias = "uid-container-id", default_value = "0"],
+        cell_uid_mappings_host_id[long, alias = "uid-host-id", default_value = "0"],
+        cell_uid_mappings_size[long, alias = "uid-size", default_value = "0"],
+        cell_gid_mappings_container_id[long, alias = "gid-container-id", default_value = "0"],
+        cell_gid_mappings_host_id[long, alias = "gid-host-id", default_value = "0"],
+        cell_gid_mappings_size[long, alias = "gid-size", default_value = "0"],
+        cell_rootfs_path[long, alias = "rootfs", default_value = ""],
+        cell_rootfs_upper_dir[long, alias = "rootfs-upper-dir"],
+        cell_mounts_target[long, alias = "mount-target", default_value = ""],
+        cell_mounts_kind[long, alias = "mount-kind", default_value = "1"],
+        cell_mounts_source[long, alias = "mount-source", default_value = ""],
+        cell_mounts_read_only[long, alias = "mount-read-only", default_value = "false"],
+        cell_mounts_propagation[long, alias = "mount-propagation", default_value = "0"],
+        cell_mounts_size_bytes[long, alias = "mount-size-bytes", default_value = "0"],
+        cell_seccomp_profile[long, alias = "seccomp-profile"],
+    },
     Start {
         cell_name[required = true],
         executable_name[required = true],
         executable_command[required = true, long, aliases = ["command", "cmd"], short = 'c'],
         executable_description[long, aliases = ["description", "desc"], default_value = ""],
+        executable_args[long, alias = "arg", action = clap::ArgAction::Append],
+        executable_env_name[long, alias = "env-name", default_value = ""],
+        executable_env_value[long, alias = "env-value", default_value = ""],
+        executable_clear_env[long, alias = "clear-env", default_value = "false"],
+        executable_working_dir[long, aliases = ["working-dir", "workdir"]],
+        executable_restart_policy[long, alias = "restart-policy", default_value = "1"],
+        executable_max_restarts[long, alias = "max-restarts"],
+        executable_restart_backoff_ms[long, alias = "restart-backoff-ms"],
+        executable_max_restart_backoff_ms[long, alias = "max-restart-backoff-ms"],
+        executable_seccomp_profile[long, alias = "seccomp-profile"],
+        executable_capabilities_privileged[long, alias = "privileged", default_value = "false"],
+        executable_capabilities_bounding[long, alias = "cap-bounding", action = clap::ArgAction::Append],
+        executable_capabilities_effective[long, alias = "cap-effective", action = clap::ArgAction::Append],
+        executable_capabilities_permitted[long, alias = "cap-permitted", action = clap::ArgAction::Append],
+        executable_capabilities_inheritable[long, alias = "cap-inheritable", action = clap::ArgAction::Append],
+        executable_capabilities_ambient[long, alias = "cap-ambient", action = clap::ArgAction::Append],
+        executable_rlimits_kind[long, alias = "rlimit-kind", default_value = "0"],
+        executable_rlimits_soft[long, alias = "rlimit-soft"],
+        executable_rlimits_hard[long, alias = "rlimit-hard"],
+        executable_command_mode[long, alias = "command-mode", default_value = "1"],
     },
     Stop {
         cell_name[required = true],
         executable_name[required = true],
+        signal[long],
+        grace_period_ms[long, alias = "grace-period"],
+    },
+    Signal {
+        cell_name[required = true],
+        executable_name[required = true],
+        signal[required = true],
+    },
+    Wait {
+        cell_name[required = true],
+        executable_name[required = true],
+    },
+    Exec {
+        cell_name[required = true],
+        executable_name[required = true],
+        executable_command[required = true, long, aliases = ["command", "cmd"], short = 'c'],
+        executable_description[long, hide = true, default_value = ""],
+        executable_args[long, alias = "arg", action = clap::ArgAction::Append],
+        executable_env_name[long, alias = "env-name", default_value = ""],
+        executable_env_value[long, alias = "env-value", default_value = ""],
+        executable_clear_env[long, alias = "clear-env", default_value = "false"],
+        executable_working_dir[long, aliases = ["working-dir", "workdir"]],
+        executable_restart_policy[long, hide = true, default_value = "1"],
+        executable_max_restarts[long, hide = true],
+        executable_restart_backoff_ms[long, hide = true],
+        executable_max_restart_backoff_ms[long, hide = true],
+        executable_seccomp_profile[long, alias = "seccomp-profile"],
+        executable_capabilities_privileged[long, alias = "privileged", default_value = "false"],
+        executable_capabilities_bounding[long, alias = "cap-bounding", action = clap::ArgAction::Append],
+        executable_capabilities_effective[long, alias = "cap-effective", action = clap::ArgAction::Append],
+        executable_capabilities_permitted[long, alias = "cap-permitted", action = clap::ArgAction::Append],
+        executable_capabilities_inheritable[long, alias = "cap-inheritable", action = clap::ArgAction::Append],
+        executable_capabilities_ambient[long, alias = "cap-ambient", action = clap::ArgAction::Append],
+        executable_rlimits_kind[long, alias = "rlimit-kind", default_value = "0"],
+        executable_rlimits_soft[long, alias = "rlimit-soft"],
+        executable_rlimits_hard[long, alias = "rlimit-hard"],
+        executable_command_mode[long, alias = "command-mode", default_value = "1"],
+        tty[long, short = 't', default_value = "false"],
+        interactive[long, short = 'i', default_value = "false"],
+        stdin[long, hide = true],
+        close_stdin[long, hide = true, default_value = "false"],
+        terminal_size_rows[long, hide = true, default_value = "0"],
+        terminal_size_columns[long, hide = true, default_value = "0"],
+    },
+    Stats {
+        cell_name[required = true],
+    },
+    WatchStats {
+        cell_name[required = true],
+        interval_ms[long, alias = "interval"],
+    },
+    Pause {
+        cell_name[required = true],
+    },
+    Resume {
+        cell_name[required = true],
+    },
+    WatchPressure {
+        cell_name[required = true],
+        resource[long, default_value = "2"],
+        kind[long, default_value = "1"],
+        stall_ms[long, alias = "stall", default_value = "150"],
+        window_ms[long, alias = "window", default_value = "1000"],
     },
 );
+
+impl Attach for CellServiceExecRequest {
+    type Response = CellServiceExecResponse;
+
+    fn tty(&self) -> bool {
+        self.tty
+    }
+
+    fn interactive(&self) -> bool {
+        self.interactive
+    }
+
+    fn set_terminal_size(&mut self, size: attach::TerminalSize) {
+        self.terminal_size = Some(TerminalSize {
+            rows: size.rows.into(),
+            columns: size.columns.into(),
+        });
+    }
+
+    fn stdin(bytes: Vec<u8>) -> Self {
+        Self { stdin: bytes, ..Default::default() }
+    }
+
+    fn close_stdin() -> Self {
+        Self { close_stdin: true, ..Default::default() }
+    }
+
+    fn resize(size: attach::TerminalSize) -> Self {
+        let mut req = Self::default();
+        req.set_terminal_size(size);
+        req
+    }
+
+    fn output(response: Self::Response) -> Output {
+        let CellServiceExecResponse { pid: _, stdout, stderr, exit_status } =
+            response;
+
+        // Like a shell, report a process killed by a signal as 128 + signal
+        let exit_code = exit_status.map(|x| {
+            x.code.unwrap_or_else(|| 128 + x.signal.unwrap_or_default())
+        });
+
+        Output { stdout, stderr, exit_code }
+    }
+}
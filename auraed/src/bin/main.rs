@@ -39,8 +39,10 @@
 
 use auraed::{AuraedRuntime, prep_oci_spec_for_spawn, run};
 use clap::{Parser, Subcommand};
+use ipnetwork::{Ipv4Network, Ipv6Network};
 use std::path::PathBuf;
 use std::process::ExitCode;
+use std::time::Duration;
 use tracing::{error, info};
 
 /// Command line options for auraed.
@@ -92,6 +94,33 @@ struct AuraedOptions {
     /// should respect this value.
     #[clap(short, long, value_parser)]
     library_dir: Option<String>,
+    /// Aurae logs path. Defaults to ${`runtime_dir`}/logs
+    ///
+    /// Here is where the daemon will write the log files of the
+    /// executables it runs, which survive restarts of the daemon.
+    #[clap(long, value_parser)]
+    logs_dir: Option<String>,
+    /// The size, in bytes, log files are rotated at. Defaults to 10485760
+    #[clap(long, value_parser)]
+    log_file_max_size: Option<u64>,
+    /// The age, in seconds, log files are rotated at. Defaults to 86400
+    #[clap(long, value_parser)]
+    log_file_max_age: Option<u64>,
+    /// The number of rotated files kept of each log file. Defaults to 5
+    #[clap(long, value_parser)]
+    log_file_retention: Option<usize>,
+    /// The pool of IPv4 addresses of the cells isolating their network.
+    /// Defaults to 10.200.0.0/16
+    #[clap(long, value_parser)]
+    cell_ipv4_pool: Option<Ipv4Network>,
+    /// The pool of IPv6 addresses of the cells isolating their network.
+    /// Defaults to fd61:7572:6165::/64
+    #[clap(long, value_parser)]
+    cell_ipv6_pool: Option<Ipv6Network>,
+    /// The seccomp profile of the cell a nested auraed runs in, installed on
+    /// the processes it spawns. Repeated for the ancestors of the cell.
+    #[clap(long = "cell-seccomp-profile", value_parser)]
+    cell_seccomp_profiles: Vec<String>,
     /// Toggle verbosity. Default false
     #[clap(short, long, alias = "ritz")]
     verbose: bool,
@@ -145,6 +174,13 @@ async fn handle_default(
         socket,
         runtime_dir,
         library_dir,
+        logs_dir,
+        log_file_max_size,
+        log_file_max_age,
+        log_file_retention,
+        cell_ipv4_pool,
+        cell_ipv6_pool,
+        cell_seccomp_profiles,
         verbose,
         nested,
         subcmd: _,
@@ -158,16 +194,38 @@ async fn handle_default(
         server_key: default_server_key,
         runtime_dir: default_runtime_dir,
         library_dir: default_library_dir,
+        logs_dir: _,
+        log_file_max_size: default_log_file_max_size,
+        log_file_max_age: default_log_file_max_age,
+        log_file_retention: default_log_file_retention,
+        cell_ipv4_pool: default_cell_ipv4_pool,
+        cell_ipv6_pool: default_cell_ipv6_pool,
+        cell_seccomp_profiles: _,
     } = AuraedRuntime::default();
 
+    let runtime_dir = runtime_dir.map_or(default_runtime_dir, PathBuf::from);
+    // The logs follow the runtime directory, unless configured otherwise
+    let logs_dir =
+        logs_dir.map_or_else(|| runtime_dir.join("logs"), PathBuf::from);
+
     // Create a new runtime configuration, using provided options or defaults
     let runtime = AuraedRuntime {
         auraed: default_auraed,
         ca_crt: ca_crt.map_or(default_ca_crt, PathBuf::from),
         server_crt: server_crt.map_or(default_server_crt, PathBuf::from),
         server_key: server_key.map_or(default_server_key, PathBuf::from),
-        runtime_dir: runtime_dir.map_or(default_runtime_dir, PathBuf::from),
+        runtime_dir,
         library_dir: library_dir.map_or(default_library_dir, PathBuf::from),
+        logs_dir,
+        log_file_max_size: log_file_max_size
+            .unwrap_or(default_log_file_max_size),
+        log_file_max_age: log_file_max_age
+            .map_or(default_log_file_max_age, Duration::from_secs),
+        log_file_retention: log_file_retention
+            .unwrap_or(default_log_file_retention),
+        cell_ipv4_pool: cell_ipv4_pool.unwrap_or(default_cell_ipv4_pool),
+        cell_ipv6_pool: cell_ipv6_pool.unwrap_or(default_cell_ipv6_pool),
+        cell_seccomp_profiles,
     };
 
     // Run the auraed daemon with the configured runtime
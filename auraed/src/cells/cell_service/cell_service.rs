@@ -16,100 +16,129 @@
 use super::{
     Result,
     cells::{CellName, Cells, CellsCache},
+    cells_state,
     error::CellsServiceError,
-    executables::Executables,
+    executables::{
+        ExecOutput, ExecProcess, ExecSpec, ExecStdin, ExecTerminal,
+        ExecutableName, ExecutableSpec, Executables, ExecutablesError,
+        ProcessState, StopSpec, TerminalSize,
+    },
     validation::{
-        ValidatedCellServiceAllocateRequest, ValidatedCellServiceFreeRequest,
-        ValidatedCellServiceStartRequest, ValidatedCellServiceStopRequest,
+        ValidatedCellServiceAllocateRequest, ValidatedCellServiceExecRequest,
+        ValidatedCellServiceFreeRequest,
+        ValidatedCellServiceListExecutablesRequest,
+        ValidatedCellServicePauseRequest, ValidatedCellServiceResumeRequest,
+        ValidatedCellServiceSignalRequest, ValidatedCellServiceStartRequest,
+        ValidatedCellServiceStatsRequest, ValidatedCellServiceStopRequest,
+        ValidatedCellServiceUpdateRequest, ValidatedCellServiceWaitRequest,
+        ValidatedCellServiceWatchPressureRequest,
+        ValidatedCellServiceWatchStatsRequest, ValidatedTerminalSize,
     },
 };
-use crate::{cells::cell_service::cells::CellsError, observe::ObserveService};
-use ::validation::ValidatedType;
+use crate::{
+    AURAED_RUNTIME, AuraedRuntime, cells::cell_service::cells::CellsError,
+    logging::log_channel::LogChannelConfig, observe::ObserveService,
+};
+use ::validation::{ValidatedField, ValidatedType};
 use backoff::backoff::Backoff;
 use client::{Client, ClientError, cells::cell_service::CellServiceClient};
+use futures::stream::BoxStream;
 use proto::{
     cells::{
         Cell, CellGraphNode, CellServiceAllocateRequest,
-        CellServiceAllocateResponse, CellServiceFreeRequest,
-        CellServiceFreeResponse, CellServiceListRequest,
-        CellServiceListResponse, CellServiceStartRequest,
-        CellServiceStartResponse, CellServiceStopRequest,
-        CellServiceStopResponse, CpuController, CpusetController,
-        MemoryController, cell_service_server,
+        CellServiceAllocateResponse, CellServiceExecRequest,
+        CellServiceExecResponse, CellServiceFreeRequest,
+        CellServiceFreeResponse, CellServiceListExecutablesRequest,
+        CellServiceListExecutablesResponse, CellServiceListRequest,
+        CellServiceListResponse, CellServicePauseRequest,
+        CellServicePauseResponse, CellServiceResumeRequest,
+        CellServiceResumeResponse, CellServiceSignalRequest,
+        CellServiceSignalResponse, CellServiceStartRequest,
+        CellServiceStartResponse, CellServiceStatsRequest,
+        CellServiceStatsResponse, CellServiceStopRequest,
+        CellServiceStopResponse, CellServiceUpdateRequest,
+        CellServiceUpdateResponse, CellServiceWaitRequest,
+        CellServiceWaitResponse, CellServiceWatchPressureRequest,
+        CellServiceWatchPressureResponse, CellServiceWatchStatsRequest,
+        CellState, CellStats, CellStatsNode, CpuController, CpuStats,
+        CpusetController, ExecutableExitStatus, ExecutableState,
+        ExecutableStatus, IdMapping, IoController, IoDeviceController,
+        MemoryController, MemoryStats, Mount, MountKind, MountPropagation,
+        PidsController, PidsStats, Pressure, PressureResource, PressureStats,
+        PressureValues, RootFs, cell_service_server,
     },
     observe::LogChannelType,
 };
 use std::os::unix::fs::MetadataExt;
-use std::time::Duration;
+use std::os::unix::process::ExitStatusExt;
+use std::path::PathBuf;
+use std::time::{Duration, UNIX_EPOCH};
 use std::{process::ExitStatus, sync::Arc};
-use tokio::sync::Mutex;
-use tonic::{Code, Request, Response, Status};
+use tokio::sync::{Mutex, mpsc};
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Code, Request, Response, Status, Streaming};
 use tracing::{info, instrument, trace, warn};
 
+/// Sampling interval of [CellService::watch_stats] when the request does not set one.
+const DEFAULT_WATCH_STATS_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Size of the terminal of [CellService::exec] when the request does not set one.
+const DEFAULT_TERMINAL_SIZE: TerminalSize =
+    TerminalSize { rows: 24, columns: 80 };
+
+/// How long [CellService::exec] waits for the rest of the output of a process
+/// once it has exited.
+const EXEC_OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+type ExecResponseStream =
+    BoxStream<'static, std::result::Result<CellServiceExecResponse, Status>>;
+
 /**
  * Macro to perform an operation within a cell.
  * It retries the operation with an exponential backoff strategy in case of connection errors.
  */
 macro_rules! do_in_cell {
     ($self:ident, $cell_name:ident, $function:ident, $request:ident) => {{
-        // Retrieve the client socket for the specified cell
-        let client_socket = {
-            let mut cells = $self.cells.lock().await;
-            cells
-            .get(&$cell_name, |cell| cell.client_socket())
-            .map_err(CellsServiceError::CellsError)?
-        };
-
-        // Initialize the exponential backoff strategy for retrying the operation
-        let mut retry_strategy = backoff::ExponentialBackoffBuilder::new()
-            .with_initial_interval(Duration::from_millis(50)) // 1st retry in 50ms
-            .with_multiplier(10.0) // 10x the delay each attempt
-            .with_randomization_factor(0.5) // with a randomness of +/-50%
-            .with_max_interval(Duration::from_secs(3)) // but never delay more than 3s
-            .with_max_elapsed_time(Some(Duration::from_secs(20))) // or 20s total
-            .build();
-
-        // Attempt to create a new client with retries in case of connection errors
-        let client = loop {
-            match Client::new_no_tls(client_socket.clone()).await {
-                Ok(client) => break Ok(client),
-                e @ Err(ClientError::ConnectionError(_)) => {
-                    trace!("aurae client failed to connect: {e:?}");
-                    if let Some(delay) = retry_strategy.next_backoff() {
-                        trace!("retrying in {delay:?}");
-                        tokio::time::sleep(delay).await
-                    } else {
-                        break e
-                    }
-                }
-                e => break e
-            }
-        }.map_err(CellsServiceError::from)?;
+        let client = $self.cell_client(&$cell_name).await?;
 
         // Attempt the operation with the backoff strategy
-        backoff::future::retry(
-            retry_strategy,
-            || async {
-                match client.$function($request.clone()).await {
-                    Ok(res) => Ok(res),
-                    Err(e) if e.code() == Code::Unknown && e.message() == "transport error" => {
-                        Err(e)?;
-                        unreachable!();
-                    }
-                    Err(e) => Err(backoff::Error::Permanent(e))
+        backoff::future::retry(retry_strategy(), || async {
+            match client.$function($request.clone()).await {
+                Ok(res) => Ok(res),
+                Err(e)
+                    if e.code() == Code::Unknown
+                        && e.message() == "transport error" =>
+                {
+                    Err(e)?;
+                    unreachable!();
                 }
-            },
-        )
+                Err(e) => Err(backoff::Error::Permanent(e)),
+            }
+        })
         .await
     }};
 }
 
+/// The exponential backoff strategy used to retry operations within a cell.
+fn retry_strategy() -> backoff::ExponentialBackoff {
+    backoff::ExponentialBackoffBuilder::new()
+        .with_initial_interval(Duration::from_millis(50)) // 1st retry in 50ms
+        .with_multiplier(10.0) // 10x the delay each attempt
+        .with_randomization_factor(0.5) // with a randomness of +/-50%
+        .with_max_interval(Duration::from_secs(3)) // but never delay more than 3s
+        .with_max_elapsed_time(Some(Duration::from_secs(20))) // or 20s total
+        .build()
+}
+
 /// CellService struct manages the lifecycle of cells and executables.
 #[derive(Debug, Clone)]
 pub struct CellService {
     cells: Arc<Mutex<Cells>>,
     executables: Arc<Mutex<Executables>>,
     observe_service: ObserveService,
+    /// The file the state of the cells is persisted to, if any
+    state_file: Option<PathBuf>,
 }
 
 impl CellService {
@@ -118,10 +147,90 @@ impl CellService {
     /// # Arguments
     /// * `observe_service` - An instance of ObserveService to manage log channels.
     pub fn new(observe_service: ObserveService) -> Self {
+        // The logs of executables are only kept on disk once the runtime
+        // directory is known
+        let runtime = AURAED_RUNTIME.get();
+        let log_config = LogChannelConfig {
+            segments_dir: runtime.map(AuraedRuntime::log_segments_dir),
+            ..Default::default()
+        };
+        let log_files = runtime.map(|x| x.log_file_config(x.logs_dir.clone()));
+
         CellService {
             cells: Default::default(),
-            executables: Default::default(),
+            executables: Arc::new(Mutex::new(Executables::new(
+                log_config, log_files,
+            ))),
             observe_service,
+            state_file: None,
+        }
+    }
+
+    /// Persists the state of the cells to the file after every change, so an
+    /// auraed restarted after a crash can adopt them with
+    /// [CellService::rehydrate].
+    pub fn with_state_file(mut self, path: PathBuf) -> Self {
+        self.state_file = Some(path);
+        self
+    }
+
+    /// Adopts the cells of the state file whose nested auraed are still
+    /// running, and discards the others along with their cgroups, so they
+    /// can be allocated again.
+    pub(crate) async fn rehydrate(&self) {
+        let Some(path) = &self.state_file else {
+            return;
+        };
+
+        let mut cells = self.cells.lock().await;
+
+        match cells_state::rehydrate(path, &mut cells) {
+            Ok(adopted) => {
+                for cell_name in &adopted {
+                    info!("Rehydrated cell {cell_name}");
+                    self.observe_service
+                        .watch_cell_events(cell_name.to_string());
+                }
+
+                // A previous auraed may have crashed before persisting cells
+                cells_state::discard_orphaned_cgroups(&adopted);
+
+                // The discarded cells leave their runtime directories behind
+                if let Some(runtime) = AURAED_RUNTIME.get() {
+                    cells_state::remove_unused_runtime_dirs(
+                        &runtime.cells_dir(),
+                        &cells,
+                    );
+                }
+            }
+            Err(e) => {
+                warn!("failed to rehydrate cells from {}: {e}", path.display())
+            }
+        }
+
+        self.persist(&cells).await;
+    }
+
+    /// Writes the state of the cells to the state file, if any. It is meant
+    /// to be called while holding the cells, so the writes follow the order
+    /// of the changes.
+    async fn persist(&self, cells: &Cells) {
+        let Some(path) = self.state_file.clone() else {
+            return;
+        };
+
+        let written = match cells_state::encode(cells) {
+            Ok(state) => tokio::task::spawn_blocking({
+                let path = path.clone();
+                move || cells_state::write(&path, &state)
+            })
+            .await
+            .expect("write cells state"),
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = written {
+            warn!("failed to persist cells to {}: {e}", path.display());
         }
     }
 
@@ -148,10 +257,17 @@ impl CellService {
 
         let cell = cells.allocate(cell_name, cell_spec)?;
 
-        Ok(CellServiceAllocateResponse {
+        // Surface the cgroup events (e.g., OOM kills) of the cell
+        self.observe_service.watch_cell_events(cell.name().to_string());
+
+        let response = CellServiceAllocateResponse {
             cell_name: cell.name().clone().to_string(),
             cgroup_v2: cell.v2().expect("allocated cell returns `Some`"),
-        })
+        };
+
+        self.persist(&cells).await;
+
+        Ok(response)
     }
 
     /// Frees a cell.
@@ -170,13 +286,99 @@ impl CellService {
 
         info!("CellService: free() cell_name={cell_name:?}");
 
-        let mut cells = self.cells.lock().await;
+        // The cell is freed once removed, as freeing waits for its nested
+        // auraed to exit, which must not hold up the other cells
+        let mut cell = {
+            let mut cells = self.cells.lock().await;
+            // The cell is gone from the cache if its cgroup is
+            let cell = cells.remove(&cell_name);
+            self.persist(&cells).await;
+            cell?
+        };
 
-        cells.free(&cell_name)?;
+        // A cell failing to free is killed once dropped
+        tokio::task::spawn_blocking(move || cell.free())
+            .await
+            .expect("free cell")?;
 
         Ok(CellServiceFreeResponse::default())
     }
 
+    /// Updates the cgroup controllers of an existing cell.
+    ///
+    /// # Arguments
+    /// * `request` - A validated request to update a cell.
+    ///
+    /// # Returns
+    /// A result containing the CellServiceUpdateResponse or an error.
+    #[tracing::instrument(skip(self))]
+    async fn update(
+        &self,
+        request: ValidatedCellServiceUpdateRequest,
+    ) -> Result<CellServiceUpdateResponse> {
+        let ValidatedCellServiceUpdateRequest { cell } = request;
+
+        let cell_name = cell.name.clone();
+        let cell_spec = cell.into();
+
+        info!("CellService: update() cell_name={cell_name:?}");
+
+        let mut cells = self.cells.lock().await;
+
+        cells.update(&cell_name, cell_spec)?;
+        self.persist(&cells).await;
+
+        Ok(CellServiceUpdateResponse::default())
+    }
+
+    /// Freezes all processes of a cell, including its nested cells.
+    ///
+    /// # Arguments
+    /// * `request` - A validated request to pause a cell.
+    ///
+    /// # Returns
+    /// A result containing the CellServicePauseResponse or an error.
+    #[tracing::instrument(skip(self))]
+    async fn pause(
+        &self,
+        request: ValidatedCellServicePauseRequest,
+    ) -> Result<CellServicePauseResponse> {
+        let ValidatedCellServicePauseRequest { cell_name } = request;
+
+        info!("CellService: pause() cell_name={cell_name:?}");
+
+        let mut cells = self.cells.lock().await;
+
+        cells.pause(&cell_name)?;
+        self.persist(&cells).await;
+
+        Ok(CellServicePauseResponse::default())
+    }
+
+    /// Thaws all processes of a paused cell.
+    ///
+    /// # Arguments
+    /// * `request` - A validated request to resume a cell.
+    ///
+    /// # Returns
+    /// A result containing the CellServiceResumeResponse or an error.
+    #[tracing::instrument(skip(self))]
+    async fn resume(
+        &self,
+        request: ValidatedCellServiceResumeRequest,
+    ) -> Result<CellServiceResumeResponse> {
+        let ValidatedCellServiceResumeRequest { cell_name } = request;
+
+        info!("CellService: resume() cell_name={cell_name:?}");
+
+        let mut cells = self.cells.lock().await;
+
+        cells.resume(&cell_name)?;
+        self.persist(&cells).await;
+
+        Ok(CellServiceResumeResponse::default())
+    }
+
     #[tracing::instrument(skip(self))]
     pub(crate) async fn free_all(&self) -> Result<()> {
         let mut cells = self.cells.lock().await;
@@ -188,6 +390,8 @@ impl CellService {
         // Forcefully kill any remaining cells that failed to shut down
         cells.broadcast_kill();
 
+        self.persist(&cells).await;
+
         Ok(())
     }
 
@@ -221,11 +425,7 @@ impl CellService {
             .map_err(CellsServiceError::ExecutablesError)?;
 
         // Retrieve the process ID (PID) of the started executable
-        let pid = executable
-            .pid()
-            .map_err(CellsServiceError::Io)?
-            .expect("pid")
-            .as_raw();
+        let pid = executable.initial_pid().expect("pid").as_raw();
 
         // Register the stdout log channel for the executable's PID
         if let Err(e) = self
@@ -263,6 +463,32 @@ impl CellService {
         }))
     }
 
+    /// Creates a client of the nested auraed of a cell, retrying in case of
+    /// connection errors (e.g., the nested auraed is still starting).
+    async fn cell_client(&self, cell_name: &CellName) -> Result<Client> {
+        // Retrieve the client socket for the specified cell
+        let client_socket = {
+            let mut cells = self.cells.lock().await;
+            cells.client_socket(cell_name)?
+        };
+
+        let mut retry_strategy = retry_strategy();
+        loop {
+            match Client::new_no_tls(client_socket.clone()).await {
+                Ok(client) => return Ok(client),
+                Err(e @ ClientError::ConnectionError(_)) => {
+                    trace!("aurae client failed to connect: {e:?}");
+                    let Some(delay) = retry_strategy.next_backoff() else {
+                        return Err(e.into());
+                    };
+                    trace!("retrying in {delay:?}");
+                    tokio::time::sleep(delay).await
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
     #[tracing::instrument(skip(self))]
     async fn start_in_cell(
         &self,
@@ -284,33 +510,42 @@ impl CellService {
         &self,
         request: ValidatedCellServiceStopRequest,
     ) -> std::result::Result<Response<CellServiceStopResponse>, Status> {
-        let ValidatedCellServiceStopRequest { cell_name, executable_name } =
-            request;
+        let ValidatedCellServiceStopRequest {
+            cell_name,
+            executable_name,
+            signal,
+            grace_period_ms,
+        } = request;
 
         assert!(cell_name.is_none());
         info!("CellService: stop() executable_name={:?}", executable_name,);
 
-        let pid = {
+        let (pid, stopped) = {
             let mut executables = self.executables.lock().await;
 
-            // Retrieve the process ID (PID) of the executable to be stopped
+            // Retrieve the PID the executable's logs are registered with
             let pid = executables
                 .get(&executable_name)
                 .map_err(CellsServiceError::ExecutablesError)?
-                .pid()
-                .map_err(CellsServiceError::Io)?
+                .initial_pid()
                 .expect("pid")
                 .as_raw();
 
-            // Stop the executable and handle any errors
-            let _: ExitStatus = executables
-                .stop(&executable_name)
-                .await
+            let stopped = executables
+                .stop(
+                    &executable_name,
+                    StopSpec { signal, grace_period: grace_period_ms },
+                )
                 .map_err(CellsServiceError::ExecutablesError)?;
 
-            pid
+            (pid, stopped)
         };
 
+        // Do not hold the lock during the grace period, so the other
+        // executables can be used meanwhile
+        let _: ExitStatus =
+            stopped.await.map_err(CellsServiceError::ExecutablesError)?;
+
         // Remove the executable's logs from the observe service.
         if let Err(e) = self
             .observe_service
@@ -339,73 +574,772 @@ impl CellService {
         do_in_cell!(self, cell_name, stop, request)
     }
 
-    #[tracing::instrument(skip(self))]
-    pub(crate) async fn stop_all(&self) -> Result<()> {
-        let mut executables = self.executables.lock().await;
-        // Broadcast a stop signal to all executables
-        executables.broadcast_stop().await;
-        Ok(())
+    #[tracing::instrument(skip(self))]
+    async fn signal(
+        &self,
+        request: ValidatedCellServiceSignalRequest,
+    ) -> Result<CellServiceSignalResponse> {
+        let ValidatedCellServiceSignalRequest {
+            cell_name,
+            executable_name,
+            signal,
+        } = request;
+
+        assert!(cell_name.is_none());
+        info!(
+            "CellService: signal() executable_name={:?} signal={signal}",
+            executable_name
+        );
+
+        let executables = self.executables.lock().await;
+        executables.signal(&executable_name, signal)?;
+
+        Ok(CellServiceSignalResponse::default())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn signal_in_cell(
+        &self,
+        cell_name: &CellName,
+        request: CellServiceSignalRequest,
+    ) -> std::result::Result<Response<CellServiceSignalResponse>, Status> {
+        do_in_cell!(self, cell_name, signal, request)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_executables(
+        &self,
+        request: ValidatedCellServiceListExecutablesRequest,
+    ) -> Result<CellServiceListExecutablesResponse> {
+        let ValidatedCellServiceListExecutablesRequest { cell_name } = request;
+
+        assert!(cell_name.is_none());
+
+        let executables = self.executables.lock().await;
+        let executables = executables.iter().map(|x| x.into()).collect();
+
+        Ok(CellServiceListExecutablesResponse { executables })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_executables_in_cell(
+        &self,
+        cell_name: &CellName,
+        request: CellServiceListExecutablesRequest,
+    ) -> std::result::Result<Response<CellServiceListExecutablesResponse>, Status>
+    {
+        do_in_cell!(self, cell_name, list_executables, request)
+    }
+
+    /// Waits until the executable has exited for good, either because it
+    /// is not restarted, or because it has been stopped.
+    #[tracing::instrument(skip(self))]
+    async fn wait(
+        &self,
+        request: ValidatedCellServiceWaitRequest,
+    ) -> Result<CellServiceWaitResponse> {
+        let ValidatedCellServiceWaitRequest { cell_name, executable_name } =
+            request;
+
+        assert!(cell_name.is_none());
+        info!("CellService: wait() executable_name={:?}", executable_name);
+
+        // Do not hold the lock while waiting, so the executable can be stopped
+        let (mut status, description) = {
+            let executables = self.executables.lock().await;
+            let executable = executables.get(&executable_name)?;
+            (executable.watch_status(), executable.description.clone())
+        };
+
+        // The supervisor always reports the exit before it drops the sender
+        let exited = status
+            .wait_for(|status| status.state == ProcessState::Exited)
+            .await
+            .map(|status| *status);
+        let status = exited.unwrap_or_else(|_| *status.borrow());
+
+        Ok(CellServiceWaitResponse {
+            status: Some(executable_status(
+                &executable_name,
+                &description,
+                status,
+            )),
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn wait_in_cell(
+        &self,
+        cell_name: &CellName,
+        request: CellServiceWaitRequest,
+    ) -> std::result::Result<Response<CellServiceWaitResponse>, Status> {
+        do_in_cell!(self, cell_name, wait, request)
+    }
+
+    /// Spawns a process attached to the stream of `requests`, and streams its
+    /// output back until it exits. The process is killed if the client goes
+    /// away before then.
+    #[tracing::instrument(skip(self, requests))]
+    async fn exec(
+        &self,
+        request: ValidatedCellServiceExecRequest,
+        requests: Streaming<CellServiceExecRequest>,
+    ) -> Result<ExecResponseStream> {
+        let ValidatedCellServiceExecRequest {
+            cell_name,
+            executable,
+            uid,
+            gid,
+            tty,
+            interactive,
+            stdin,
+            close_stdin,
+            terminal_size,
+        } = request;
+
+        assert!(cell_name.is_none());
+        info!("CellService: exec() executable={:?}", executable);
+
+        let ExecutableSpec {
+            name,
+            mut command,
+            seccomp_profile,
+            capabilities,
+            rlimits,
+            ..
+        } = executable.into();
+        let spec = ExecSpec {
+            // A client that does not know the size of its terminal may send
+            // an empty one
+            tty: tty.then(|| {
+                terminal_size
+                    .filter(|x| x.rows > 0 && x.columns > 0)
+                    .map(Into::into)
+                    .unwrap_or(DEFAULT_TERMINAL_SIZE)
+            }),
+            interactive,
+            seccomp_profile,
+            capabilities,
+            rlimits,
+        };
+
+        let (mut process, mut output) =
+            ExecProcess::spawn(&mut command, spec, uid, gid).map_err(|e| {
+                ExecutablesError::FailedToStartExecutable {
+                    executable_name: name,
+                    source: e,
+                }
+            })?;
+        let pid = process.child.id().map(|id| id as i32).unwrap_or_default();
+
+        // Forward the input on its own, so a process that does not read its
+        // stdin cannot hold back its output
+        let input = tokio::spawn(forward_exec_input(
+            requests,
+            process.stdin.take(),
+            process.terminal.take(),
+            (stdin, close_stdin),
+        ));
+
+        let (tx, rx) = mpsc::channel::<
+            std::result::Result<CellServiceExecResponse, Status>,
+        >(4);
+
+        let _ignored = tokio::spawn(async move {
+            let started = CellServiceExecResponse { pid, ..Default::default() };
+            if tx.send(Ok(started)).await.is_err() {
+                return;
+            }
+
+            let mut output_done = false;
+            let exit_status = loop {
+                tokio::select! {
+                    exit_status = process.child.wait() => break exit_status,
+                    res = output.recv(), if !output_done => match res {
+                        Some(res) => {
+                            if tx.send(Ok(res.into())).await.is_err() {
+                                return;
+                            }
+                        }
+                        None => output_done = true,
+                    },
+                    // the client is gone, the process is killed when dropped
+                    _ = tx.closed() => return,
+                }
+            };
+            input.abort();
+
+            // Send the output that is left before the exit status. Processes
+            // left behind may keep the output open, so don't wait for them.
+            let drain = async {
+                while let Some(res) = output.recv().await {
+                    if tx.send(Ok(res.into())).await.is_err() {
+                        break;
+                    }
+                }
+            };
+            let _ =
+                tokio::time::timeout(EXEC_OUTPUT_DRAIN_TIMEOUT, drain).await;
+
+            let res = exit_status
+                .map(|exit_status| CellServiceExecResponse {
+                    exit_status: Some(ExecutableExitStatus {
+                        code: exit_status.code(),
+                        signal: exit_status.signal(),
+                    }),
+                    ..Default::default()
+                })
+                .map_err(|e| CellsServiceError::Io(e).into());
+            let _ = tx.send(res).await;
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    #[tracing::instrument(skip(self, requests))]
+    async fn exec_in_cell(
+        &self,
+        cell_name: &CellName,
+        request: CellServiceExecRequest,
+        requests: Streaming<CellServiceExecRequest>,
+    ) -> std::result::Result<Response<ExecResponseStream>, Status> {
+        let client = self.cell_client(cell_name).await?;
+
+        // Unlike do_in_cell!, the requests cannot be replayed to retry
+        let requests = tokio_stream::once(request)
+            .chain(requests.map_while(std::result::Result::ok));
+        let response = client.exec(Box::pin(requests)).await?;
+
+        let stream: ExecResponseStream = Box::pin(response.into_inner());
+        Ok(Response::new(stream))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub(crate) async fn stop_all(&self) -> Result<()> {
+        let mut executables = self.executables.lock().await;
+        // Broadcast a stop signal to all executables
+        executables.broadcast_stop(StopSpec::default()).await;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list(&self) -> Result<CellServiceListResponse> {
+        let mut cells: Vec<CellGraphNode> = {
+            let cells = self.cells.lock().await;
+
+            // Retrieve all cells and convert them for returning
+            cells
+                .get_all(|x| x.try_into())
+                .expect("cells doesn't error")
+                .into_iter()
+                .filter_map(|x| x.ok())
+                .collect()
+        };
+
+        // The executables of a cell are managed by its nested auraed,
+        // which can not serve requests while the cell is paused
+        let mut nodes: Vec<&mut CellGraphNode> = cells.iter_mut().collect();
+        while let Some(node) = nodes.pop() {
+            if let Some(cell) = &node.cell
+                && node.state != CellState::Paused as i32
+            {
+                let cell_name =
+                    CellName::validate(Some(cell.name.clone()), "name", None)
+                        .expect("names of allocated cells are valid");
+                match self.list_in_cell(&cell_name).await {
+                    Ok(res) => node.executables = res.into_inner().executables,
+                    Err(e) => warn!(
+                        "failed to list executables of cell {cell_name}: {e}"
+                    ),
+                }
+            }
+
+            nodes.extend(node.children.iter_mut());
+        }
+
+        let executables =
+            self.executables.lock().await.iter().map(|x| x.into()).collect();
+
+        Ok(CellServiceListResponse { cells, executables })
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn list_in_cell(
+        &self,
+        cell_name: &CellName,
+    ) -> std::result::Result<Response<CellServiceListResponse>, Status> {
+        let request = CellServiceListRequest {};
+        do_in_cell!(self, cell_name, list, request)
+    }
+
+    #[tracing::instrument(skip(self))]
+    async fn stats(
+        &self,
+        request: ValidatedCellServiceStatsRequest,
+    ) -> Result<CellServiceStatsResponse> {
+        let ValidatedCellServiceStatsRequest { cell_name } = request;
+
+        let mut cells = self.cells.lock().await;
+
+        // Read the stats of the cell and all of its nested cells
+        let stats = cells.get(&cell_name, |cell| cell.try_into())?;
+
+        Ok(CellServiceStatsResponse { stats: Some(stats) })
+    }
+
+    /// Samples the stats of a cell at the requested interval until the
+    /// receiver is dropped or the stats can no longer be read (e.g., the
+    /// cell has been freed).
+    #[tracing::instrument(skip(self))]
+    fn watch_stats(
+        &self,
+        request: ValidatedCellServiceWatchStatsRequest,
+    ) -> ReceiverStream<std::result::Result<CellServiceStatsResponse, Status>>
+    {
+        let ValidatedCellServiceWatchStatsRequest { cell_name, interval_ms } =
+            request;
+
+        let period = interval_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_WATCH_STATS_INTERVAL);
+
+        let (tx, rx) = mpsc::channel::<
+            std::result::Result<CellServiceStatsResponse, Status>,
+        >(4);
+
+        let service = self.clone();
+        let _ignored = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                let _instant = interval.tick().await;
+
+                let res = service
+                    .stats(ValidatedCellServiceStatsRequest {
+                        cell_name: cell_name.clone(),
+                    })
+                    .await
+                    .map_err(Status::from);
+
+                let failed = res.is_err();
+                if tx.send(res).await.is_err() || failed {
+                    // receiver is gone, or there is nothing left to watch
+                    break;
+                }
+            }
+        });
+
+        ReceiverStream::new(rx)
+    }
+
+    /// Registers a PSI trigger with a cell, and streams the pressure of the
+    /// resource every time the threshold is crossed, until the receiver is
+    /// dropped or the cell has been freed.
+    #[tracing::instrument(skip(self))]
+    async fn watch_pressure(
+        &self,
+        request: ValidatedCellServiceWatchPressureRequest,
+    ) -> Result<
+        ReceiverStream<
+            std::result::Result<CellServiceWatchPressureResponse, Status>,
+        >,
+    > {
+        let ValidatedCellServiceWatchPressureRequest {
+            cell_name,
+            resource,
+            kind,
+            stall_ms,
+            window_ms,
+        } = request;
+
+        let trigger = super::cells::cgroups::pressure::PressureTrigger {
+            resource,
+            kind,
+            stall: Duration::from_millis(stall_ms),
+            window: Duration::from_millis(window_ms),
+        };
+
+        // Register the trigger now, so the client gets an error if it fails
+        let watch = {
+            let mut cells = self.cells.lock().await;
+            cells.get(&cell_name, |cell| cell.watch_pressure(&trigger))?
+        };
+
+        let (tx, rx) = mpsc::channel::<
+            std::result::Result<CellServiceWatchPressureResponse, Status>,
+        >(4);
+
+        let service = self.clone();
+        let _ignored = tokio::spawn(async move {
+            loop {
+                let crossed = tokio::select! {
+                    crossed = watch.crossed() => crossed,
+                    _ = tx.closed() => break,
+                };
+
+                let res = match crossed {
+                    // the cgroup is gone, there is nothing left to watch
+                    Ok(false) => break,
+                    Ok(true) => {
+                        let mut cells = service.cells.lock().await;
+                        cells
+                            .get(&cell_name, |cell| cell.pressure(resource))
+                            .map(|pressure| CellServiceWatchPressureResponse {
+                                cell_name: cell_name.to_string(),
+                                resource: PressureResource::from(resource)
+                                    as i32,
+                                pressure: pressure.as_ref().map(|x| x.into()),
+                            })
+                            .map_err(|e| CellsServiceError::from(e).into())
+                    }
+                    Err(e) => Err(CellsServiceError::Io(e).into()),
+                };
+
+                let failed = res.is_err();
+                if tx.send(res).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(ReceiverStream::new(rx))
+    }
+}
+
+/// Writes the stdin of the requests to an exec'd process, and resizes its
+/// terminal, until the client is done writing.
+async fn forward_exec_input(
+    mut requests: Streaming<CellServiceExecRequest>,
+    mut stdin: Option<ExecStdin>,
+    terminal: Option<ExecTerminal>,
+    first: (Vec<u8>, bool),
+) {
+    let (mut bytes, mut close_stdin) = first;
+    loop {
+        if let Some(writer) = &mut stdin
+            && !bytes.is_empty()
+            && let Err(e) = writer.write(&bytes).await
+        {
+            trace!("failed to write to stdin: {e}");
+        }
+
+        if close_stdin
+            && let Some(writer) = stdin.take()
+            && let Err(e) = writer.close().await
+        {
+            trace!("failed to close stdin: {e}");
+        }
+
+        let request = match requests.message().await {
+            Ok(Some(request)) => request,
+            // the client is done writing, but may still read the output
+            Ok(None) | Err(_) => break,
+        };
+
+        if let Some(terminal_size) = request.terminal_size {
+            match ValidatedTerminalSize::validate(
+                terminal_size,
+                Some("terminal_size"),
+            ) {
+                Ok(size) => {
+                    if let Some(terminal) = &terminal
+                        && let Err(e) = terminal.resize(size.into())
+                    {
+                        warn!("failed to resize terminal: {e}");
+                    }
+                }
+                Err(e) => warn!("ignoring terminal size: {e}"),
+            }
+        }
+
+        bytes = request.stdin;
+        close_stdin = request.close_stdin;
+    }
+}
+
+impl From<ExecOutput> for CellServiceExecResponse {
+    fn from(value: ExecOutput) -> Self {
+        match value {
+            ExecOutput::Stdout(stdout) => Self { stdout, ..Default::default() },
+            ExecOutput::Stderr(stderr) => Self { stderr, ..Default::default() },
+        }
+    }
+}
+
+impl From<&super::executables::Executable> for ExecutableStatus {
+    fn from(value: &super::executables::Executable) -> Self {
+        executable_status(&value.name, &value.description, value.status())
+    }
+}
+
+/// Converts the status of an executable, which may no longer be cached once
+/// it has been stopped.
+fn executable_status(
+    name: &ExecutableName,
+    description: &str,
+    status: super::executables::ExecutableStatus,
+) -> ExecutableStatus {
+    let super::executables::ExecutableStatus {
+        state,
+        pid,
+        started_at,
+        restart_count,
+        last_exit_status,
+    } = status;
+
+    ExecutableStatus {
+        name: name.to_string(),
+        pid: pid.map(|pid| pid.as_raw()).unwrap_or_default(),
+        restart_count,
+        last_exit_status: last_exit_status.map(|status| ExecutableExitStatus {
+            code: status.code(),
+            signal: status.signal(),
+        }),
+        description: description.to_string(),
+        state: ExecutableState::from(state) as i32,
+        start_time_ms: started_at
+            .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+            .map(|x| x.as_millis() as i64)
+            .unwrap_or_default(),
+    }
+}
+
+impl From<ProcessState> for ExecutableState {
+    fn from(value: ProcessState) -> Self {
+        match value {
+            ProcessState::NotStarted => ExecutableState::Unspecified,
+            ProcessState::Running => ExecutableState::Running,
+            ProcessState::Restarting => ExecutableState::Restarting,
+            ProcessState::Exited => ExecutableState::Exited,
+        }
+    }
+}
+
+impl TryFrom<&super::cells::Cell> for CellGraphNode {
+    type Error = CellsError;
+
+    /// Converts a Cell into a CellGraphNode.
+    ///
+    /// # Arguments
+    /// * `value` - A reference to the Cell.
+    ///
+    /// # Returns
+    /// A result containing the CellGraphNode or an error.
+    fn try_from(
+        value: &super::cells::Cell,
+    ) -> std::result::Result<Self, Self::Error> {
+        // Retrieve and convert all child cells
+        let children = CellsCache::get_all(value, |x| x.try_into())?
+            .into_iter()
+            .filter_map(|x| x.ok())
+            .collect();
+
+        Ok(Self {
+            cell: Some(value.into()),
+            children,
+            state: if value.is_paused() {
+                CellState::Paused
+            } else {
+                CellState::Allocated
+            } as i32,
+            // Filled in by CellService::list, as it requires a request to
+            // the nested auraed of the cell
+            executables: vec![],
+            ip_addresses: value
+                .addresses()
+                .iter()
+                .map(|x| x.to_string())
+                .collect(),
+        })
+    }
+}
+
+impl From<&super::cells::Cell> for Cell {
+    /// Converts the name and specification of a Cell into a Cell message.
+    fn from(value: &super::cells::Cell) -> Self {
+        // Extract cgroup and isolation specifications
+        let super::cells::CellSpec { cgroup_spec, iso_ctl } = value.spec();
+        // Extract CPU, cpuset, and memory specifications
+        let super::cells::cgroups::CgroupSpec { cpu, cpuset, memory, io, pids } =
+            cgroup_spec;
+
+        Self {
+            name: value.name().to_string(),
+            cpu: cpu.as_ref().map(|x| x.into()),
+            cpuset: cpuset.as_ref().map(|x| x.into()),
+            memory: memory.as_ref().map(|x| x.into()),
+            io: io.as_ref().map(|x| x.into()),
+            pids: pids.as_ref().map(|x| x.into()),
+            isolate_process: iso_ctl.isolate_process,
+            isolate_network: iso_ctl.isolate_network,
+            isolate_user: iso_ctl.isolate_user,
+            uid_mappings: iso_ctl
+                .uid_mappings
+                .iter()
+                .map(|x| x.into())
+                .collect(),
+            gid_mappings: iso_ctl
+                .gid_mappings
+                .iter()
+                .map(|x| x.into())
+                .collect(),
+            rootfs: iso_ctl.rootfs.as_ref().map(|x| x.into()),
+            mounts: iso_ctl.mounts.iter().map(|x| x.into()).collect(),
+            seccomp_profile: iso_ctl
+                .seccomp_profile
+                .as_ref()
+                .map(|x| x.as_str().to_string()),
+        }
     }
+}
 
-    #[tracing::instrument(skip(self))]
-    async fn list(&self) -> Result<CellServiceListResponse> {
-        let cells = self.cells.lock().await;
+impl From<&super::cells::Mount> for Mount {
+    fn from(value: &super::cells::Mount) -> Self {
+        use super::cells::MountPropagation as Propagation;
 
-        // Retrieve all cells and convert them for returning
-        let cells = cells
-            .get_all(|x| x.try_into())
-            .expect("cells doesn't error")
-            .into_iter()
-            .filter_map(|x| x.ok())
-            .collect();
+        let super::cells::Mount { target, kind, read_only, propagation } =
+            value;
+
+        let (kind, source, size_bytes) = match kind {
+            super::cells::MountKind::Bind { source } => {
+                (MountKind::Bind, source.to_string_lossy().to_string(), 0)
+            }
+            super::cells::MountKind::Tmpfs { size } => {
+                (MountKind::Tmpfs, String::new(), size.unwrap_or(0))
+            }
+        };
+
+        let propagation = match propagation {
+            Propagation::Private => MountPropagation::Private,
+            Propagation::RPrivate => MountPropagation::Rprivate,
+            Propagation::Slave => MountPropagation::Slave,
+            Propagation::RSlave => MountPropagation::Rslave,
+            Propagation::Shared => MountPropagation::Shared,
+            Propagation::RShared => MountPropagation::Rshared,
+        };
 
-        Ok(CellServiceListResponse { cells })
+        Self {
+            target: target.to_string_lossy().to_string(),
+            kind: kind as i32,
+            source,
+            read_only: *read_only,
+            propagation: propagation as i32,
+            size_bytes,
+        }
     }
 }
 
-impl TryFrom<&super::cells::Cell> for CellGraphNode {
+impl From<&super::cells::RootFs> for RootFs {
+    fn from(value: &super::cells::RootFs) -> Self {
+        let super::cells::RootFs { path, upper_dir } = value;
+        Self {
+            path: path.to_string_lossy().to_string(),
+            upper_dir: upper_dir
+                .as_ref()
+                .map(|x| x.to_string_lossy().to_string()),
+        }
+    }
+}
+
+impl From<&super::cells::IdMapping> for IdMapping {
+    fn from(value: &super::cells::IdMapping) -> Self {
+        let super::cells::IdMapping { container_id, host_id, size } = *value;
+        Self { container_id, host_id, size }
+    }
+}
+
+impl TryFrom<&super::cells::Cell> for CellStatsNode {
     type Error = CellsError;
 
-    /// Converts a Cell into a CellGraphNode.
+    /// Reads the stats of a Cell, and recursively of its children, into a CellStatsNode.
     ///
     /// # Arguments
     /// * `value` - A reference to the Cell.
     ///
     /// # Returns
-    /// A result containing the CellGraphNode or an error.
+    /// A result containing the CellStatsNode or an error.
     fn try_from(
         value: &super::cells::Cell,
     ) -> std::result::Result<Self, Self::Error> {
-        // Extract the name and specification of the cell
-        let name = value.name();
-        let spec = value.spec();
-        // Retrieve and convert all child cells
+        use super::cells::cgroups::pressure::PressureResource as Resource;
+
+        let libcgroups::stats::Stats { cpu, memory, pids, .. } =
+            value.stats()?;
+
+        // Retrieve the stats of all child cells
         let children = CellsCache::get_all(value, |x| x.try_into())?
             .into_iter()
             .filter_map(|x| x.ok())
             .collect();
 
-        // Extract cgroup and isolation specifications
-        let super::cells::CellSpec { cgroup_spec, iso_ctl } = spec;
-        // Extract CPU, cpuset, and memory specifications
-        let super::cells::cgroups::CgroupSpec { cpu, cpuset, memory } =
-            cgroup_spec;
-
         Ok(Self {
-            // Create a new Cell instance with the extracted specifications
-            cell: Some(Cell {
-                name: name.to_string(),
-                cpu: cpu.as_ref().map(|x| x.into()),
-                cpuset: cpuset.as_ref().map(|x| x.into()),
-                memory: memory.as_ref().map(|x| x.into()),
-                isolate_process: iso_ctl.isolate_process,
-                isolate_network: iso_ctl.isolate_network,
+            cell_name: value.name().to_string(),
+            stats: Some(CellStats {
+                cpu: Some(CpuStats {
+                    usage_usec: cpu.usage.usage_total,
+                    user_usec: cpu.usage.usage_user,
+                    system_usec: cpu.usage.usage_kernel,
+                    nr_periods: cpu.throttling.periods,
+                    nr_throttled: cpu.throttling.throttled_periods,
+                    throttled_usec: cpu.throttling.throttled_time,
+                }),
+                memory: Some(MemoryStats {
+                    current: memory.memory.usage,
+                    peak: memory.memory.max_usage,
+                }),
+                pids: Some(PidsStats { current: pids.current }),
+                pressure: Some(PressureStats {
+                    cpu: read_pressure(value, Resource::Cpu)?,
+                    memory: read_pressure(value, Resource::Memory)?,
+                    io: read_pressure(value, Resource::Io)?,
+                }),
             }),
             children,
         })
     }
 }
 
+fn read_pressure(
+    cell: &super::cells::Cell,
+    resource: super::cells::cgroups::pressure::PressureResource,
+) -> std::result::Result<Option<Pressure>, CellsError> {
+    Ok(cell.pressure(resource)?.as_ref().map(|x| x.into()))
+}
+
+impl From<&super::cells::cgroups::pressure::Pressure> for Pressure {
+    fn from(value: &super::cells::cgroups::pressure::Pressure) -> Self {
+        let super::cells::cgroups::pressure::Pressure { some, full } = value;
+
+        Self { some: Some(some.into()), full: full.as_ref().map(|x| x.into()) }
+    }
+}
+
+impl From<&super::cells::cgroups::pressure::PressureValues> for PressureValues {
+    fn from(value: &super::cells::cgroups::pressure::PressureValues) -> Self {
+        let super::cells::cgroups::pressure::PressureValues {
+            avg10,
+            avg60,
+            avg300,
+            total,
+        } = *value;
+
+        Self { avg10, avg60, avg300, total }
+    }
+}
+
+impl From<super::cells::cgroups::pressure::PressureResource>
+    for PressureResource
+{
+    fn from(value: super::cells::cgroups::pressure::PressureResource) -> Self {
+        match value {
+            super::cells::cgroups::pressure::PressureResource::Cpu => Self::Cpu,
+            super::cells::cgroups::pressure::PressureResource::Memory => {
+                Self::Memory
+            }
+            super::cells::cgroups::pressure::PressureResource::Io => Self::Io,
+        }
+    }
+}
+
 impl From<&super::cells::cgroups::CpuController> for CpuController {
     fn from(value: &super::cells::cgroups::CpuController) -> Self {
         let super::cells::cgroups::CpuController { weight, max, period } =
@@ -437,14 +1371,61 @@ impl From<&super::cells::cgroups::memory::MemoryController>
     for MemoryController
 {
     fn from(value: &super::cells::cgroups::MemoryController) -> Self {
-        let super::cells::cgroups::MemoryController { min, low, high, max } =
-            value.clone();
+        let super::cells::cgroups::MemoryController {
+            min,
+            low,
+            high,
+            max,
+            swap_max,
+            swap_high,
+        } = value.clone();
 
         Self {
             min: min.map(|x| x.into_inner()),
             low: low.map(|x| x.into_inner()),
             high: high.map(|x| x.into_inner()),
             max: max.map(|x| x.into_inner()),
+            swap_max: swap_max.map(|x| x.into_inner()),
+            swap_high: swap_high.map(|x| x.into_inner()),
+        }
+    }
+}
+
+impl From<&super::cells::cgroups::PidsController> for PidsController {
+    fn from(value: &super::cells::cgroups::PidsController) -> Self {
+        let super::cells::cgroups::PidsController { max } = value.clone();
+
+        Self { max: max.map(|x| x.into_inner()) }
+    }
+}
+
+impl From<&super::cells::cgroups::IoController> for IoController {
+    fn from(value: &super::cells::cgroups::IoController) -> Self {
+        let super::cells::cgroups::IoController { devices } = value.clone();
+
+        Self {
+            devices: devices
+                .into_iter()
+                .map(|x| {
+                    let super::cells::cgroups::IoDeviceController {
+                        device,
+                        weight,
+                        rbps,
+                        wbps,
+                        riops,
+                        wiops,
+                    } = x;
+
+                    IoDeviceController {
+                        device: device.to_string(),
+                        weight: weight.map(u64::from),
+                        rbps,
+                        wbps,
+                        riops,
+                        wiops,
+                    }
+                })
+                .collect(),
         }
     }
 }
@@ -491,6 +1472,20 @@ impl cell_service_server::CellService for CellService {
         Ok(Response::new(self.free(request).await?))
     }
 
+    #[instrument(skip(self))]
+    async fn update(
+        &self,
+        request: Request<CellServiceUpdateRequest>,
+    ) -> std::result::Result<Response<CellServiceUpdateResponse>, Status> {
+        let request = request.into_inner();
+        // Validate the update request
+        let request =
+            ValidatedCellServiceUpdateRequest::validate(request, None)?;
+
+        // update the cell
+        Ok(Response::new(self.update(request).await?))
+    }
+
     #[instrument(skip(self))]
     async fn start(
         &self,
@@ -549,6 +1544,78 @@ impl cell_service_server::CellService for CellService {
         }
     }
 
+    #[instrument(skip(self))]
+    async fn signal(
+        &self,
+        request: Request<CellServiceSignalRequest>,
+    ) -> std::result::Result<Response<CellServiceSignalResponse>, Status> {
+        let request = request.into_inner();
+
+        // Validate the signal request
+        let validated =
+            ValidatedCellServiceSignalRequest::validate(request.clone(), None)?;
+
+        match validated.cell_name {
+            None => Ok(Response::new(self.signal(validated).await?)),
+            Some(cell_name) => {
+                let mut request = request;
+                request.cell_name = None;
+
+                // signal the executable in the cell
+                self.signal_in_cell(&cell_name, request).await
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_executables(
+        &self,
+        request: Request<CellServiceListExecutablesRequest>,
+    ) -> std::result::Result<Response<CellServiceListExecutablesResponse>, Status>
+    {
+        let request = request.into_inner();
+
+        // Validate the list executables request
+        let validated = ValidatedCellServiceListExecutablesRequest::validate(
+            request.clone(),
+            None,
+        )?;
+
+        match validated.cell_name {
+            None => Ok(Response::new(self.list_executables(validated).await?)),
+            Some(cell_name) => {
+                let mut request = request;
+                request.cell_name = None;
+
+                // list the executables in the cell
+                self.list_executables_in_cell(&cell_name, request).await
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn wait(
+        &self,
+        request: Request<CellServiceWaitRequest>,
+    ) -> std::result::Result<Response<CellServiceWaitResponse>, Status> {
+        let request = request.into_inner();
+
+        // Validate the wait request
+        let validated =
+            ValidatedCellServiceWaitRequest::validate(request.clone(), None)?;
+
+        match validated.cell_name {
+            None => Ok(Response::new(self.wait(validated).await?)),
+            Some(cell_name) => {
+                let mut request = request;
+                request.cell_name = None;
+
+                // wait for the executable in the cell
+                self.wait_in_cell(&cell_name, request).await
+            }
+        }
+    }
+
     /// Response with a list of cells
     ///
     /// # Arguments
@@ -562,6 +1629,110 @@ impl cell_service_server::CellService for CellService {
     ) -> std::result::Result<Response<CellServiceListResponse>, Status> {
         Ok(Response::new(self.list().await?))
     }
+
+    #[instrument(skip(self))]
+    async fn stats(
+        &self,
+        request: Request<CellServiceStatsRequest>,
+    ) -> std::result::Result<Response<CellServiceStatsResponse>, Status> {
+        let request = request.into_inner();
+        // Validate the stats request
+        let request =
+            ValidatedCellServiceStatsRequest::validate(request, None)?;
+
+        Ok(Response::new(self.stats(request).await?))
+    }
+
+    #[instrument(skip(self))]
+    async fn pause(
+        &self,
+        request: Request<CellServicePauseRequest>,
+    ) -> std::result::Result<Response<CellServicePauseResponse>, Status> {
+        let request = request.into_inner();
+        // Validate the pause request
+        let request =
+            ValidatedCellServicePauseRequest::validate(request, None)?;
+
+        // pause the cell
+        Ok(Response::new(self.pause(request).await?))
+    }
+
+    #[instrument(skip(self))]
+    async fn resume(
+        &self,
+        request: Request<CellServiceResumeRequest>,
+    ) -> std::result::Result<Response<CellServiceResumeResponse>, Status> {
+        let request = request.into_inner();
+        // Validate the resume request
+        let request =
+            ValidatedCellServiceResumeRequest::validate(request, None)?;
+
+        // resume the cell
+        Ok(Response::new(self.resume(request).await?))
+    }
+
+    type ExecStream = ExecResponseStream;
+
+    #[instrument(skip(self, request))]
+    async fn exec(
+        &self,
+        request: Request<Streaming<CellServiceExecRequest>>,
+    ) -> std::result::Result<Response<Self::ExecStream>, Status> {
+        let mut requests = request.into_inner();
+
+        // The first request starts the process
+        let Some(request) = requests.message().await? else {
+            return Err(Status::invalid_argument("missing exec request"));
+        };
+
+        // Validate the exec request
+        let validated =
+            ValidatedCellServiceExecRequest::validate(request.clone(), None)?;
+
+        match validated.cell_name {
+            None => Ok(Response::new(self.exec(validated, requests).await?)),
+            Some(cell_name) => {
+                let mut request = request;
+                request.cell_name = None;
+
+                // exec the process in the cell
+                self.exec_in_cell(&cell_name, request, requests).await
+            }
+        }
+    }
+
+    type WatchPressureStream = ReceiverStream<
+        std::result::Result<CellServiceWatchPressureResponse, Status>,
+    >;
+
+    #[instrument(skip(self))]
+    async fn watch_pressure(
+        &self,
+        request: Request<CellServiceWatchPressureRequest>,
+    ) -> std::result::Result<Response<Self::WatchPressureStream>, Status> {
+        let request = request.into_inner();
+        // Validate the watch pressure request
+        let request =
+            ValidatedCellServiceWatchPressureRequest::validate(request, None)?;
+
+        Ok(Response::new(self.watch_pressure(request).await?))
+    }
+
+    type WatchStatsStream =
+        ReceiverStream<std::result::Result<CellServiceStatsResponse, Status>>;
+
+    #[instrument(skip(self))]
+    async fn watch_stats(
+        &self,
+        request: Request<CellServiceWatchStatsRequest>,
+    ) -> std::result::Result<Response<Self::WatchStatsStream>, Status> {
+        let request = request.into_inner();
+        // Validate the watch stats request
+        let request =
+            ValidatedCellServiceWatchStatsRequest::validate(request, None)?;
+
+        Ok(Response::new(self.watch_stats(request)))
+    }
 }
 
 #[cfg(test)]
@@ -569,9 +1740,12 @@ mod tests {
     use super::*;
     use crate::{AURAED_RUNTIME, AuraedRuntime};
     use crate::{
-        cells::cell_service::validation::{
-            ValidatedCell, ValidatedCpuController, ValidatedCpusetController,
-            ValidatedMemoryController,
+        cells::cell_service::{
+            cells::cgroups::Limit,
+            validation::{
+                ValidatedCell, ValidatedCpuController,
+                ValidatedCpusetController, ValidatedMemoryController,
+            },
         },
         logging::log_channel::LogChannel,
     };
@@ -658,6 +1832,282 @@ mod tests {
         assert_eq!(actual_nested_cell_names, expected_nested_cell_names);
     }
 
+    /// Test for the update function.
+    #[tokio::test]
+    async fn test_update() {
+        skip_if_not_root!("test_update");
+        skip_if_seccomp!("test_update");
+
+        let _ = AURAED_RUNTIME.set(AuraedRuntime::default());
+
+        let service = CellService::new(ObserveService::new(
+            LogChannel::new(String::from("test")),
+            (None, None, None),
+        ));
+
+        let cell_name = format!("ae-test-{}", uuid::Uuid::new_v4());
+        assert!(service.allocate(allocate_request(&cell_name)).await.is_ok());
+
+        // Only set the memory max, cpu and cpuset are left unchanged
+        let ValidatedCellServiceAllocateRequest { mut cell } =
+            allocate_request(&cell_name);
+        cell.cpu = None;
+        cell.cpuset = None;
+        cell.memory = Some(ValidatedMemoryController {
+            min: None,
+            low: None,
+            high: None,
+            max: Some(Limit::new(1_000_000)),
+            swap_max: None,
+            swap_high: None,
+        });
+
+        let result =
+            service.update(ValidatedCellServiceUpdateRequest { cell }).await;
+        assert!(result.is_ok());
+
+        let list = service.list().await.expect("list");
+        let cell = list
+            .cells
+            .iter()
+            .filter_map(|c| c.cell.as_ref())
+            .find(|c| c.name == cell_name)
+            .expect("cell");
+        assert!(cell.cpu.is_some());
+        assert_eq!(cell.memory.as_ref().and_then(|m| m.max), Some(1_000_000));
+
+        let memory_max = std::fs::read_to_string(format!(
+            "/sys/fs/cgroup/{cell_name}/memory.max"
+        ))
+        .expect("memory.max");
+        assert_eq!(memory_max.trim(), "1000000");
+
+        // Isolation controls can not be updated
+        let ValidatedCellServiceAllocateRequest { mut cell } =
+            allocate_request(&cell_name);
+        cell.isolate_network = true;
+
+        let result =
+            service.update(ValidatedCellServiceUpdateRequest { cell }).await;
+        assert!(matches!(
+            result,
+            Err(CellsServiceError::CellsError(
+                CellsError::IsolationControlsCannotBeUpdated { .. }
+            ))
+        ));
+    }
+
+    /// Test for the stats function.
+    #[tokio::test]
+    async fn test_stats() {
+        skip_if_not_root!("test_stats");
+        skip_if_seccomp!("test_stats");
+
+        let _ = AURAED_RUNTIME.set(AuraedRuntime::default());
+
+        let service = CellService::new(ObserveService::new(
+            LogChannel::new(String::from("test")),
+            (None, None, None),
+        ));
+
+        // Allocate a parent cell, and a nested cell within it
+        let parent_cell_name = format!("ae-test-{}", uuid::Uuid::new_v4());
+        assert!(
+            service.allocate(allocate_request(&parent_cell_name)).await.is_ok()
+        );
+
+        let nested_cell_name =
+            format!("{}/ae-test-{}", &parent_cell_name, uuid::Uuid::new_v4());
+        assert!(
+            service.allocate(allocate_request(&nested_cell_name)).await.is_ok()
+        );
+
+        let result = service
+            .stats(ValidatedCellServiceStatsRequest {
+                cell_name: CellName::from(parent_cell_name.as_str()),
+            })
+            .await;
+        assert!(result.is_ok());
+
+        let stats = result.unwrap().stats.expect("stats");
+        assert_eq!(stats.cell_name, parent_cell_name);
+
+        // The nested auraed is running in the cell
+        let cell_stats = stats.stats.expect("cell stats");
+        assert!(cell_stats.pids.expect("pids").current > 0);
+        assert!(cell_stats.memory.expect("memory").current > 0);
+
+        let actual_nested_cell_names =
+            stats.children.iter().map(|c| c.cell_name.as_str()).collect_vec();
+        assert_eq!(actual_nested_cell_names, vec![&nested_cell_name]);
+
+        // Stats of an unknown cell is an error
+        let result = service
+            .stats(ValidatedCellServiceStatsRequest {
+                cell_name: CellName::random_for_tests(),
+            })
+            .await;
+        assert!(result.is_err());
+    }
+
+    /// Test for the pause and resume functions.
+    #[tokio::test]
+    async fn test_pause_resume() {
+        skip_if_not_root!("test_pause_resume");
+        skip_if_seccomp!("test_pause_resume");
+
+        let _ = AURAED_RUNTIME.set(AuraedRuntime::default());
+
+        let service = CellService::new(ObserveService::new(
+            LogChannel::new(String::from("test")),
+            (None, None, None),
+        ));
+
+        let cell_name = format!("ae-test-{}", uuid::Uuid::new_v4());
+        assert!(service.allocate(allocate_request(&cell_name)).await.is_ok());
+
+        let read_freeze = || {
+            std::fs::read_to_string(format!(
+                "/sys/fs/cgroup/{cell_name}/cgroup.freeze"
+            ))
+            .expect("cgroup.freeze")
+        };
+        let read_state = |list: CellServiceListResponse| {
+            list.cells
+                .into_iter()
+                .find(|c| c.cell.as_ref().is_some_and(|c| c.name == cell_name))
+                .expect("cell")
+                .state
+        };
+
+        let result = service
+            .pause(ValidatedCellServicePauseRequest {
+                cell_name: CellName::from(cell_name.as_str()),
+            })
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(read_freeze().trim(), "1");
+
+        let list = service.list().await.expect("list");
+        assert_eq!(read_state(list), CellState::Paused as i32);
+
+        // Executables can not be started in a paused cell
+        let result = service
+            .start_in_cell(
+                &CellName::from(cell_name.as_str()),
+                CellServiceStartRequest {
+                    cell_name: None,
+                    executable: Some(Executable {
+                        name: String::from("sleep"),
+                        command: String::from("sleep 30"),
+                        description: String::new(),
+                        args: vec![],
+                        env: vec![],
+                        clear_env: false,
+                        working_dir: None,
+                        restart_policy: 0,
+                        max_restarts: None,
+                        restart_backoff_ms: None,
+                        max_restart_backoff_ms: None,
+                        seccomp_profile: None,
+                        capabilities: None,
+                        rlimits: vec![],
+                        command_mode: 0,
+                    }),
+                    uid: None,
+                    gid: None,
+                },
+            )
+            .await;
+        assert_eq!(
+            result.expect_err("paused").code(),
+            Code::FailedPrecondition
+        );
+
+        let result = service
+            .resume(ValidatedCellServiceResumeRequest {
+                cell_name: CellName::from(cell_name.as_str()),
+            })
+            .await;
+        assert!(result.is_ok());
+        assert_eq!(read_freeze().trim(), "0");
+
+        let list = service.list().await.expect("list");
+        assert_eq!(read_state(list), CellState::Allocated as i32);
+    }
+
+    #[tokio::test]
+    async fn test_paused_ancestor() {
+        skip_if_not_root!("test_paused_ancestor");
+        skip_if_seccomp!("test_paused_ancestor");
+
+        let _ = AURAED_RUNTIME.set(AuraedRuntime::default());
+
+        let service = CellService::new(ObserveService::new(
+            LogChannel::new(String::from("test")),
+            (None, None, None),
+        ));
+
+        // Allocate a parent cell, and a nested cell within it
+        let parent_cell_name = format!("ae-test-{}", uuid::Uuid::new_v4());
+        assert!(
+            service.allocate(allocate_request(&parent_cell_name)).await.is_ok()
+        );
+
+        let nested_cell_name =
+            format!("{}/ae-test-{}", &parent_cell_name, uuid::Uuid::new_v4());
+        assert!(
+            service.allocate(allocate_request(&nested_cell_name)).await.is_ok()
+        );
+
+        let result = service
+            .pause(ValidatedCellServicePauseRequest {
+                cell_name: CellName::from(parent_cell_name.as_str()),
+            })
+            .await;
+        assert!(result.is_ok());
+
+        // The nested auraed of the nested cell is frozen with its parent
+        let result = service
+            .start_in_cell(
+                &CellName::from(nested_cell_name.as_str()),
+                CellServiceStartRequest {
+                    cell_name: None,
+                    executable: Some(Executable {
+                        name: String::from("sleep"),
+                        command: String::from("sleep 30"),
+                        description: String::new(),
+                        args: vec![],
+                        env: vec![],
+                        clear_env: false,
+                        working_dir: None,
+                        restart_policy: 0,
+                        max_restarts: None,
+                        restart_backoff_ms: None,
+                        max_restart_backoff_ms: None,
+                        seccomp_profile: None,
+                        capabilities: None,
+                        rlimits: vec![],
+                        command_mode: 0,
+                    }),
+                    uid: None,
+                    gid: None,
+                },
+            )
+            .await;
+        assert_eq!(
+            result.expect_err("paused").code(),
+            Code::FailedPrecondition
+        );
+
+        let result = service
+            .resume(ValidatedCellServiceResumeRequest {
+                cell_name: CellName::from(parent_cell_name.as_str()),
+            })
+            .await;
+        assert!(result.is_ok());
+    }
+
     /// Helper function to create a ValidatedCellServiceAllocateRequest.
     ///
     /// # Arguments
@@ -682,9 +2132,19 @@ mod tests {
                 low: None,
                 high: None,
                 max: None,
+                swap_max: None,
+                swap_high: None,
             }),
+            io: None,
+            pids: None,
             isolate_process: false,
             isolate_network: false,
+            isolate_user: false,
+            uid_mappings: vec![],
+            gid_mappings: vec![],
+            rootfs: None,
+            mounts: vec![],
+            seccomp_profile: None,
         };
         // Return the validated allocate request
         ValidatedCellServiceAllocateRequest { cell }
@@ -706,6 +2166,18 @@ mod tests {
                 name: executable_name.clone(),
                 command: "sleep 30".into(),
                 description: "test executable".into(),
+                args: vec![],
+                env: vec![],
+                clear_env: false,
+                working_dir: None,
+                restart_policy: 0,
+                max_restarts: None,
+                restart_backoff_ms: None,
+                max_restart_backoff_ms: None,
+                seccomp_profile: None,
+                capabilities: None,
+                rlimits: vec![],
+                command_mode: 0,
             }),
             uid: None,
             gid: None,
@@ -751,6 +2223,8 @@ mod tests {
         let stop_request = CellServiceStopRequest {
             cell_name: None,
             executable_name: executable_name.clone(),
+            signal: None,
+            grace_period_ms: None,
         };
         let validated_stop =
             ValidatedCellServiceStopRequest::validate(stop_request, None)
@@ -14,11 +14,22 @@
 \* -------------------------------------------------------------------------- */
 
 use super::{
-    CellName, CellSpec, Cells, CellsCache, CellsError, Result, cgroups::Cgroup,
-    nested_auraed::NestedAuraed,
+    CellName, CellSpec, Cells, CellsCache, CellsError, IsolationControls,
+    Result,
+    cgroups::{
+        Cgroup,
+        memory::UnorderedBoundary,
+        pressure::{
+            Pressure, PressureResource, PressureTrigger, PressureWatch,
+        },
+    },
+    nested_auraed::{NestedAuraed, NestedAuraedState},
 };
 use client::AuraeSocket;
-use tracing::info;
+use ipnetwork::IpNetwork;
+use libcgroups::stats::Stats;
+use std::io;
+use tracing::{info, warn};
 
 // TODO https://github.com/aurae-runtime/aurae/issues/199 &&
 //      aurae.io/signals, which is more accurate
@@ -30,7 +41,13 @@ macro_rules! do_free {
         $nested_auraed_call:ident($($nested_auraed_call_arg:ident),*),
         $($children_call:ident($($children_call_arg:ident),*)),*
     ) => {{
-        if let CellState::Allocated { cgroup, nested_auraed, children } =
+        if let CellState::Paused { cgroup, .. } = &$self.state {
+            // the nested auraed can not handle a graceful shutdown while frozen
+            let _best_effort = cgroup.thaw();
+        }
+
+        if let CellState::Allocated { cgroup, nested_auraed, children }
+        | CellState::Paused { cgroup, nested_auraed, children } =
             &mut $self.state
         {
             $(children.$children_call($($children_call_arg),*));*;
@@ -57,8 +74,9 @@ macro_rules! do_free {
     }};
 }
 
-// We should not be able to change a cell after it has been created.
-// You must free the cell and create a new one if you want to change anything about the cell.
+// We should not be able to change a cell after it has been created, other than
+// updating its cgroup controllers through [Cell::update] and pausing it through [Cell::pause].
+// You must free the cell and create a new one if you want to change anything else about the cell.
 // In order to facilitate that immutability:
 // NEVER MAKE THE FIELDS PUB (OF ANY KIND)
 #[derive(Debug)]
@@ -73,6 +91,7 @@ pub struct Cell {
 enum CellState {
     Unallocated,
     Allocated { cgroup: Cgroup, nested_auraed: NestedAuraed, children: Cells },
+    Paused { cgroup: Cgroup, nested_auraed: NestedAuraed, children: Cells },
     Freed,
 }
 
@@ -89,13 +108,12 @@ impl Cell {
             return Ok(());
         };
 
-        let name = self.cell_name.leaf().to_string();
-
-        let mut auraed = NestedAuraed::new(name, self.spec.iso_ctl.clone())
-            .map_err(|e| CellsError::FailedToAllocateCell {
-                cell_name: self.cell_name.clone(),
-                source: e,
-            })?;
+        let mut auraed =
+            NestedAuraed::new(&self.cell_name, self.spec.iso_ctl.clone())
+                .map_err(|e| CellsError::FailedToAllocateCell {
+                    cell_name: self.cell_name.clone(),
+                    source: e,
+                })?;
 
         let pid = auraed.pid();
 
@@ -135,6 +153,229 @@ impl Cell {
         Ok(())
     }
 
+    /// Adopts a [Cell] allocated by a previous auraed, whose [NestedAuraed] is
+    /// still running. The [Cell] is paused if its cgroup is frozen.
+    /// Its children are adopted separately (see [CellsCache::adopt]).
+    ///
+    /// # Errors
+    /// * If the cgroup does not exist -> [CellsError::CgroupNotFound]
+    /// * If the [NestedAuraed] is not running -> [CellsError::FailedToAdoptCell]
+    pub fn adopt(
+        cell_name: CellName,
+        cell_spec: CellSpec,
+        nested_auraed: &NestedAuraedState,
+    ) -> Result<Self> {
+        let Some(cgroup) = Cgroup::adopt(cell_name.clone()) else {
+            return Err(CellsError::CgroupNotFound { cell_name });
+        };
+
+        let nested_auraed =
+            NestedAuraed::adopt(nested_auraed, cell_spec.iso_ctl.clone())
+                .map_err(|e| CellsError::FailedToAdoptCell {
+                    cell_name: cell_name.clone(),
+                    source: e,
+                })?;
+
+        let children = Cells::new(cell_name.clone());
+        let state = if cgroup.is_frozen() {
+            CellState::Paused { cgroup, nested_auraed, children }
+        } else {
+            CellState::Allocated { cgroup, nested_auraed, children }
+        };
+
+        info!("Adopted cgroup {cell_name}");
+
+        Ok(Self { cell_name, spec: cell_spec, state })
+    }
+
+    /// Cleans up what a previous auraed left of a [Cell] that can not be
+    /// adopted: kills the [NestedAuraed] if it is still running, deletes the
+    /// cgroup and removes the socket. Errors are logged and ignored.
+    /// The children of the [Cell] must be discarded first, as a cgroup with
+    /// children can not be deleted.
+    pub fn discard(cell_name: &CellName, nested_auraed: &NestedAuraedState) {
+        let cgroup = Cgroup::adopt(cell_name.clone());
+        if let Some(cgroup) = &cgroup {
+            // the nested auraed would not exit until thawed
+            let _best_effort = cgroup.thaw();
+        }
+
+        if let Ok(mut auraed) =
+            NestedAuraed::adopt(nested_auraed, IsolationControls::default())
+            && let Err(e) = auraed.kill()
+        {
+            warn!("failed to kill nested auraed of cell {cell_name}: {e}");
+        }
+
+        if let Some(cgroup) = cgroup
+            && let Err(e) = cgroup.delete()
+        {
+            warn!("failed to delete cgroup of cell {cell_name}: {e}");
+        }
+
+        match std::fs::remove_file(&nested_auraed.socket) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                warn!(
+                    "failed to remove socket {}: {e}",
+                    nested_auraed.socket.display()
+                );
+            }
+            _ => {}
+        }
+
+        info!("Discarded cell {cell_name}");
+    }
+
+    /// Cleans up the cgroup of a [Cell] a previous auraed did not persist,
+    /// e.g., as it crashed while allocating the [Cell]: deletes the cgroup,
+    /// which kills the processes in it. Errors are logged and ignored.
+    /// The children of the [Cell] must be discarded first.
+    pub fn discard_orphan(cell_name: &CellName) {
+        let Some(cgroup) = Cgroup::adopt(cell_name.clone()) else {
+            return;
+        };
+
+        // the processes would not exit until thawed
+        let _best_effort = cgroup.thaw();
+        if let Err(e) = cgroup.delete() {
+            warn!("failed to delete cgroup of cell {cell_name}: {e}");
+            return;
+        }
+
+        info!("Discarded orphaned cgroup {cell_name}");
+    }
+
+    /// Applies the cgroup controllers of the [CellSpec] to the underlying cgroup,
+    /// without restarting the [NestedAuraed] or any of the executables.
+    /// Controllers and values that are not set are left unchanged.
+    ///
+    /// # Errors
+    /// * If the [Cell] is not allocated -> [CellsError::CellNotAllocated]
+    /// * If the isolation controls differ -> [CellsError::IsolationControlsCannotBeUpdated]
+    /// * If the merged memory boundaries are out of order -> [CellsError::UnorderedMemoryBoundaries]
+    /// * If the controllers fail to apply -> [CellsError::FailedToUpdateCell]
+    pub fn update(&mut self, cell_spec: CellSpec) -> Result<()> {
+        let (CellState::Allocated { cgroup, .. }
+        | CellState::Paused { cgroup, .. }) = &self.state
+        else {
+            return Err(CellsError::CellNotAllocated {
+                cell_name: self.cell_name.clone(),
+            });
+        };
+
+        let CellSpec { cgroup_spec, iso_ctl } = cell_spec;
+
+        // namespaces are created when the nested auraed is spawned
+        if iso_ctl != self.spec.iso_ctl {
+            return Err(CellsError::IsolationControlsCannotBeUpdated {
+                cell_name: self.cell_name.clone(),
+            });
+        }
+
+        // The update is only validated on its own, the boundaries it leaves
+        // unchanged must still be in order
+        let merged = self.spec.cgroup_spec.merge(cgroup_spec.clone());
+        if let Some(UnorderedBoundary {
+            name,
+            value,
+            lower_name,
+            lower_value,
+        }) = merged.memory.as_ref().and_then(|x| x.unordered_boundary())
+        {
+            return Err(CellsError::UnorderedMemoryBoundaries {
+                cell_name: self.cell_name.clone(),
+                name,
+                value,
+                lower_name,
+                lower_value,
+            });
+        }
+
+        cgroup.update(cgroup_spec).map_err(|e| {
+            CellsError::FailedToUpdateCell {
+                cell_name: self.cell_name.clone(),
+                source: e,
+            }
+        })?;
+
+        self.spec.cgroup_spec = merged;
+
+        info!("Updated cgroup {}", self.cell_name);
+
+        Ok(())
+    }
+
+    /// Freezes the underlying cgroup, which stops all processes of the [Cell],
+    /// including the [NestedAuraed] and all descendants, until [Cell::resume] is called.
+    /// Does nothing if the [Cell] is already paused.
+    ///
+    /// # Errors
+    /// * If the [Cell] is not allocated -> [CellsError::CellNotAllocated]
+    /// * If the cgroup fails to freeze -> [CellsError::FailedToPauseCell]
+    pub fn pause(&mut self) -> Result<()> {
+        let cgroup = match &self.state {
+            CellState::Allocated { cgroup, .. } => cgroup,
+            CellState::Paused { .. } => return Ok(()),
+            CellState::Unallocated | CellState::Freed => {
+                return Err(CellsError::CellNotAllocated {
+                    cell_name: self.cell_name.clone(),
+                });
+            }
+        };
+
+        cgroup.freeze().map_err(|e| CellsError::FailedToPauseCell {
+            cell_name: self.cell_name.clone(),
+            source: e,
+        })?;
+
+        self.state = match std::mem::replace(&mut self.state, CellState::Freed)
+        {
+            CellState::Allocated { cgroup, nested_auraed, children } => {
+                CellState::Paused { cgroup, nested_auraed, children }
+            }
+            state => state,
+        };
+
+        info!("Paused cgroup {}", self.cell_name);
+
+        Ok(())
+    }
+
+    /// Thaws the underlying cgroup of a [Cell] that was paused with [Cell::pause].
+    /// Does nothing if the [Cell] is not paused.
+    ///
+    /// # Errors
+    /// * If the [Cell] is not allocated -> [CellsError::CellNotAllocated]
+    /// * If the cgroup fails to thaw -> [CellsError::FailedToResumeCell]
+    pub fn resume(&mut self) -> Result<()> {
+        let cgroup = match &self.state {
+            CellState::Paused { cgroup, .. } => cgroup,
+            CellState::Allocated { .. } => return Ok(()),
+            CellState::Unallocated | CellState::Freed => {
+                return Err(CellsError::CellNotAllocated {
+                    cell_name: self.cell_name.clone(),
+                });
+            }
+        };
+
+        cgroup.thaw().map_err(|e| CellsError::FailedToResumeCell {
+            cell_name: self.cell_name.clone(),
+            source: e,
+        })?;
+
+        self.state = match std::mem::replace(&mut self.state, CellState::Freed)
+        {
+            CellState::Paused { cgroup, nested_auraed, children } => {
+                CellState::Allocated { cgroup, nested_auraed, children }
+            }
+            state => state,
+        };
+
+        info!("Resumed cgroup {}", self.cell_name);
+
+        Ok(())
+    }
+
     /// Broadcasts a graceful shutdown signal to all [NestedAuraed] and
     /// deletes the underlying cgroup and all descendants.
     ///
@@ -152,14 +393,51 @@ impl Cell {
         do_free!(self, kill(), broadcast_kill())
     }
 
+    /// Returns the socket of the [NestedAuraed] of the [Cell].
+    ///
+    /// # Errors
+    /// * If the [Cell] is not allocated -> [CellsError::CellNotAllocated]
+    /// * If the [Cell] is paused, as the [NestedAuraed] can not respond -> [CellsError::CellPaused]
     pub fn client_socket(&self) -> Result<AuraeSocket> {
-        let CellState::Allocated { nested_auraed, .. } = &self.state else {
-            return Err(CellsError::CellNotAllocated {
+        match &self.state {
+            CellState::Allocated { nested_auraed, .. } => {
+                Ok(nested_auraed.client_socket.clone())
+            }
+            CellState::Paused { .. } => Err(CellsError::CellPaused {
                 cell_name: self.cell_name.clone(),
-            });
+            }),
+            CellState::Unallocated | CellState::Freed => {
+                Err(CellsError::CellNotAllocated {
+                    cell_name: self.cell_name.clone(),
+                })
+            }
+        }
+    }
+
+    /// Returns what is needed to adopt the [NestedAuraed] of the [Cell],
+    /// should auraed be restarted (see [Cell::adopt]).
+    /// Returns [None] if the [Cell] is not allocated.
+    pub fn nested_auraed_state(&self) -> Option<NestedAuraedState> {
+        let (CellState::Allocated { nested_auraed, .. }
+        | CellState::Paused { nested_auraed, .. }) = &self.state
+        else {
+            return None;
         };
 
-        Ok(nested_auraed.client_socket.clone())
+        Some(nested_auraed.state())
+    }
+
+    /// Returns the addresses assigned to the network namespace of the
+    /// [Cell]. Empty if the [Cell] is not allocated, or does not isolate its
+    /// network.
+    pub fn addresses(&self) -> Vec<IpNetwork> {
+        let (CellState::Allocated { nested_auraed, .. }
+        | CellState::Paused { nested_auraed, .. }) = &self.state
+        else {
+            return vec![];
+        };
+
+        nested_auraed.addresses().to_vec()
     }
 
     /// Returns the [CellName] of the [Cell]
@@ -171,14 +449,93 @@ impl Cell {
         &self.spec
     }
 
+    /// Returns true if the [Cell] has been paused with [Cell::pause].
+    pub fn is_paused(&self) -> bool {
+        matches!(self.state, CellState::Paused { .. })
+    }
+
     /// Returns [None] if the [Cell] is not allocated.
     pub fn v2(&self) -> Option<bool> {
-        let CellState::Allocated { cgroup, .. } = &self.state else {
+        let (CellState::Allocated { cgroup, .. }
+        | CellState::Paused { cgroup, .. }) = &self.state
+        else {
             return None;
         };
 
         Some(cgroup.v2())
     }
+
+    /// Reads the cgroup statistics of the [Cell].
+    ///
+    /// # Errors
+    /// * If the [Cell] is not allocated -> [CellsError::CellNotAllocated]
+    /// * If the cgroup interface files can not be read -> [CellsError::FailedToReadCellStats]
+    pub fn stats(&self) -> Result<Stats> {
+        let (CellState::Allocated { cgroup, .. }
+        | CellState::Paused { cgroup, .. }) = &self.state
+        else {
+            return Err(CellsError::CellNotAllocated {
+                cell_name: self.cell_name.clone(),
+            });
+        };
+
+        cgroup.stats().map_err(|e| CellsError::FailedToReadCellStats {
+            cell_name: self.cell_name.clone(),
+            source: e,
+        })
+    }
+
+    /// Reads the Pressure Stall Information (PSI) of a resource of the [Cell].
+    /// Returns [None] if the kernel does not provide PSI.
+    ///
+    /// # Errors
+    /// * If the [Cell] is not allocated -> [CellsError::CellNotAllocated]
+    /// * If the pressure file can not be read -> [CellsError::FailedToReadCellStats]
+    pub fn pressure(
+        &self,
+        resource: PressureResource,
+    ) -> Result<Option<Pressure>> {
+        let (CellState::Allocated { cgroup, .. }
+        | CellState::Paused { cgroup, .. }) = &self.state
+        else {
+            return Err(CellsError::CellNotAllocated {
+                cell_name: self.cell_name.clone(),
+            });
+        };
+
+        cgroup.pressure(resource).map_err(|e| {
+            CellsError::FailedToReadCellStats {
+                cell_name: self.cell_name.clone(),
+                source: e,
+            }
+        })
+    }
+
+    /// Registers a PSI trigger with the underlying cgroup.
+    /// The trigger stays registered until the returned [PressureWatch] is dropped.
+    ///
+    /// # Errors
+    /// * If the [Cell] is not allocated -> [CellsError::CellNotAllocated]
+    /// * If the trigger can not be registered -> [CellsError::FailedToWatchCellPressure]
+    pub fn watch_pressure(
+        &self,
+        trigger: &PressureTrigger,
+    ) -> Result<PressureWatch> {
+        let (CellState::Allocated { cgroup, .. }
+        | CellState::Paused { cgroup, .. }) = &self.state
+        else {
+            return Err(CellsError::CellNotAllocated {
+                cell_name: self.cell_name.clone(),
+            });
+        };
+
+        cgroup.watch_pressure(trigger).map_err(|e| {
+            CellsError::FailedToWatchCellPressure {
+                cell_name: self.cell_name.clone(),
+                source: e,
+            }
+        })
+    }
 }
 
 impl CellsCache for Cell {
@@ -187,17 +544,45 @@ impl CellsCache for Cell {
         cell_name: CellName,
         cell_spec: CellSpec,
     ) -> Result<&Cell> {
-        let CellState::Allocated { children, .. } = &mut self.state else {
+        let children = match &mut self.state {
+            CellState::Allocated { children, .. } => children,
+            // the nested auraed of a new cell would be frozen on creation
+            CellState::Paused { .. } => {
+                return Err(CellsError::CellPaused {
+                    cell_name: self.cell_name.clone(),
+                });
+            }
+            CellState::Unallocated | CellState::Freed => {
+                return Err(CellsError::CellNotAllocated {
+                    cell_name: self.cell_name.clone(),
+                });
+            }
+        };
+
+        children.allocate(cell_name, cell_spec)
+    }
+
+    fn adopt(
+        &mut self,
+        cell_name: CellName,
+        cell_spec: CellSpec,
+        nested_auraed: NestedAuraedState,
+    ) -> Result<&Cell> {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
             return Err(CellsError::CellNotAllocated {
                 cell_name: self.cell_name.clone(),
             });
         };
 
-        children.allocate(cell_name, cell_spec)
+        children.adopt(cell_name, cell_spec, nested_auraed)
     }
 
     fn free(&mut self, cell_name: &CellName) -> Result<()> {
-        let CellState::Allocated { children, .. } = &mut self.state else {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
             return Err(CellsError::CellNotAllocated {
                 cell_name: self.cell_name.clone(),
             });
@@ -206,11 +591,65 @@ impl CellsCache for Cell {
         children.free(cell_name)
     }
 
+    fn remove(&mut self, cell_name: &CellName) -> Result<Cell> {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
+            return Err(CellsError::CellNotAllocated {
+                cell_name: self.cell_name.clone(),
+            });
+        };
+
+        children.remove(cell_name)
+    }
+
+    fn update(
+        &mut self,
+        cell_name: &CellName,
+        cell_spec: CellSpec,
+    ) -> Result<()> {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
+            return Err(CellsError::CellNotAllocated {
+                cell_name: self.cell_name.clone(),
+            });
+        };
+
+        children.update(cell_name, cell_spec)
+    }
+
+    fn pause(&mut self, cell_name: &CellName) -> Result<()> {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
+            return Err(CellsError::CellNotAllocated {
+                cell_name: self.cell_name.clone(),
+            });
+        };
+
+        children.pause(cell_name)
+    }
+
+    fn resume(&mut self, cell_name: &CellName) -> Result<()> {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
+            return Err(CellsError::CellNotAllocated {
+                cell_name: self.cell_name.clone(),
+            });
+        };
+
+        children.resume(cell_name)
+    }
+
     fn get<F, R>(&mut self, cell_name: &CellName, f: F) -> Result<R>
     where
         F: Fn(&Cell) -> Result<R>,
     {
-        let CellState::Allocated { children, .. } = &mut self.state else {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
             return Err(CellsError::CellNotAllocated {
                 cell_name: self.cell_name.clone(),
             });
@@ -219,11 +658,30 @@ impl CellsCache for Cell {
         children.get(cell_name, f)
     }
 
+    fn client_socket(&mut self, cell_name: &CellName) -> Result<AuraeSocket> {
+        match &mut self.state {
+            CellState::Allocated { children, .. } => {
+                children.client_socket(cell_name)
+            }
+            // Freezing a cgroup also freezes all of its descendants
+            CellState::Paused { .. } => Err(CellsError::CellPaused {
+                cell_name: self.cell_name.clone(),
+            }),
+            CellState::Unallocated | CellState::Freed => {
+                Err(CellsError::CellNotAllocated {
+                    cell_name: self.cell_name.clone(),
+                })
+            }
+        }
+    }
+
     fn get_all<F, R>(&self, f: F) -> Result<Vec<Result<R>>>
     where
         F: Fn(&Cell) -> Result<R>,
     {
-        let CellState::Allocated { children, .. } = &self.state else {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &self.state
+        else {
             return Err(CellsError::CellNotAllocated {
                 cell_name: self.cell_name.clone(),
             });
@@ -233,7 +691,9 @@ impl CellsCache for Cell {
     }
 
     fn broadcast_free(&mut self) {
-        let CellState::Allocated { children, .. } = &mut self.state else {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
             return;
         };
 
@@ -241,7 +701,9 @@ impl CellsCache for Cell {
     }
 
     fn broadcast_kill(&mut self) {
-        let CellState::Allocated { children, .. } = &mut self.state else {
+        let (CellState::Allocated { children, .. }
+        | CellState::Paused { children, .. }) = &mut self.state
+        else {
             return;
         };
 
@@ -13,8 +13,12 @@
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
 
-use super::{Cell, CellName, CellSpec, CellsError, Result, cgroups::Cgroup};
+use super::{
+    Cell, CellName, CellSpec, CellsError, NestedAuraedState, Result,
+    cgroups::Cgroup,
+};
 use crate::cells::cell_service::cells::cells_cache::CellsCache;
+use client::AuraeSocket;
 use std::collections::HashMap;
 use tracing::warn;
 
@@ -99,6 +103,29 @@ impl Cells {
         })
     }
 
+    fn adopt(
+        &mut self,
+        cell_name: CellName,
+        cell_spec: CellSpec,
+        nested_auraed: NestedAuraedState,
+    ) -> Result<&Cell> {
+        proxy_if_needed!(
+            self,
+            cell_name,
+            adopt(cell_name, cell_spec, nested_auraed),
+            {
+                if self.cache.contains_key(&cell_name) {
+                    return Err(CellsError::CellExists { cell_name });
+                }
+
+                let cell =
+                    Cell::adopt(cell_name.clone(), cell_spec, &nested_auraed)?;
+
+                Ok(self.cache.entry(cell_name).or_insert(cell))
+            }
+        )
+    }
+
     fn free(&mut self, cell_name: &CellName) -> Result<()> {
         proxy_if_needed!(self, cell_name, free(cell_name), {
             self.handle_cgroup_does_not_exist(cell_name)?;
@@ -108,6 +135,37 @@ impl Cells {
         })
     }
 
+    fn remove(&mut self, cell_name: &CellName) -> Result<Cell> {
+        proxy_if_needed!(self, cell_name, remove(cell_name), {
+            self.handle_cgroup_does_not_exist(cell_name)?;
+            self.cache.remove(cell_name).ok_or_else(|| {
+                CellsError::CgroupIsNotACell { cell_name: cell_name.clone() }
+            })
+        })
+    }
+
+    fn update(
+        &mut self,
+        cell_name: &CellName,
+        cell_spec: CellSpec,
+    ) -> Result<()> {
+        proxy_if_needed!(self, cell_name, update(cell_name, cell_spec), {
+            self.get_mut(cell_name, |cell| cell.update(cell_spec))
+        })
+    }
+
+    fn pause(&mut self, cell_name: &CellName) -> Result<()> {
+        proxy_if_needed!(self, cell_name, pause(cell_name), {
+            self.get_mut(cell_name, |cell| cell.pause())
+        })
+    }
+
+    fn resume(&mut self, cell_name: &CellName) -> Result<()> {
+        proxy_if_needed!(self, cell_name, resume(cell_name), {
+            self.get_mut(cell_name, |cell| cell.resume())
+        })
+    }
+
     fn get<F, R>(&mut self, cell_name: &CellName, f: F) -> Result<R>
     where
         F: Fn(&Cell) -> Result<R>,
@@ -131,6 +189,12 @@ impl Cells {
         })
     }
 
+    fn client_socket(&mut self, cell_name: &CellName) -> Result<AuraeSocket> {
+        proxy_if_needed!(self, cell_name, client_socket(cell_name), {
+            self.get(cell_name, |cell| cell.client_socket())
+        })
+    }
+
     fn get_all<F, R>(&self, f: F) -> Result<Vec<Result<R>>>
     where
         F: Fn(&Cell) -> Result<R>,
@@ -240,10 +304,39 @@ impl CellsCache for Cells {
         self.allocate(cell_name, cell_spec)
     }
 
+    fn adopt(
+        &mut self,
+        cell_name: CellName,
+        cell_spec: CellSpec,
+        nested_auraed: NestedAuraedState,
+    ) -> Result<&Cell> {
+        self.adopt(cell_name, cell_spec, nested_auraed)
+    }
+
     fn free(&mut self, cell_name: &CellName) -> Result<()> {
         self.free(cell_name)
     }
 
+    fn remove(&mut self, cell_name: &CellName) -> Result<Cell> {
+        self.remove(cell_name)
+    }
+
+    fn update(
+        &mut self,
+        cell_name: &CellName,
+        cell_spec: CellSpec,
+    ) -> Result<()> {
+        self.update(cell_name, cell_spec)
+    }
+
+    fn pause(&mut self, cell_name: &CellName) -> Result<()> {
+        self.pause(cell_name)
+    }
+
+    fn resume(&mut self, cell_name: &CellName) -> Result<()> {
+        self.resume(cell_name)
+    }
+
     fn get<F, R>(&mut self, cell_name: &CellName, f: F) -> Result<R>
     where
         F: Fn(&Cell) -> Result<R>,
@@ -251,6 +344,10 @@ impl CellsCache for Cells {
         self.get(cell_name, f)
     }
 
+    fn client_socket(&mut self, cell_name: &CellName) -> Result<AuraeSocket> {
+        self.client_socket(cell_name)
+    }
+
     fn get_all<F, R>(&self, f: F) -> Result<Vec<Result<R>>>
     where
         F: Fn(&Cell) -> Result<R>,
@@ -383,6 +480,28 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_remove_then_free() {
+        skip_if_not_root!("test_remove_then_free");
+        skip_if_seccomp!("test_remove_then_free");
+
+        let _ = AURAED_RUNTIME.set(AuraedRuntime::default());
+
+        let mut cells = Cells::default();
+
+        let cell_name = CellName::random_for_tests();
+        let cell = CellSpec::new_for_tests();
+        let _ = cells
+            .allocate(cell_name.clone(), cell)
+            .expect("failed to allocate");
+
+        let mut cell = cells.remove(&cell_name).expect("failed to remove");
+        assert!(cells.cache.is_empty());
+
+        cell.free().expect("failed to free");
+        assert!(!Cgroup::exists(&cell_name));
+    }
+
     struct Graph {
         name: CellName,
         children: Vec<Self>,
@@ -42,7 +42,8 @@
  *                                                                            *
 \* -------------------------------------------------------------------------- */
 
-use super::{Cell, CellName, CellSpec, Result};
+use super::{Cell, CellName, CellSpec, NestedAuraedState, Result};
+use client::AuraeSocket;
 
 pub trait CellsCache {
     /// Calls [Cell::allocate] on a new [Cell] and adds it to it's cache with key [CellName].
@@ -57,6 +58,19 @@ pub trait CellsCache {
         cell_spec: CellSpec,
     ) -> Result<&Cell>;
 
+    /// Calls [Cell::adopt] and adds the adopted [Cell] to it's cache with key [CellName].
+    /// The parent of the cell must have been adopted first.
+    ///
+    /// # Errors
+    /// * If cell exists -> [CellsError::CellExists]
+    /// * If cell fails to be adopted (see [Cell::adopt])
+    fn adopt(
+        &mut self,
+        cell_name: CellName,
+        cell_spec: CellSpec,
+        nested_auraed: NestedAuraedState,
+    ) -> Result<&Cell>;
+
     /// Calls [Cell::free] on a [Cell] and removes it from the cache.
     ///
     /// # Errors
@@ -67,10 +81,63 @@ pub trait CellsCache {
     /// * If cell fails to free (see [Cell::free])
     fn free(&mut self, cell_name: &CellName) -> Result<()>;
 
+    /// Removes a [Cell] from the cache, so it can be freed with [Cell::free]
+    /// without holding the cache, as freeing waits for its [NestedAuraed]
+    /// to exit.
+    ///
+    /// # Errors
+    /// * If cell is not cached and cgroup does not exist -> [CellsError::CellNotFound]
+    /// * If cell is cached and cgroup does not exist -> [CellsError::CgroupNotFound]
+    ///     - note: cell will be removed from cache
+    /// * If cell is not cached and cgroup exists on fs -> [CellsError::CgroupIsNotACell]
+    fn remove(&mut self, cell_name: &CellName) -> Result<Cell>;
+
+    /// Calls [Cell::update] on a [Cell] in the cache.
+    ///
+    /// # Errors
+    /// * If cell is not cached and cgroup does not exist -> [CellsError::CellNotFound]
+    /// * If cell is cached and cgroup does not exist -> [CellsError::CgroupNotFound]
+    /// * If cell is not cached and cgroup exists on fs -> [CellsError::CgroupIsNotACell]
+    /// * If cell fails to update (see [Cell::update])
+    fn update(
+        &mut self,
+        cell_name: &CellName,
+        cell_spec: CellSpec,
+    ) -> Result<()>;
+
+    /// Calls [Cell::pause] on a [Cell] in the cache.
+    ///
+    /// # Errors
+    /// * If cell is not cached and cgroup does not exist -> [CellsError::CellNotFound]
+    /// * If cell is cached and cgroup does not exist -> [CellsError::CgroupNotFound]
+    /// * If cell is not cached and cgroup exists on fs -> [CellsError::CgroupIsNotACell]
+    /// * If cell fails to pause (see [Cell::pause])
+    fn pause(&mut self, cell_name: &CellName) -> Result<()>;
+
+    /// Calls [Cell::resume] on a [Cell] in the cache.
+    ///
+    /// # Errors
+    /// * If cell is not cached and cgroup does not exist -> [CellsError::CellNotFound]
+    /// * If cell is cached and cgroup does not exist -> [CellsError::CgroupNotFound]
+    /// * If cell is not cached and cgroup exists on fs -> [CellsError::CgroupIsNotACell]
+    /// * If cell fails to resume (see [Cell::resume])
+    fn resume(&mut self, cell_name: &CellName) -> Result<()>;
+
     fn get<F, R>(&mut self, cell_name: &CellName, f: F) -> Result<R>
     where
         F: Fn(&Cell) -> Result<R>;
 
+    /// Calls [Cell::client_socket] on a [Cell] in the cache.
+    /// Unlike [CellsCache::get], the ancestors of the cell are checked too,
+    /// as a paused ancestor freezes the [NestedAuraed] of all its descendants.
+    ///
+    /// # Errors
+    /// * If cell is not cached and cgroup does not exist -> [CellsError::CellNotFound]
+    /// * If cell is cached and cgroup does not exist -> [CellsError::CgroupNotFound]
+    /// * If cell is not cached and cgroup exists on fs -> [CellsError::CgroupIsNotACell]
+    /// * If the cell or any of its ancestors is paused -> [CellsError::CellPaused]
+    fn client_socket(&mut self, cell_name: &CellName) -> Result<AuraeSocket>;
+
     fn get_all<F, R>(&self, f: F) -> Result<Vec<Result<R>>>
     where
         F: Fn(&Cell) -> Result<R>;
@@ -15,17 +15,29 @@
 
 use crate::cells::cell_service::cells::{
     CellName, CgroupSpec,
-    cgroups::{CpuController, CpusetController, MemoryController},
+    cgroups::{
+        CpuController, CpusetController, IoController, IoDeviceController,
+        MemoryController, PidsController,
+        pressure::{
+            Pressure, PressureResource, PressureTrigger, PressureWatch,
+        },
+    },
+};
+use libcgroups::common::{
+    CgroupManager, ControllerOpt, DEFAULT_CGROUP_ROOT, FreezerState,
 };
-use libcgroups::common::{CgroupManager, ControllerOpt, DEFAULT_CGROUP_ROOT};
 use libcgroups::stats::Stats;
 use libcgroups::v2;
 use nix::unistd::Pid;
 use oci_spec::runtime::{
-    LinuxCpuBuilder, LinuxMemoryBuilder, LinuxResourcesBuilder,
+    LinuxBlockIo, LinuxBlockIoBuilder, LinuxCpuBuilder, LinuxMemoryBuilder,
+    LinuxPidsBuilder, LinuxResources, LinuxResourcesBuilder,
+    LinuxThrottleDevice, LinuxThrottleDeviceBuilder, LinuxWeightDeviceBuilder,
 };
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
+use validation::ValidatedField;
 
 use super::error::{CgroupsError, Result};
 
@@ -40,8 +52,6 @@ impl Cgroup {
         spec: CgroupSpec,
         nested_auraed_pid: Pid,
     ) -> Result<Self> {
-        let CgroupSpec { cpu, cpuset, memory } = spec;
-
         // Note: Cgroups v2 "no internal processes" rule.
         // Docs: https://man7.org/linux/man-pages/man7/cgroups.7.html
         // TLDR: "...with the exception of the root cgroup, processes may reside only
@@ -71,84 +81,9 @@ impl Cgroup {
             });
         }
 
-        let builder = LinuxResourcesBuilder::default();
-
-        // oci_spec, which libcgroups uses, combines the cpu and cpuset controllers
-        let builder = if cpu.is_some() || cpuset.is_some() || memory.is_some() {
-            let cpu_builder = LinuxCpuBuilder::default();
-
-            // cpu controller
-            let cpu_builder =
-                if let Some(CpuController { weight, max, period }) = cpu {
-                    let mut cpu_builder = if let Some(weight) = weight {
-                        cpu_builder.shares(weight.into_inner())
-                    } else {
-                        cpu_builder
-                    };
-
-                    cpu_builder = if let Some(max) = max {
-                        cpu_builder.quota(max.into_inner())
-                    } else {
-                        cpu_builder
-                    };
-
-                    if let Some(period) = period {
-                        cpu_builder.period(period)
-                    } else {
-                        cpu_builder
-                    }
-                } else {
-                    cpu_builder
-                };
-
-            // cpuset controller
-            let cpu_builder =
-                if let Some(CpusetController { cpus, mems }) = cpuset {
-                    let cpu_builder = if let Some(cpus) = cpus {
-                        cpu_builder.cpus(cpus.into_inner())
-                    } else {
-                        cpu_builder
-                    };
-
-                    if let Some(mems) = mems {
-                        cpu_builder.mems(mems.into_inner())
-                    } else {
-                        cpu_builder
-                    }
-                } else {
-                    cpu_builder
-                };
-
-            let memory_builder = LinuxMemoryBuilder::default();
-            let memory_builder =
-                if let Some(MemoryController { min: _, low, high: _, max }) =
-                    memory
-                {
-                    let memory_builder = if let Some(low) = low {
-                        memory_builder.reservation(low.into_inner())
-                    } else {
-                        memory_builder
-                    };
-
-                    if let Some(max) = max {
-                        memory_builder.limit(max.into_inner())
-                    } else {
-                        memory_builder
-                    }
-                } else {
-                    memory_builder
-                };
-
-            let cpu = cpu_builder.build().expect("valid cpu builder");
-            let memory = memory_builder.build().expect("valid memory builder");
-            builder.cpu(cpu).memory(memory)
-        } else {
-            builder
-        };
-
-        let options = builder.build().expect("valid options");
+        let resources = get_resources(spec);
         let options = ControllerOpt {
-            resources: &options,
+            resources: &resources,
             disable_oom_killer: false,
             oom_score_adj: None,
             freezer_state: None,
@@ -168,6 +103,65 @@ impl Cgroup {
         Ok(Self { cell_name })
     }
 
+    /// Returns the existing cgroup of a cell allocated by a previous auraed,
+    /// or [None] if it does not exist.
+    pub fn adopt(cell_name: CellName) -> Option<Self> {
+        Self::exists(&cell_name).then_some(Self { cell_name })
+    }
+
+    /// Applies the controllers of the [CgroupSpec] to the existing non-leaf cgroup.
+    /// Values that are not set in the [CgroupSpec] are left unchanged.
+    pub fn update(&self, spec: CgroupSpec) -> Result<()> {
+        let non_leaf = v2::manager::Manager::new(
+            DEFAULT_CGROUP_ROOT.into(),
+            self.cell_name.clone().into_inner(),
+        )
+        .expect("valid cgroup");
+
+        let resources = get_resources(spec);
+        let options = ControllerOpt {
+            resources: &resources,
+            disable_oom_killer: false,
+            oom_score_adj: None,
+            freezer_state: None,
+        };
+
+        non_leaf.apply(&options).map_err(|e| CgroupsError::UpdateCgroup {
+            cell_name: self.cell_name.clone(),
+            source: e.into(),
+        })
+    }
+
+    /// Freezes all processes of the non-leaf cgroup (cgroup.freeze), which
+    /// includes the nested auraed, its executables and all nested cells.
+    pub fn freeze(&self) -> Result<()> {
+        self.set_freezer_state(FreezerState::Frozen)
+    }
+
+    /// Thaws all processes of the non-leaf cgroup (cgroup.freeze).
+    pub fn thaw(&self) -> Result<()> {
+        self.set_freezer_state(FreezerState::Thawed)
+    }
+
+    fn set_freezer_state(&self, state: FreezerState) -> Result<()> {
+        let non_leaf = v2::manager::Manager::new(
+            DEFAULT_CGROUP_ROOT.into(),
+            self.cell_name.clone().into_inner(),
+        )
+        .expect("valid cgroup");
+
+        non_leaf.freeze(state).map_err(|e| CgroupsError::FreezeCgroup {
+            cell_name: self.cell_name.clone(),
+            source: e.into(),
+        })
+    }
+
+    /// Returns true if the non-leaf cgroup is frozen (cgroup.freeze).
+    pub fn is_frozen(&self) -> bool {
+        let path = get_path(&self.cell_name).join("cgroup.freeze");
+        std::fs::read_to_string(path).is_ok_and(|x| x.trim() == "1")
+    }
+
     pub fn add_task(&self, pid: Pid) -> Result<()> {
         let manager = v2::manager::Manager::new(
             DEFAULT_CGROUP_ROOT.into(),
@@ -214,8 +208,8 @@ impl Cgroup {
         true
     }
 
-    // TODO: use this
-    #[allow(unused)]
+    /// Reads the statistics of the non-leaf cgroup, which includes the
+    /// nested auraed, its executables and all nested cells.
     pub fn stats(&self) -> Result<Stats> {
         let non_leaf = v2::manager::Manager::new(
             DEFAULT_CGROUP_ROOT.into(),
@@ -223,18 +217,271 @@ impl Cgroup {
         )
         .expect("valid cgroup");
 
-        non_leaf.stats().map_err(|e| CgroupsError::ReadStats {
-            cell_name: self.cell_name.clone(),
-            source: e.into(),
+        let mut stats =
+            non_leaf.stats().map_err(|e| CgroupsError::ReadStats {
+                cell_name: self.cell_name.clone(),
+                source: e.into(),
+            })?;
+
+        // libcgroups does not read memory.peak for cgroup v2.
+        // The file only exists on kernels >= 5.19, so we leave the value untouched otherwise.
+        if let Some(peak) = self.memory_peak() {
+            stats.memory.memory.max_usage = peak;
+        }
+
+        Ok(stats)
+    }
+
+    /// Reads the Pressure Stall Information (PSI) of the non-leaf cgroup.
+    /// Returns [None] if the kernel does not provide PSI (e.g., `psi=0`).
+    pub fn pressure(
+        &self,
+        resource: PressureResource,
+    ) -> Result<Option<Pressure>> {
+        let path = get_path(&self.cell_name).join(resource.file());
+
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Err(e) => {
+                return Err(CgroupsError::ReadStats {
+                    cell_name: self.cell_name.clone(),
+                    source: e.into(),
+                });
+            }
+        };
+
+        Pressure::parse(&content).map(Some).ok_or_else(|| {
+            CgroupsError::ReadStats {
+                cell_name: self.cell_name.clone(),
+                source: anyhow::anyhow!("invalid {}", resource.file()),
+            }
         })
     }
 
+    /// Registers a PSI trigger with the non-leaf cgroup.
+    pub fn watch_pressure(
+        &self,
+        trigger: &PressureTrigger,
+    ) -> Result<PressureWatch> {
+        PressureWatch::new(&get_path(&self.cell_name), trigger).map_err(|e| {
+            CgroupsError::WatchPressure {
+                cell_name: self.cell_name.clone(),
+                source: e.into(),
+            }
+        })
+    }
+
+    fn memory_peak(&self) -> Option<u64> {
+        let path = get_path(&self.cell_name).join("memory.peak");
+        std::fs::read_to_string(path).ok()?.trim().parse().ok()
+    }
+
     pub fn exists(cell_name: &CellName) -> bool {
-        let mut path =
-            PathBuf::from_str(DEFAULT_CGROUP_ROOT).expect("valid path");
-        path.push(cell_name.as_inner());
-        path.exists()
+        get_path(cell_name).exists()
     }
+
+    /// Returns the names of the cells whose cgroups are children of the
+    /// cgroup of `parent`, or of the root cgroup. The cgroups of cells are
+    /// told apart from others (e.g., system.slice) by their leaf cgroup.
+    pub fn children(parent: Option<&CellName>) -> Vec<CellName> {
+        let path = parent.map_or_else(
+            || PathBuf::from_str(DEFAULT_CGROUP_ROOT).expect("valid path"),
+            get_path,
+        );
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return vec![];
+        };
+
+        entries
+            .filter_map(|x| x.ok())
+            .filter(|x| x.path().join("_").is_dir())
+            .filter_map(|x| {
+                let name = x.file_name().into_string().ok()?;
+                let name = match parent {
+                    Some(parent) => format!("{parent}/{name}"),
+                    None => name,
+                };
+                CellName::validate(Some(name), "name", None).ok()
+            })
+            .collect()
+    }
+}
+
+fn get_resources(spec: CgroupSpec) -> LinuxResources {
+    let CgroupSpec { cpu, cpuset, memory, io, pids } = spec;
+
+    let builder = LinuxResourcesBuilder::default();
+
+    // oci_spec, which libcgroups uses, combines the cpu and cpuset controllers
+    let builder = if cpu.is_some() || cpuset.is_some() || memory.is_some() {
+        let cpu_builder = LinuxCpuBuilder::default();
+
+        // cpu controller
+        let cpu_builder =
+            if let Some(CpuController { weight, max, period }) = cpu {
+                let mut cpu_builder = if let Some(weight) = weight {
+                    cpu_builder.shares(weight.into_inner())
+                } else {
+                    cpu_builder
+                };
+
+                cpu_builder = if let Some(max) = max {
+                    cpu_builder.quota(max.into_inner())
+                } else {
+                    cpu_builder
+                };
+
+                if let Some(period) = period {
+                    cpu_builder.period(period)
+                } else {
+                    cpu_builder
+                }
+            } else {
+                cpu_builder
+            };
+
+        // cpuset controller
+        let cpu_builder = if let Some(CpusetController { cpus, mems }) = cpuset
+        {
+            let cpu_builder = if let Some(cpus) = cpus {
+                cpu_builder.cpus(cpus.into_inner())
+            } else {
+                cpu_builder
+            };
+
+            if let Some(mems) = mems {
+                cpu_builder.mems(mems.into_inner())
+            } else {
+                cpu_builder
+            }
+        } else {
+            cpu_builder
+        };
+
+        let memory_builder = LinuxMemoryBuilder::default();
+        let memory_builder =
+            if let Some(MemoryController { low, max, .. }) = &memory {
+                let memory_builder = if let Some(low) = low {
+                    memory_builder.reservation(low.into_inner())
+                } else {
+                    memory_builder
+                };
+
+                if let Some(max) = max {
+                    memory_builder.limit(max.into_inner())
+                } else {
+                    memory_builder
+                }
+            } else {
+                memory_builder
+            };
+
+        let cpu = cpu_builder.build().expect("valid cpu builder");
+        let memory_resources =
+            memory_builder.build().expect("valid memory builder");
+        let builder = builder.cpu(cpu).memory(memory_resources);
+
+        // oci_spec has no fields for memory.min and memory.high, and its swap
+        // value is the combined memory + swap limit, so we write those files
+        // directly using the unified map which libcgroups applies as is.
+        let unified = memory.map(get_memory_unified).unwrap_or_default();
+        if unified.is_empty() { builder } else { builder.unified(unified) }
+    } else {
+        builder
+    };
+
+    // io controller
+    let builder = if let Some(io) = io {
+        builder.block_io(get_block_io(io))
+    } else {
+        builder
+    };
+
+    // pids controller
+    let builder = if let Some(PidsController { max: Some(max) }) = pids {
+        let pids = LinuxPidsBuilder::default()
+            .limit(max.into_inner())
+            .build()
+            .expect("valid pids builder");
+        builder.pids(pids)
+    } else {
+        builder
+    };
+
+    builder.build().expect("valid resources")
+}
+
+fn get_memory_unified(memory: MemoryController) -> HashMap<String, String> {
+    let MemoryController { min, low: _, high, max: _, swap_max, swap_high } =
+        memory;
+
+    [
+        ("memory.min", min.map(|x| x.into_inner())),
+        ("memory.high", high.map(|x| x.into_inner())),
+        ("memory.swap.max", swap_max.map(|x| x.into_inner())),
+        ("memory.swap.high", swap_high.map(|x| x.into_inner())),
+    ]
+    .into_iter()
+    .filter_map(|(file, value)| {
+        value.map(|x| (file.to_string(), x.to_string()))
+    })
+    .collect()
+}
+
+fn get_block_io(io: IoController) -> LinuxBlockIo {
+    let IoController { devices } = io;
+
+    // libcgroups writes the weights to io.bfq.weight and the limits to io.max
+    let weight_device = devices
+        .iter()
+        .filter_map(|x| {
+            x.weight.map(|weight| {
+                LinuxWeightDeviceBuilder::default()
+                    .major(x.device.major())
+                    .minor(x.device.minor())
+                    .weight(weight)
+                    .build()
+                    .expect("valid weight device")
+            })
+        })
+        .collect::<Vec<_>>();
+
+    LinuxBlockIoBuilder::default()
+        .weight_device(weight_device)
+        .throttle_read_bps_device(get_throttle_devices(&devices, |x| x.rbps))
+        .throttle_write_bps_device(get_throttle_devices(&devices, |x| x.wbps))
+        .throttle_read_iops_device(get_throttle_devices(&devices, |x| x.riops))
+        .throttle_write_iops_device(get_throttle_devices(&devices, |x| x.wiops))
+        .build()
+        .expect("valid block io builder")
+}
+
+fn get_throttle_devices(
+    devices: &[IoDeviceController],
+    rate: fn(&IoDeviceController) -> Option<u64>,
+) -> Vec<LinuxThrottleDevice> {
+    devices
+        .iter()
+        .filter_map(|x| {
+            rate(x).map(|rate| {
+                LinuxThrottleDeviceBuilder::default()
+                    .major(x.device.major())
+                    .minor(x.device.minor())
+                    .rate(rate)
+                    .build()
+                    .expect("valid throttle device")
+            })
+        })
+        .collect()
+}
+
+fn get_path(cell_name: &CellName) -> PathBuf {
+    let mut path = PathBuf::from_str(DEFAULT_CGROUP_ROOT).expect("valid path");
+    path.push(cell_name.as_inner());
+    path
 }
 
 fn get_leaf_path(cell_name: &CellName) -> PathBuf {
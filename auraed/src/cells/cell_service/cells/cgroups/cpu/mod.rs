@@ -21,3 +21,16 @@ pub struct CpuController {
     pub max: Option<Limit>,
     pub period: Option<u64>,
 }
+
+impl CpuController {
+    /// Returns a new [CpuController] with the values that are set in `update`,
+    /// falling back to the values of `self`.
+    pub fn merge(&self, update: Self) -> Self {
+        let Self { weight, max, period } = update;
+        Self {
+            weight: weight.or(self.weight),
+            max: max.or(self.max),
+            period: period.or(self.period),
+        }
+    }
+}
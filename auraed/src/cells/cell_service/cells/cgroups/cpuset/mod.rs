@@ -24,3 +24,15 @@ pub struct CpusetController {
     pub cpus: Option<Cpus>,
     pub mems: Option<Mems>,
 }
+
+impl CpusetController {
+    /// Returns a new [CpusetController] with the values that are set in `update`,
+    /// falling back to the values of `self`.
+    pub fn merge(&self, update: Self) -> Self {
+        let Self { cpus, mems } = update;
+        Self {
+            cpus: cpus.or_else(|| self.cpus.clone()),
+            mems: mems.or_else(|| self.mems.clone()),
+        }
+    }
+}
@@ -22,10 +22,18 @@ pub type Result<T> = std::result::Result<T, CgroupsError>;
 pub enum CgroupsError {
     #[error("cgroup '{cell_name}' creation failed: {source}")]
     CreateCgroup { cell_name: CellName, source: anyhow::Error },
+    #[error("cgroup '{cell_name}' update failed: {source}")]
+    UpdateCgroup { cell_name: CellName, source: anyhow::Error },
     #[error("cgroup '{cell_name}' failed to add task: {source}")]
     AddTaskToCgroup { cell_name: CellName, source: anyhow::Error },
     #[error("cgroup '{cell_name}' deletion failed: {source}")]
     DeleteCgroup { cell_name: CellName, source: anyhow::Error },
+    #[error("cgroup '{cell_name}' failed to change freezer state: {source}")]
+    FreezeCgroup { cell_name: CellName, source: anyhow::Error },
     #[error("cgroup '{cell_name}' failed to read stats: {source}")]
     ReadStats { cell_name: CellName, source: anyhow::Error },
+    #[error(
+        "cgroup '{cell_name}' failed to register pressure trigger: {source}"
+    )]
+    WatchPressure { cell_name: CellName, source: anyhow::Error },
 }
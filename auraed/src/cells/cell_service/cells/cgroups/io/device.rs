@@ -0,0 +1,109 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use fancy_regex::Regex;
+use lazy_static::lazy_static;
+use std::fmt::{Display, Formatter};
+use validation::{ValidatedField, ValidationError};
+
+lazy_static! {
+    // input should be a block device number in the form of major:minor
+    static ref DEVICE_INPUT_REGEX: Regex = {
+        Regex::new(r"^[0-9]{1,10}:[0-9]{1,10}$").expect("regex construction")
+    };
+}
+
+/// A block device, identified by its major and minor numbers.
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Device {
+    major: i64,
+    minor: i64,
+}
+
+impl Device {
+    #[cfg(test)]
+    pub fn new(major: i64, minor: i64) -> Self {
+        Self { major, minor }
+    }
+
+    pub fn major(&self) -> i64 {
+        self.major
+    }
+
+    pub fn minor(&self) -> i64 {
+        self.minor
+    }
+}
+
+impl ValidatedField<String> for Device {
+    fn validate(
+        input: Option<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Self, ValidationError> {
+        let input =
+            validation::required_not_empty(input, field_name, parent_name)?;
+
+        validation::allow_regex(
+            &input,
+            &DEVICE_INPUT_REGEX,
+            field_name,
+            parent_name,
+        )?;
+
+        let (major, minor) = input.split_once(':').expect("major:minor");
+
+        Ok(Self {
+            major: major.parse().expect("valid major"),
+            minor: minor.parse().expect("valid minor"),
+        })
+    }
+}
+
+impl Display for Device {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.major, self.minor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simple_test_case::test_case;
+
+    #[test_case("8:0"; "disk")]
+    #[test_case("259:12"; "nvme partition")]
+    #[test]
+    fn test_validation_success(input: &str) {
+        let device =
+            Device::validate(Some(input.to_string()), "io_device", None);
+        assert!(device.is_ok());
+        assert_eq!(device.unwrap().to_string(), input);
+    }
+
+    #[test_case(""; "empty string")]
+    #[test_case("8"; "no minor")]
+    #[test_case("8:"; "empty minor")]
+    #[test_case("a:b"; "text")]
+    #[test_case("-1:0"; "negative")]
+    #[test_case("8:0 "; "trailing space")]
+    #[test]
+    fn test_validation_failure(input: &str) {
+        assert!(
+            Device::validate(Some(input.to_string()), "io_device", None)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,66 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+pub use device::Device;
+
+mod device;
+
+#[derive(Debug, Clone)]
+pub struct IoController {
+    pub devices: Vec<IoDeviceController>,
+}
+
+impl IoController {
+    /// Returns a new [IoController] where the devices in `update` are merged
+    /// with the devices of `self` that have the same [Device].
+    pub fn merge(&self, update: Self) -> Self {
+        let mut devices = self.devices.clone();
+
+        for update in update.devices {
+            match devices.iter_mut().find(|x| x.device == update.device) {
+                Some(device) => *device = device.merge(update),
+                None => devices.push(update),
+            }
+        }
+
+        Self { devices }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IoDeviceController {
+    pub device: Device,
+    pub weight: Option<u16>,
+    pub rbps: Option<u64>,
+    pub wbps: Option<u64>,
+    pub riops: Option<u64>,
+    pub wiops: Option<u64>,
+}
+
+impl IoDeviceController {
+    /// Returns a new [IoDeviceController] with the values that are set in `update`,
+    /// falling back to the values of `self`.
+    pub fn merge(&self, update: Self) -> Self {
+        let Self { device, weight, rbps, wbps, riops, wiops } = update;
+        Self {
+            device,
+            weight: weight.or(self.weight),
+            rbps: rbps.or(self.rbps),
+            wbps: wbps.or(self.wbps),
+            riops: riops.or(self.riops),
+            wiops: wiops.or(self.wiops),
+        }
+    }
+}
@@ -21,4 +21,99 @@ pub struct MemoryController {
     pub low: Option<Protection>,
     pub high: Option<Limit>,
     pub max: Option<Limit>,
+    pub swap_max: Option<Limit>,
+    pub swap_high: Option<Limit>,
+}
+
+impl MemoryController {
+    /// Returns a new [MemoryController] with the values that are set in `update`,
+    /// falling back to the values of `self`.
+    pub fn merge(&self, update: Self) -> Self {
+        let Self { min, low, high, max, swap_max, swap_high } = update;
+        Self {
+            min: min.or(self.min),
+            low: low.or(self.low),
+            high: high.or(self.high),
+            max: max.or(self.max),
+            swap_max: swap_max.or(self.swap_max),
+            swap_high: swap_high.or(self.swap_high),
+        }
+    }
+
+    /// Returns the first boundary that is below the boundary preceding it.
+    /// The boundaries that are set must satisfy: min <= low <= high <= max
+    pub fn unordered_boundary(&self) -> Option<UnorderedBoundary> {
+        let boundaries = [
+            ("min", self.min.map(|x| *x)),
+            ("low", self.low.map(|x| *x)),
+            ("high", self.high.map(|x| *x)),
+            ("max", self.max.map(|x| *x)),
+        ];
+
+        let mut lower: Option<(&'static str, i64)> = None;
+        for (name, value) in boundaries {
+            let Some(value) = value else {
+                continue;
+            };
+
+            if let Some((lower_name, lower_value)) = lower
+                && value < lower_value
+            {
+                return Some(UnorderedBoundary {
+                    name,
+                    value,
+                    lower_name,
+                    lower_value,
+                });
+            }
+
+            lower = Some((name, value));
+        }
+
+        None
+    }
+}
+
+/// A boundary of a [MemoryController] that is below a lower boundary
+/// (e.g., `high` below `low`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnorderedBoundary {
+    pub name: &'static str,
+    pub value: i64,
+    pub lower_name: &'static str,
+    pub lower_value: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(min: i64, high: Option<i64>) -> MemoryController {
+        MemoryController {
+            min: Some(Protection::new(min)),
+            low: None,
+            high: high.map(Limit::new),
+            max: None,
+            swap_max: None,
+            swap_high: None,
+        }
+    }
+
+    #[test]
+    fn merge_should_check_the_merged_boundaries() {
+        let current = memory(100, Some(200));
+        assert_eq!(current.unordered_boundary(), None);
+
+        // A partial update that is valid on its own
+        let merged = current.merge(memory(300, None));
+        assert_eq!(
+            merged.unordered_boundary(),
+            Some(UnorderedBoundary {
+                name: "high",
+                value: 200,
+                lower_name: "min",
+                lower_value: 300,
+            })
+        );
+    }
 }
@@ -16,15 +16,20 @@
 pub use cgroup::Cgroup;
 pub use cpu::CpuController;
 pub use cpuset::CpusetController;
+pub use io::{IoController, IoDeviceController};
 pub use limit::Limit;
 pub use memory::MemoryController;
+pub use pids::PidsController;
 pub use protection::Protection;
 pub use weight::Weight;
 
 pub mod cpu;
 pub mod cpuset;
 pub mod error;
+pub mod io;
 pub mod memory;
+pub mod pids;
+pub mod pressure;
 
 mod allocation;
 mod cgroup;
@@ -37,4 +42,33 @@ pub struct CgroupSpec {
     pub cpu: Option<CpuController>,
     pub cpuset: Option<CpusetController>,
     pub memory: Option<MemoryController>,
+    pub io: Option<IoController>,
+    pub pids: Option<PidsController>,
+}
+
+impl CgroupSpec {
+    /// Returns a new [CgroupSpec] with the controllers and values that are set in `update`,
+    /// falling back to the controllers and values of `self`.
+    pub fn merge(&self, update: Self) -> Self {
+        let Self { cpu, cpuset, memory, io, pids } = update;
+        Self {
+            cpu: merge(&self.cpu, cpu, CpuController::merge),
+            cpuset: merge(&self.cpuset, cpuset, CpusetController::merge),
+            memory: merge(&self.memory, memory, MemoryController::merge),
+            io: merge(&self.io, io, IoController::merge),
+            pids: merge(&self.pids, pids, PidsController::merge),
+        }
+    }
+}
+
+fn merge<T: Clone>(
+    current: &Option<T>,
+    update: Option<T>,
+    f: fn(&T, T) -> T,
+) -> Option<T> {
+    match (current, update) {
+        (Some(current), Some(update)) => Some(f(current, update)),
+        (None, Some(update)) => Some(update),
+        (current, None) => current.clone(),
+    }
 }
@@ -0,0 +1,84 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use std::{
+    fmt::{Display, Formatter},
+    ops::Deref,
+};
+use validation::{ValidatedField, ValidationError};
+
+/// The maximum number of processes (and threads) in a cgroup (pids.max).
+#[derive(Debug, Clone, Copy, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct PidsMax(i64);
+
+impl PidsMax {
+    #[cfg(test)]
+    pub fn new(max: i64) -> Self {
+        Self(max)
+    }
+
+    pub fn into_inner(self) -> i64 {
+        self.0
+    }
+}
+
+impl ValidatedField<i64> for PidsMax {
+    fn validate(
+        input: Option<i64>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Self, ValidationError> {
+        let input = validation::required(input, field_name, parent_name)?;
+
+        // libcgroups interprets a value <= 0 as "max" (no limit)
+        validation::minimum_value(input, 1, "unit", field_name, parent_name)?;
+
+        Ok(Self(input))
+    }
+}
+
+impl Deref for PidsMax {
+    type Target = i64;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Display for PidsMax {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use simple_test_case::test_case;
+
+    #[test_case(1; "minimum")]
+    #[test_case(4096; "typical")]
+    #[test]
+    fn test_validation_success(input: i64) {
+        assert!(PidsMax::validate(Some(input), "max", None).is_ok());
+    }
+
+    #[test_case(0; "zero")]
+    #[test_case(-1; "negative")]
+    #[test]
+    fn test_validation_failure(input: i64) {
+        assert!(PidsMax::validate(Some(input), "max", None).is_err());
+    }
+}
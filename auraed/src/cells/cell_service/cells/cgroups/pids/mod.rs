@@ -0,0 +1,32 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+pub use max::PidsMax;
+
+mod max;
+
+#[derive(Debug, Clone)]
+pub struct PidsController {
+    pub max: Option<PidsMax>,
+}
+
+impl PidsController {
+    /// Returns a new [PidsController] with the values that are set in `update`,
+    /// falling back to the values of `self`.
+    pub fn merge(&self, update: Self) -> Self {
+        let Self { max } = update;
+        Self { max: max.or(self.max) }
+    }
+}
@@ -0,0 +1,228 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::Interest;
+use tokio::io::unix::AsyncFd;
+
+/// The resources for which the kernel tracks Pressure Stall Information (PSI).
+/// Docs: https://docs.kernel.org/accounting/psi.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureResource {
+    Cpu,
+    Memory,
+    Io,
+}
+
+impl PressureResource {
+    /// The cgroup interface file of the resource.
+    pub fn file(&self) -> &'static str {
+        match self {
+            PressureResource::Cpu => "cpu.pressure",
+            PressureResource::Memory => "memory.pressure",
+            PressureResource::Io => "io.pressure",
+        }
+    }
+}
+
+/// "some" tracks the time in which at least one task is stalled, "full" the
+/// time in which all non-idle tasks are stalled simultaneously.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PressureKind {
+    Some,
+    Full,
+}
+
+impl Display for PressureKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PressureKind::Some => write!(f, "some"),
+            PressureKind::Full => write!(f, "full"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureValues {
+    /// Percentage of stalled time in the last 10 seconds
+    pub avg10: f64,
+    /// Percentage of stalled time in the last 60 seconds
+    pub avg60: f64,
+    /// Percentage of stalled time in the last 300 seconds
+    pub avg300: f64,
+    /// Total stalled time in microseconds
+    pub total: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pressure {
+    pub some: PressureValues,
+    /// Not reported for cpu.pressure by kernels < 5.13
+    pub full: Option<PressureValues>,
+}
+
+impl Pressure {
+    /// Parses the content of a pressure file, e.g.:
+    ///
+    /// ```text
+    /// some avg10=0.00 avg60=0.00 avg300=0.00 total=0
+    /// full avg10=0.00 avg60=0.00 avg300=0.00 total=0
+    /// ```
+    pub fn parse(content: &str) -> Option<Self> {
+        let mut some = None;
+        let mut full = None;
+
+        for line in content.lines() {
+            let Some((kind, values)) = line.split_once(' ') else {
+                continue;
+            };
+
+            match kind {
+                "some" => some = Some(parse_values(values)?),
+                "full" => full = Some(parse_values(values)?),
+                _ => {}
+            }
+        }
+
+        Some(Self { some: some?, full })
+    }
+}
+
+fn parse_values(values: &str) -> Option<PressureValues> {
+    let mut output = PressureValues::default();
+
+    for pair in values.split_whitespace() {
+        let (key, value) = pair.split_once('=')?;
+        match key {
+            "avg10" => output.avg10 = value.parse().ok()?,
+            "avg60" => output.avg60 = value.parse().ok()?,
+            "avg300" => output.avg300 = value.parse().ok()?,
+            "total" => output.total = value.parse().ok()?,
+            _ => {}
+        }
+    }
+
+    Some(output)
+}
+
+/// A threshold of stalled time within a time window, e.g., "memory some 150ms/1s".
+#[derive(Debug, Clone, Copy)]
+pub struct PressureTrigger {
+    pub resource: PressureResource,
+    pub kind: PressureKind,
+    pub stall: Duration,
+    pub window: Duration,
+}
+
+/// A registered [PressureTrigger].
+/// The trigger is unregistered by the kernel when this is dropped.
+#[derive(Debug)]
+pub struct PressureWatch {
+    fd: AsyncFd<File>,
+    cgroup_path: PathBuf,
+}
+
+impl PressureWatch {
+    /// Registers the trigger with the pressure file of the cgroup at `cgroup_path`.
+    pub(crate) fn new(
+        cgroup_path: &Path,
+        trigger: &PressureTrigger,
+    ) -> io::Result<Self> {
+        let PressureTrigger { resource, kind, stall, window } = trigger;
+
+        let mut file = File::options()
+            .read(true)
+            .write(true)
+            .open(cgroup_path.join(resource.file()))?;
+
+        // The kernel expects "<some|full> <stall in us> <window in us>"
+        file.write_all(
+            format!("{kind} {} {}\0", stall.as_micros(), window.as_micros())
+                .as_bytes(),
+        )?;
+
+        // The kernel signals a crossed threshold with POLLPRI
+        let fd = AsyncFd::with_interest(file, Interest::PRIORITY)?;
+
+        Ok(Self { fd, cgroup_path: cgroup_path.to_path_buf() })
+    }
+
+    /// Waits until the threshold of the trigger has been crossed.
+    /// The kernel signals at most once per window.
+    ///
+    /// Returns false if the cgroup has been removed, after which the watch will
+    /// not be signaled again.
+    pub async fn crossed(&self) -> io::Result<bool> {
+        let mut guard = self.fd.ready(Interest::PRIORITY).await?;
+        guard.clear_ready();
+
+        Ok(self.cgroup_path.exists())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_must_parse_some_and_full() {
+        let pressure = Pressure::parse(
+            "some avg10=1.50 avg60=0.25 avg300=0.00 total=12345\nfull avg10=0.10 avg60=0.00 avg300=0.00 total=678\n",
+        )
+        .expect("valid pressure");
+
+        assert_eq!(
+            pressure.some,
+            PressureValues {
+                avg10: 1.5,
+                avg60: 0.25,
+                avg300: 0.0,
+                total: 12345
+            }
+        );
+        assert_eq!(
+            pressure.full,
+            Some(PressureValues {
+                avg10: 0.1,
+                avg60: 0.0,
+                avg300: 0.0,
+                total: 678
+            })
+        );
+    }
+
+    #[test]
+    fn parse_must_allow_missing_full() {
+        let pressure =
+            Pressure::parse("some avg10=0.00 avg60=0.00 avg300=0.00 total=0\n")
+                .expect("valid pressure");
+
+        assert_eq!(pressure.some, PressureValues::default());
+        assert_eq!(pressure.full, None);
+    }
+
+    #[test]
+    fn parse_must_reject_invalid_values() {
+        assert!(Pressure::parse("").is_none());
+        assert!(
+            Pressure::parse("some avg10=abc avg60=0.00 avg300=0.00 total=0\n")
+                .is_none()
+        );
+    }
+}
@@ -30,12 +30,38 @@ pub enum CellsError {
     CellNotAllocated { cell_name: CellName },
     #[error("cell '{cell_name}' could not be allocated: {source}")]
     FailedToAllocateCell { cell_name: CellName, source: io::Error },
+    #[error("cell '{cell_name}' could not be adopted: {source}")]
+    FailedToAdoptCell { cell_name: CellName, source: io::Error },
     #[error("cell '{cell_name}' allocation was aborted: {source}")]
     AbortedAllocateCell { cell_name: CellName, source: CgroupsError },
     #[error("cell '{cell_name}' could not kill children: {source}")]
     FailedToKillCellChildren { cell_name: CellName, source: io::Error },
     #[error("cell '{cell_name}' could not be freed: {source}")]
     FailedToFreeCell { cell_name: CellName, source: CgroupsError },
+    #[error("cell '{cell_name}' could not be updated: {source}")]
+    FailedToUpdateCell { cell_name: CellName, source: CgroupsError },
+    #[error("cell '{cell_name}' isolation controls can not be updated")]
+    IsolationControlsCannotBeUpdated { cell_name: CellName },
+    #[error(
+        "cell '{cell_name}' memory.{name} ({value}) can not be below memory.{lower_name} ({lower_value})"
+    )]
+    UnorderedMemoryBoundaries {
+        cell_name: CellName,
+        name: &'static str,
+        value: i64,
+        lower_name: &'static str,
+        lower_value: i64,
+    },
+    #[error("cell '{cell_name}' is paused")]
+    CellPaused { cell_name: CellName },
+    #[error("cell '{cell_name}' could not be paused: {source}")]
+    FailedToPauseCell { cell_name: CellName, source: CgroupsError },
+    #[error("cell '{cell_name}' could not be resumed: {source}")]
+    FailedToResumeCell { cell_name: CellName, source: CgroupsError },
+    #[error("cell '{cell_name}' stats could not be read: {source}")]
+    FailedToReadCellStats { cell_name: CellName, source: CgroupsError },
+    #[error("cell '{cell_name}' pressure could not be watched: {source}")]
+    FailedToWatchCellPressure { cell_name: CellName, source: CgroupsError },
     #[error(
         "cgroup '{cell_name}' exists on host, but is not controlled by auraed"
     )]
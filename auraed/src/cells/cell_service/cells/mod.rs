@@ -19,7 +19,10 @@ pub use cells::Cells;
 pub use cells_cache::CellsCache;
 use cgroups::CgroupSpec;
 pub use error::{CellsError, Result};
-pub use nested_auraed::IsolationControls;
+pub use nested_auraed::{
+    IdMapping, IsolationControls, Mount, MountKind, MountPropagation,
+    NestedAuraedState, RootFs,
+};
 
 mod cell;
 mod cell_name;
@@ -56,11 +59,21 @@ impl CellSpec {
                     low: None,
                     high: None,
                     max: Some(Limit::new(1000000)),
+                    swap_max: None,
+                    swap_high: None,
                 }),
+                io: None,
+                pids: None,
             },
             iso_ctl: IsolationControls {
                 isolate_network: false,
                 isolate_process: false,
+                isolate_user: false,
+                uid_mappings: vec![],
+                gid_mappings: vec![],
+                rootfs: None,
+                mounts: vec![],
+                seccomp_profile: None,
             },
         }
     }
@@ -13,25 +13,126 @@
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
 
+use super::network::CellNetwork;
+use crate::cells::cell_service::seccomp::SeccompProfile;
+use nix::errno::Errno;
 use nix::libc::{c_char, setdomainname};
+use nix::mount::{MntFlags, MsFlags};
+use nix::unistd::{Gid, Uid};
+use std::collections::VecDeque;
+use std::ffi::OsString;
+use std::fs;
 use std::io;
-use std::path::PathBuf;
+use std::os::unix::fs::symlink;
+use std::path::{Component, Path, PathBuf};
 use tracing::info;
 
-#[derive(Debug, Clone, Default)]
+/// The devices bind mounted from the host into the /dev of a root
+/// filesystem, as they can not be created in a user namespace.
+const DEVICES: [&str; 6] = ["null", "zero", "full", "random", "urandom", "tty"];
+
+/// The number of symlinks a mount point is resolved through, as for a path
+/// (see path_resolution(7)).
+const MAX_SYMLINKS: usize = 40;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct IsolationControls {
     pub isolate_process: bool,
     pub isolate_network: bool,
+    pub isolate_user: bool,
+    pub uid_mappings: Vec<IdMapping>,
+    pub gid_mappings: Vec<IdMapping>,
+    pub rootfs: Option<RootFs>,
+    pub mounts: Vec<Mount>,
+    pub seccomp_profile: Option<SeccompProfile>,
+}
+
+/// The root filesystem of a cell, pivoted into by the nested auraed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RootFs {
+    /// A directory, or an unpacked OCI image
+    pub path: PathBuf,
+    /// Where the changes are written with an overlay, if set
+    pub upper_dir: Option<PathBuf>,
+}
+
+/// A mount of a cell, in its mount namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    /// Where the mount is mounted, in the cell
+    pub target: PathBuf,
+    pub kind: MountKind,
+    pub read_only: bool,
+    pub propagation: MountPropagation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MountKind {
+    /// A file or directory of the host
+    Bind { source: PathBuf },
+    /// A tmpfs, limited to half of the memory if no size is set
+    Tmpfs { size: Option<u64> },
+}
+
+/// See mount_namespaces(7).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MountPropagation {
+    Private,
+    #[default]
+    RPrivate,
+    Slave,
+    RSlave,
+    Shared,
+    RShared,
+}
+
+impl MountPropagation {
+    fn flags(self) -> MsFlags {
+        match self {
+            Self::Private => MsFlags::MS_PRIVATE,
+            Self::RPrivate => MsFlags::MS_PRIVATE | MsFlags::MS_REC,
+            Self::Slave => MsFlags::MS_SLAVE,
+            Self::RSlave => MsFlags::MS_SLAVE | MsFlags::MS_REC,
+            Self::Shared => MsFlags::MS_SHARED,
+            Self::RShared => MsFlags::MS_SHARED | MsFlags::MS_REC,
+        }
+    }
+}
+
+/// A range of ids of a cell mapped to a range of ids of the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdMapping {
+    pub container_id: u32,
+    pub host_id: u32,
+    pub size: u32,
 }
 
 #[derive(Default)]
 pub(crate) struct Isolation {
     name: String,
+    /// The directories of the host bind mounted into the root filesystem,
+    /// and whether they are read only
+    shared_dirs: Vec<(PathBuf, bool)>,
+    /// The root filesystem, once prepared by [Isolation::setup]
+    rootfs: Option<PreparedRootFs>,
+}
+
+/// A [RootFs] whose directories have been resolved and created.
+struct PreparedRootFs {
+    root: PathBuf,
+    /// The upper and work directories of the overlay
+    overlay: Option<(PathBuf, PathBuf)>,
 }
 
 impl Isolation {
     pub fn new(name: String) -> Isolation {
-        Isolation { name }
+        Isolation { name, ..Default::default() }
+    }
+
+    /// Shares a directory of the host with the cell, at the same path, if it
+    /// has a root filesystem.
+    pub fn share_dir(&mut self, dir: PathBuf, read_only: bool) {
+        self.shared_dirs.push((dir, read_only));
     }
     pub fn setup(&mut self, iso_ctl: &IsolationControls) -> io::Result<()> {
         // The only setup we will need to do is for isolate_process at this time.
@@ -50,6 +151,34 @@ impl Isolation {
             None::<&str>,
         )?;
         info!("Isolation: Mounted root dir (/) in cell");
+
+        if let Some(rootfs) = &iso_ctl.rootfs {
+            self.rootfs = Some(prepare_rootfs(rootfs, iso_ctl)?);
+        }
+
+        // Fail before spawning the nested auraed
+        for mount in &iso_ctl.mounts {
+            if let MountKind::Bind { source } = &mount.kind
+                && !source.exists()
+            {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("bind mount source {} not found", source.display()),
+                ));
+            }
+
+            // Mount points are only created in a root filesystem, never on
+            // the host
+            if iso_ctl.rootfs.is_none() && !mount.target.exists() {
+                return Err(io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!(
+                        "mount point {} not found on the host",
+                        mount.target.display()
+                    ),
+                ));
+            }
+        }
         Ok(())
     }
 
@@ -61,15 +190,33 @@ impl Isolation {
             return Ok(());
         }
 
-        // Mount proc in the new pid and mount namespace
-        let target = PathBuf::from("/proc");
-        nix::mount::mount(
-            Some("/proc"),
-            &target,
-            Some("proc"),
-            nix::mount::MsFlags::empty(),
-            None::<&str>,
-        )?;
+        match &self.rootfs {
+            Some(rootfs) => {
+                // Keep the mounts of the cell from propagating to the host
+                nix::mount::mount(
+                    None::<&str>,
+                    "/",
+                    None::<&str>,
+                    MsFlags::MS_PRIVATE | MsFlags::MS_REC,
+                    None::<&str>,
+                )?;
+                mount_rootfs(rootfs, &self.shared_dirs)?;
+                mount_all(&iso_ctl.mounts, Some(&rootfs.root))?;
+                pivot_root(&rootfs.root)?;
+            }
+            None => {
+                // Mount proc in the new pid and mount namespace
+                let target = PathBuf::from("/proc");
+                nix::mount::mount(
+                    Some("/proc"),
+                    &target,
+                    Some("proc"),
+                    nix::mount::MsFlags::empty(),
+                    None::<&str>,
+                )?;
+                mount_all(&iso_ctl.mounts, None)?;
+            }
+        }
 
         // We are in a new UTS namespace so we manage hostname and domainname.
         nix::unistd::sethostname(&self.name)?;
@@ -85,14 +232,452 @@ impl Isolation {
         Ok(())
     }
 
-    pub fn isolate_network(
+    /// Maps the users and groups of the user namespace of the process.
+    /// This is done by the parent, as a process can not map the ids of its
+    /// own namespace once it is in it.
+    pub fn map_users(
+        &self,
+        pid: i32,
+        iso_ctl: &IsolationControls,
+    ) -> io::Result<()> {
+        if !iso_ctl.isolate_user {
+            return Ok(());
+        }
+
+        write_id_map(pid, "uid_map", &iso_ctl.uid_mappings)?;
+        write_id_map(pid, "gid_map", &iso_ctl.gid_mappings)?;
+        info!("Isolation: Mapped users and groups of pid {pid}");
+        Ok(())
+    }
+
+    /// Switches to the root of the user namespace, once mapped with
+    /// [Isolation::map_users].
+    pub fn isolate_user(
         &mut self,
         iso_ctl: &IsolationControls,
     ) -> io::Result<()> {
-        if !iso_ctl.isolate_network {
+        if !iso_ctl.isolate_user {
             return Ok(());
         }
-        // Insert pre_exec network logic here
+
+        // The ids inherited from the host are not mapped in the namespace,
+        // and the capabilities of the process would be dropped on exec
+        let root_uid = Uid::from_raw(0);
+        let root_gid = Gid::from_raw(0);
+        nix::unistd::setgroups(&[])?;
+        nix::unistd::setresgid(root_gid, root_gid, root_gid)?;
+        nix::unistd::setresuid(root_uid, root_uid, root_uid)?;
         Ok(())
     }
+
+    /// Connects the network namespace of the process to the host.
+    /// This is done by the parent, as the host end of the veth pair has to
+    /// stay in the network namespace of the host.
+    pub fn isolate_network(
+        &self,
+        pid: i32,
+        iso_ctl: &IsolationControls,
+    ) -> io::Result<Option<CellNetwork>> {
+        if !iso_ctl.isolate_network {
+            return Ok(None);
+        }
+
+        CellNetwork::connect(pid).map(Some)
+    }
+}
+
+/// Resolves the root directory of the [RootFs], and creates the directories
+/// of its overlay. The directories are owned by the root of the user
+/// namespace, if any, for it to write to the overlay.
+fn prepare_rootfs(
+    rootfs: &RootFs,
+    iso_ctl: &IsolationControls,
+) -> io::Result<PreparedRootFs> {
+    let RootFs { path, upper_dir } = rootfs;
+
+    // An unpacked OCI image has its root filesystem next to its config
+    let root = if path.join("config.json").is_file() {
+        path.join("rootfs")
+    } else {
+        path.clone()
+    };
+    if !root.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("root filesystem {} is not a directory", root.display()),
+        ));
+    }
+
+    let overlay = match upper_dir {
+        Some(upper_dir) => {
+            let mut work_dir = upper_dir.clone().into_os_string();
+            work_dir.push(".work");
+            let work_dir = PathBuf::from(work_dir);
+
+            for dir in [upper_dir, &work_dir] {
+                fs::create_dir_all(dir)?;
+                chown_to_user_namespace_root(dir, iso_ctl)?;
+            }
+            Some((upper_dir.clone(), work_dir))
+        }
+        None => None,
+    };
+
+    Ok(PreparedRootFs { root, overlay })
+}
+
+/// Gives the directory to the root of the user namespace of the cell, if
+/// any, as it is mapped to an unprivileged user of the host that could not
+/// write to it otherwise.
+pub(super) fn chown_to_user_namespace_root(
+    dir: &Path,
+    iso_ctl: &IsolationControls,
+) -> io::Result<()> {
+    if !iso_ctl.isolate_user {
+        return Ok(());
+    }
+
+    nix::unistd::chown(
+        dir,
+        host_root(&iso_ctl.uid_mappings).map(Uid::from_raw),
+        host_root(&iso_ctl.gid_mappings).map(Gid::from_raw),
+    )?;
+    Ok(())
+}
+
+/// The id of the host mapped to the root (0) of the user namespace.
+fn host_root(mappings: &[IdMapping]) -> Option<u32> {
+    mappings.iter().find(|x| x.container_id == 0).map(|x| x.host_id)
+}
+
+/// Mounts the root filesystem onto its root directory (a mount point is
+/// required to pivot into), then mounts /proc, /dev, /sys and the shared
+/// directories in it.
+fn mount_rootfs(
+    rootfs: &PreparedRootFs,
+    shared_dirs: &[(PathBuf, bool)],
+) -> io::Result<()> {
+    let PreparedRootFs { root, overlay } = rootfs;
+
+    match overlay {
+        Some((upper_dir, work_dir)) => {
+            let options = format!(
+                "lowerdir={},upperdir={},workdir={}",
+                root.display(),
+                upper_dir.display(),
+                work_dir.display()
+            );
+            nix::mount::mount(
+                Some("overlay"),
+                root,
+                Some("overlay"),
+                MsFlags::empty(),
+                Some(options.as_str()),
+            )?;
+        }
+        None => {
+            nix::mount::mount(
+                Some(root),
+                root,
+                None::<&str>,
+                MsFlags::MS_BIND | MsFlags::MS_REC,
+                None::<&str>,
+            )?;
+        }
+    }
+
+    // The pid namespace is new, so is the proc filesystem
+    let proc = create_mount_point(root, Path::new("proc"), true)?;
+    nix::mount::mount(
+        Some("proc"),
+        &proc,
+        Some("proc"),
+        MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
+        None::<&str>,
+    )?;
+
+    mount_dev(&create_mount_point(root, Path::new("dev"), true)?)?;
+
+    let sys = create_mount_point(root, Path::new("sys"), true)?;
+    let sys_flags = MsFlags::MS_RDONLY
+        | MsFlags::MS_NOSUID
+        | MsFlags::MS_NODEV
+        | MsFlags::MS_NOEXEC;
+    // Only the owner of the network namespace can mount a sysfs
+    if nix::mount::mount(
+        Some("sysfs"),
+        &sys,
+        Some("sysfs"),
+        sys_flags,
+        None::<&str>,
+    )
+    .is_err()
+    {
+        bind_mount(Path::new("/sys"), &sys, true)?;
+    }
+
+    for (dir, read_only) in shared_dirs {
+        let target = create_mount_point(root, dir, true)?;
+        bind_mount(dir, &target, *read_only)?;
+    }
+
+    info!("Isolation: Mounted root filesystem {}", root.display());
+    Ok(())
+}
+
+/// Mounts the [Mount]s of a cell under the root directory, if any, in order.
+/// Without a root directory, the mounts are mounted on the existing mount
+/// points of the host.
+fn mount_all(mounts: &[Mount], root: Option<&Path>) -> io::Result<()> {
+    for Mount { target, kind, read_only, propagation } in mounts {
+        let is_dir = match kind {
+            MountKind::Bind { source } => source.is_dir(),
+            MountKind::Tmpfs { .. } => true,
+        };
+        let target = match root {
+            Some(root) => create_mount_point(root, target, is_dir)?,
+            None => target.clone(),
+        };
+
+        match kind {
+            MountKind::Bind { source } => {
+                bind_mount(source, &target, *read_only)?;
+            }
+            MountKind::Tmpfs { size } => {
+                let mut flags = MsFlags::MS_NOSUID | MsFlags::MS_NODEV;
+                if *read_only {
+                    flags |= MsFlags::MS_RDONLY;
+                }
+                let options = size.map(|x| format!("size={x}"));
+                nix::mount::mount(
+                    Some("tmpfs"),
+                    &target,
+                    Some("tmpfs"),
+                    flags,
+                    options.as_deref(),
+                )?;
+            }
+        }
+
+        nix::mount::mount(
+            None::<&str>,
+            &target,
+            None::<&str>,
+            propagation.flags(),
+            None::<&str>,
+        )?;
+        info!("Isolation: Mounted {}", target.display());
+    }
+
+    Ok(())
+}
+
+/// Returns the path of the target in the root directory, creating the
+/// directory, or the file, a mount is mounted at, and its parents, if
+/// missing. Symlinks are resolved in the root directory, as they would be
+/// once pivoted into it, so the path can not escape it.
+fn create_mount_point(
+    root: &Path,
+    target: &Path,
+    is_dir: bool,
+) -> io::Result<PathBuf> {
+    let mut path = root.to_path_buf();
+    let mut remaining: VecDeque<OsString> = components(target).collect();
+    let mut symlinks = 0;
+
+    while let Some(name) = remaining.pop_front() {
+        if name == ".." {
+            // The root directory is its own parent
+            if path != root {
+                let _ = path.pop();
+            }
+            continue;
+        }
+        path.push(&name);
+
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_symlink() => {
+                symlinks += 1;
+                if symlinks > MAX_SYMLINKS {
+                    return Err(Errno::ELOOP.into());
+                }
+
+                let link = fs::read_link(&path)?;
+                let _ = path.pop();
+                if link.is_absolute() {
+                    path = root.to_path_buf();
+                }
+                for name in components(&link).rev() {
+                    remaining.push_front(name);
+                }
+            }
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                // Neither creating follows a symlink created meanwhile
+                if remaining.is_empty() && !is_dir {
+                    let _ = fs::File::create_new(&path)?;
+                } else {
+                    fs::create_dir(&path)?;
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(path)
+}
+
+/// The names of the components of the path, `..` included.
+fn components(path: &Path) -> impl DoubleEndedIterator<Item = OsString> {
+    path.components().filter_map(|x| match x {
+        Component::Normal(name) => Some(name.to_os_string()),
+        Component::ParentDir => Some(OsString::from("..")),
+        _ => None,
+    })
+}
+
+/// Mounts a minimal /dev, with the [DEVICES] of the host, a new instance of
+/// devpts and a /dev/shm.
+fn mount_dev(dev: &Path) -> io::Result<()> {
+    fs::create_dir_all(dev)?;
+    nix::mount::mount(
+        Some("tmpfs"),
+        dev,
+        Some("tmpfs"),
+        MsFlags::MS_NOSUID | MsFlags::MS_STRICTATIME,
+        Some("mode=755,size=65536k"),
+    )?;
+
+    for device in DEVICES {
+        let target = dev.join(device);
+        let _ = fs::File::create(&target)?;
+        nix::mount::mount(
+            Some(&Path::new("/dev").join(device)),
+            &target,
+            None::<&str>,
+            MsFlags::MS_BIND,
+            None::<&str>,
+        )?;
+    }
+
+    let pts = dev.join("pts");
+    fs::create_dir_all(&pts)?;
+    nix::mount::mount(
+        Some("devpts"),
+        &pts,
+        Some("devpts"),
+        MsFlags::MS_NOSUID | MsFlags::MS_NOEXEC,
+        Some("newinstance,ptmxmode=0666,mode=0620"),
+    )?;
+    symlink("pts/ptmx", dev.join("ptmx"))?;
+
+    let shm = dev.join("shm");
+    fs::create_dir_all(&shm)?;
+    nix::mount::mount(
+        Some("shm"),
+        &shm,
+        Some("tmpfs"),
+        MsFlags::MS_NOSUID | MsFlags::MS_NODEV | MsFlags::MS_NOEXEC,
+        Some("mode=1777,size=65536k"),
+    )?;
+
+    for (link, target) in [
+        ("fd", "/proc/self/fd"),
+        ("stdin", "/proc/self/fd/0"),
+        ("stdout", "/proc/self/fd/1"),
+        ("stderr", "/proc/self/fd/2"),
+    ] {
+        symlink(target, dev.join(link))?;
+    }
+
+    Ok(())
+}
+
+/// Bind mounts the directory, recursively. A read only bind mount has to be
+/// remounted, as the flags of the first mount are ignored.
+fn bind_mount(source: &Path, target: &Path, read_only: bool) -> io::Result<()> {
+    nix::mount::mount(
+        Some(source),
+        target,
+        None::<&str>,
+        MsFlags::MS_BIND | MsFlags::MS_REC,
+        None::<&str>,
+    )?;
+
+    if read_only {
+        nix::mount::mount(
+            None::<&str>,
+            target,
+            None::<&str>,
+            MsFlags::MS_BIND | MsFlags::MS_REMOUNT | MsFlags::MS_RDONLY,
+            None::<&str>,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Makes the root directory the root of the mount namespace, and detaches the
+/// root of the host, stacked under it by pivoting into the same directory.
+fn pivot_root(root: &Path) -> io::Result<()> {
+    std::env::set_current_dir(root)?;
+    nix::unistd::pivot_root(".", ".")?;
+    nix::mount::umount2(".", MntFlags::MNT_DETACH)?;
+    std::env::set_current_dir("/")?;
+    info!("Isolation: Pivoted into {}", root.display());
+    Ok(())
+}
+
+/// Writes the mappings to /proc/<pid>/<file>, which must be done at once.
+fn write_id_map(
+    pid: i32,
+    file: &str,
+    mappings: &[IdMapping],
+) -> io::Result<()> {
+    let map: String = mappings
+        .iter()
+        .map(|IdMapping { container_id, host_id, size }| {
+            format!("{container_id} {host_id} {size}\n")
+        })
+        .collect();
+
+    std::fs::write(format!("/proc/{pid}/{file}"), map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn create_mount_point_should_create_the_missing_mount_point() {
+        let root = tempfile::tempdir().expect("tempdir");
+
+        let dir = create_mount_point(root.path(), Path::new("/a/b"), true)
+            .expect("dir");
+        assert_eq!(dir, root.path().join("a/b"));
+        assert!(dir.is_dir());
+
+        let file = create_mount_point(root.path(), Path::new("/a/c"), false)
+            .expect("file");
+        assert!(file.is_file());
+    }
+
+    #[test]
+    fn create_mount_point_should_resolve_symlinks_in_the_root() {
+        let root = tempfile::tempdir().expect("tempdir");
+        let host = tempfile::tempdir().expect("tempdir");
+        symlink(host.path(), root.path().join("absolute")).expect("symlink");
+        symlink("../../..", root.path().join("relative")).expect("symlink");
+
+        let dir =
+            create_mount_point(root.path(), Path::new("/absolute/a"), true)
+                .expect("dir");
+        assert!(dir.starts_with(root.path()));
+        assert!(!host.path().join("a").exists());
+
+        let dir =
+            create_mount_point(root.path(), Path::new("/relative/b"), true)
+                .expect("dir");
+        assert_eq!(dir, root.path().join("b"));
+    }
 }
@@ -13,9 +13,12 @@
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
 
-pub use isolation_controls::IsolationControls;
-pub use nested_auraed::NestedAuraed;
+pub use isolation_controls::{
+    IdMapping, IsolationControls, Mount, MountKind, MountPropagation, RootFs,
+};
+pub use nested_auraed::{NestedAuraed, NestedAuraedState};
 
 mod isolation_controls;
 #[allow(clippy::module_inception)]
 mod nested_auraed;
+mod network;
@@ -13,71 +13,136 @@
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
 
-use super::isolation_controls::{Isolation, IsolationControls};
+use super::isolation_controls::{
+    Isolation, IsolationControls, chown_to_user_namespace_root,
+};
+use super::network::CellNetwork;
 use crate::AURAED_RUNTIME;
+use crate::cells::CellName;
 use client::AuraeSocket;
 use clone3::Flags;
+use ipnetwork::IpNetwork;
 use nix::{
     errno::Errno,
-    libc::SIGCHLD,
+    libc::{self, SIGCHLD},
     sys::{
         signal::{Signal, Signal::SIGKILL, Signal::SIGTERM},
         wait::{WaitStatus, waitpid},
     },
     unistd::Pid,
 };
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::{
-    io,
+    fs::File,
+    io::{self, Read, Write},
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
     os::unix::process::{CommandExt, ExitStatusExt},
     process::{Command, ExitStatus},
+    time::Duration,
 };
-use tracing::{error, info, trace};
+use tracing::{error, info, trace, warn};
+
+/// How long an adopted [NestedAuraed] is waited for to exit, so freeing its
+/// cell does not hang if it ignores the signal.
+const ADOPTED_EXIT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// What identifies a running [NestedAuraed], so an auraed restarted after a
+/// crash can adopt it with [NestedAuraed::adopt].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NestedAuraedState {
+    /// The host pid of the nested auraed
+    pub pid: i32,
+    /// The path of the socket the nested auraed listens on
+    pub socket: PathBuf,
+    /// The addresses assigned to the network namespace of the nested auraed
+    #[serde(default)]
+    pub addresses: Vec<IpNetwork>,
+    /// The runtime directory of the nested auraed, which holds its socket
+    #[serde(default)]
+    pub runtime_dir: Option<PathBuf>,
+}
 
 #[derive(Debug)]
 pub struct NestedAuraed {
     process: procfs::process::Process,
-    #[allow(unused)]
-    pidfd: i32,
+    /// Polled for the exit of the process, as an adopted process can not be
+    /// waited for
+    pidfd: OwnedFd,
     #[allow(unused)]
     iso_ctl: IsolationControls,
     pub client_socket: AuraeSocket,
+    /// The directory of the cell's own the nested auraed keeps its socket and
+    /// other runtime files in, removed once the nested auraed has exited
+    runtime_dir: Option<PathBuf>,
+    /// Only set if the network is isolated
+    network: Option<CellNetwork>,
+    /// Whether the process was spawned by a previous auraed, in which case it
+    /// is not our child and can not be waited for.
+    adopted: bool,
 }
 
 impl NestedAuraed {
-    pub fn new(name: String, iso_ctl: IsolationControls) -> io::Result<Self> {
+    pub fn new(
+        cell_name: &CellName,
+        iso_ctl: IsolationControls,
+    ) -> io::Result<Self> {
         // Here we launch a nested auraed with the --nested flag
         // which is used our way of "hooking" into the newly created
         // aurae isolation zone.
 
         let auraed_runtime = AURAED_RUNTIME.get().expect("runtime");
 
-        let socket_path = format!(
-            "{}/aurae-{}.sock",
-            auraed_runtime.runtime_dir.to_string_lossy(),
-            uuid::Uuid::new_v4(),
-        );
+        // The nested auraed does not share any directory with the host: its
+        // socket and runtime files are kept in a directory of the cell's own,
+        // and the log files of its executables in another one.
+        let runtime_dir = auraed_runtime
+            .cells_dir()
+            .join(uuid::Uuid::new_v4().simple().to_string());
+        let socket_path = runtime_dir.join("aurae.sock");
+        let logs_dir = auraed_runtime.cell_logs_dir(cell_name);
+        // The nested auraed binds its socket and writes its logs as the root
+        // of its user namespace, if any
+        for dir in [&runtime_dir, &logs_dir] {
+            std::fs::create_dir_all(dir)?;
+            chown_to_user_namespace_root(dir, &iso_ctl)?;
+        }
 
-        let client_socket = AuraeSocket::Path(socket_path.clone().into());
+        let client_socket = AuraeSocket::Path(socket_path.clone());
+        let name = cell_name.leaf().to_string();
 
         let auraed_path: PathBuf =
             auraed_runtime.auraed.clone().try_into().expect("path to auraed");
-        let mut command = Command::new(auraed_path);
+        // auraed is not in the root filesystem of the cell, so it is executed
+        // through a file descriptor opened on the host
+        let auraed_exe = iso_ctl
+            .rootfs
+            .is_some()
+            .then(|| File::open(&auraed_path))
+            .transpose()?;
+        let mut command = match &auraed_exe {
+            Some(exe) => {
+                Command::new(format!("/proc/self/fd/{}", exe.as_raw_fd()))
+            }
+            None => Command::new(&auraed_path),
+        };
 
+        // The nested auraed does not serve TLS, nor persist the state of
+        // cells, so it is not given the certificates or library directory
         let _ = command.args([
             "--socket",
-            &socket_path,
+            &socket_path.to_string_lossy(),
             "--nested", // NOTE: for now, the nested flag only signals for the code in the init module to not trigger (i.e., don't run the pid 1 code, run the non pid 1 code)
-            "--server-crt",
-            &auraed_runtime.server_crt.to_string_lossy(),
-            "--server-key",
-            &auraed_runtime.server_key.to_string_lossy(),
-            "--ca-crt",
-            &auraed_runtime.ca_crt.to_string_lossy(),
             "--runtime-dir",
-            &auraed_runtime.runtime_dir.to_string_lossy(),
-            "--library-dir",
-            &auraed_runtime.library_dir.to_string_lossy(),
+            &runtime_dir.to_string_lossy(),
+            "--logs-dir",
+            &logs_dir.to_string_lossy(),
+            "--log-file-max-size",
+            &auraed_runtime.log_file_max_size.to_string(),
+            "--log-file-max-age",
+            &auraed_runtime.log_file_max_age.as_secs().to_string(),
+            "--log-file-retention",
+            &auraed_runtime.log_file_retention.to_string(),
         ]);
 
         // We have a concern that the "command" API make change/break in the future and this
@@ -86,6 +151,18 @@ impl NestedAuraed {
         // to command.args, whose return value we ignored above.
         assert_eq!(command.get_args().len(), 13);
 
+        // The profiles of the cell are installed on the processes the nested
+        // auraed spawns, rather than on itself, as they may deny creating the
+        // namespaces of nested cells (e.g., the "default" profile)
+        for profile in auraed_runtime
+            .cell_seccomp_profiles
+            .iter()
+            .map(String::as_str)
+            .chain(iso_ctl.seccomp_profile.as_ref().map(|x| x.as_str()))
+        {
+            let _ = command.args(["--cell-seccomp-profile", profile]);
+        }
+
         // *****************************************************************
         // ██████╗██╗      ██████╗ ███╗   ██╗███████╗██████╗
         // ██╔════╝██║     ██╔═══██╗████╗  ██║██╔════╝╚════██╗
@@ -111,8 +188,11 @@ impl NestedAuraed {
         // We check that the clone we kept has set the first flag we set above.
         assert_eq!(clone.as_clone_args().flags, Flags::PIDFD.bits());
 
-        // Freeze the parent until the child calls execvp
-        let _ = clone.flag_vfork();
+        // Freeze the parent until the child calls execvp, unless the parent
+        // has to map the users and groups of the child first
+        if !iso_ctl.isolate_user {
+            let _ = clone.flag_vfork();
+        }
 
         // Manage SIGCHLD for the nested process
         // Define SIGCHLD for signal handler
@@ -121,6 +201,15 @@ impl NestedAuraed {
         // [ Namespaces and Isolation ]
 
         let mut isolation = Isolation::new(name);
+
+        // The host reaches the socket of the nested auraed through its
+        // runtime directory, which is shared with the root filesystem along
+        // with its logs directory, and nothing else of the host
+        if iso_ctl.rootfs.is_some() {
+            isolation.share_dir(runtime_dir.clone(), false);
+            isolation.share_dir(logs_dir, false);
+        }
+
         isolation.setup(&iso_ctl)?;
 
         // Always unshare the Cgroup namespace
@@ -139,6 +228,16 @@ impl NestedAuraed {
             let _ = clone.flag_newuts();
         }
 
+        // Isolate Users
+        // The other namespaces are owned by the new user namespace. The child
+        // waits on the pipe until the parent mapped its users and groups.
+        let mut users_mapped = if iso_ctl.isolate_user {
+            let _ = clone.flag_newuser();
+            Some(io::pipe()?)
+        } else {
+            None
+        };
+
         // Execute the clone system call and create the new process with the relevant namespaces.
         match unsafe { clone.call() }? {
             0 => {
@@ -146,8 +245,19 @@ impl NestedAuraed {
                 let command = {
                     unsafe {
                         command.pre_exec(move || {
+                            if let Some((mut rx, tx)) = users_mapped.take() {
+                                drop(tx);
+                                let mut mapped = [0u8; 1];
+                                // the parent closed the pipe without
+                                // mapping the users and groups
+                                if rx.read(&mut mapped)? != 1 {
+                                    return Err(
+                                        io::ErrorKind::UnexpectedEof.into()
+                                    );
+                                }
+                            }
+                            isolation.isolate_user(&iso_ctl)?;
                             isolation.isolate_process(&iso_ctl)?;
-                            isolation.isolate_network(&iso_ctl)?;
                             Ok(())
                         })
                     }
@@ -159,28 +269,149 @@ impl NestedAuraed {
             }
             pid => {
                 // parent
+                // SAFETY: clone3 returned the pidfd of the child, which is ours
+                let pidfd = unsafe { OwnedFd::from_raw_fd(pidfd) };
+                let kill_child = |e: io::Error| {
+                    let pid = Pid::from_raw(pid);
+                    let _best_effort = nix::sys::signal::kill(pid, SIGKILL);
+                    let _best_effort = waitpid(pid, None);
+                    e
+                };
+
+                if let Some((rx, mut tx)) = users_mapped.take() {
+                    drop(rx);
+                    isolation
+                        .map_users(pid, &iso_ctl)
+                        .and_then(|_| tx.write_all(&[1]))
+                        .map_err(kill_child)?;
+                }
+
+                let network = isolation
+                    .isolate_network(pid, &iso_ctl)
+                    .map_err(kill_child)?;
+
                 info!("Nested auraed running with host pid {}", pid.clone());
                 let process = procfs::process::Process::new(pid)
                     .map_err(io::Error::other)?;
 
-                Ok(Self { process, pidfd, iso_ctl, client_socket })
+                Ok(Self {
+                    process,
+                    pidfd,
+                    iso_ctl,
+                    client_socket,
+                    runtime_dir: Some(runtime_dir),
+                    network,
+                    adopted: false,
+                })
             }
         }
     }
 
+    /// Adopts a nested auraed spawned by a previous auraed.
+    ///
+    /// # Errors
+    /// * If the process is not running anymore -> [io::ErrorKind::NotFound]
+    /// * If the pid has been reused by a process that is not listening on
+    ///   the socket -> [io::ErrorKind::NotFound]
+    pub fn adopt(
+        state: &NestedAuraedState,
+        iso_ctl: IsolationControls,
+    ) -> io::Result<Self> {
+        let NestedAuraedState { pid, socket, addresses, runtime_dir } = state;
+
+        let not_found = || {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("nested auraed with pid {pid} is not running"),
+            )
+        };
+
+        let process =
+            procfs::process::Process::new(*pid).map_err(|_| not_found())?;
+        // Opened before checking the process, as it keeps referring to the
+        // process even once its pid is reused
+        let pidfd = pidfd_open(*pid).map_err(|_| not_found())?;
+        if !process.is_alive() {
+            return Err(not_found());
+        }
+
+        // The pid may have been reused since the nested auraed exited
+        let socket_arg = socket.to_string_lossy();
+        let cmdline = process.cmdline().map_err(|_| not_found())?;
+        if !cmdline.iter().any(|x| *x == socket_arg) {
+            return Err(not_found());
+        }
+
+        info!("Adopted nested auraed running with host pid {pid}");
+
+        let network = (!addresses.is_empty())
+            .then(|| CellNetwork::adopt(addresses.clone()));
+
+        Ok(Self {
+            process,
+            pidfd,
+            iso_ctl,
+            client_socket: AuraeSocket::Path(socket.clone()),
+            runtime_dir: runtime_dir.clone(),
+            network,
+            adopted: true,
+        })
+    }
+
+    /// Returns what is needed to adopt the nested auraed, should auraed be
+    /// restarted.
+    pub fn state(&self) -> NestedAuraedState {
+        let socket = match &self.client_socket {
+            AuraeSocket::Path(path) => path.clone(),
+            AuraeSocket::Addr(addr) => PathBuf::from(addr.to_string()),
+        };
+
+        NestedAuraedState {
+            pid: self.process.pid,
+            socket,
+            addresses: self.addresses().to_vec(),
+            runtime_dir: self.runtime_dir.clone(),
+        }
+    }
+
+    /// The addresses assigned to the network namespace of the nested
+    /// auraed. Empty if the network is not isolated.
+    pub fn addresses(&self) -> &[IpNetwork] {
+        self.network.as_ref().map_or(&[], |x| x.addresses())
+    }
+
     /// Sends a graceful shutdown signal to the nested process.
     pub fn shutdown(&mut self) -> io::Result<ExitStatus> {
         // TODO: Here, SIGTERM works when using auraescript, but hangs(?) during unit tests.
         //       SIGKILL, however, works. The hang is avoided if the process is not isolated.
         //       Tests have not been done to figure out which namespace is the cause of the hang.
         self.do_kill(Some(SIGTERM))?;
-        self.wait()
+        let exit_status = self.wait()?;
+        self.remove_runtime_dir();
+        Ok(exit_status)
     }
 
     /// Sends a [SIGKILL] signal to the nested process.
     pub fn kill(&mut self) -> io::Result<ExitStatus> {
         self.do_kill(Some(SIGKILL))?;
-        self.wait()
+        let exit_status = self.wait()?;
+        self.remove_runtime_dir();
+        Ok(exit_status)
+    }
+
+    /// Removes the runtime directory once the nested auraed has exited.
+    /// Errors are logged and ignored.
+    fn remove_runtime_dir(&mut self) {
+        let Some(dir) = self.runtime_dir.take() else {
+            return;
+        };
+
+        match std::fs::remove_dir_all(&dir) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                warn!("failed to remove {}: {e}", dir.display());
+            }
+            _ => {}
+        }
     }
 
     fn do_kill<T: Into<Option<Signal>>>(
@@ -196,6 +427,14 @@ impl NestedAuraed {
     fn wait(&mut self) -> io::Result<ExitStatus> {
         let pid = Pid::from_raw(self.process.pid);
 
+        // Only the parent of a process can wait for it. An adopted process
+        // has been reparented, and its new parent reaps it.
+        if self.adopted {
+            poll_exit(&self.pidfd, ADOPTED_EXIT_TIMEOUT)?;
+            trace!("Adopted pid {pid} exited");
+            return Ok(ExitStatus::from_raw(0));
+        }
+
         let status = loop {
             match waitpid(pid, None) {
                 Ok(status) => break status,
@@ -258,3 +497,44 @@ impl NestedAuraed {
         Pid::from_raw(self.process.pid)
     }
 }
+
+/// Opens a file descriptor referring to the process.
+fn pidfd_open(pid: i32) -> io::Result<OwnedFd> {
+    // SAFETY: pidfd_open takes no pointer
+    let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid, 0) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: pidfd_open returned a new file descriptor, which is ours
+    Ok(unsafe { OwnedFd::from_raw_fd(fd as i32) })
+}
+
+/// Waits for the process of the pidfd to exit, for up to the timeout.
+///
+/// # Errors
+/// * If the process has not exited in time -> [io::ErrorKind::TimedOut]
+fn poll_exit(pidfd: &OwnedFd, timeout: Duration) -> io::Result<()> {
+    let mut fds = libc::pollfd {
+        fd: pidfd.as_raw_fd(),
+        events: libc::POLLIN,
+        revents: 0,
+    };
+
+    loop {
+        // SAFETY: fds is valid for the duration of the call
+        let ready =
+            unsafe { libc::poll(&mut fds, 1, timeout.as_millis() as i32) };
+        match ready {
+            0 => {
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("process did not exit within {timeout:?}"),
+                ));
+            }
+            1.. => return Ok(()),
+            _ if Errno::last() == Errno::EINTR => continue,
+            _ => return Err(Errno::last().into()),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+//! Connects the network namespace of a cell to the host, with a veth pair
+//! whose host end is attached to the bridge of auraed.
+
+use crate::AURAED_RUNTIME;
+use crate::init::network::{Network, NetworkError};
+use ipnetwork::{IpNetwork, Ipv4Network, Ipv6Network};
+use lazy_static::lazy_static;
+use nix::sched::CloneFlags;
+use std::collections::HashSet;
+use std::fs::File;
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::sync::Mutex;
+use tracing::info;
+
+/// The bridge the host ends of the veth pairs of the cells are attached to.
+const BRIDGE: &str = "aurae0";
+/// The name of the veth peer in the network namespace of a cell.
+const CELL_IFACE: &str = "eth0";
+
+lazy_static! {
+    static ref ADDRESS_POOL: Mutex<AddressPool> = {
+        let runtime = AURAED_RUNTIME.get().expect("runtime");
+        Mutex::new(AddressPool::new(
+            runtime.cell_ipv4_pool,
+            runtime.cell_ipv6_pool,
+        ))
+    };
+}
+
+/// The network of a cell, connected with [CellNetwork::connect].
+/// Its addresses are given back to the pool when dropped.
+#[derive(Debug)]
+pub struct CellNetwork {
+    addresses: Vec<IpNetwork>,
+}
+
+impl CellNetwork {
+    /// Assigns addresses from the pool to the network namespace of the
+    /// process, and connects it to the bridge of auraed. The veth pair is
+    /// removed by the kernel along with the namespace.
+    pub(crate) fn connect(pid: i32) -> io::Result<Self> {
+        let (network, gateways) = {
+            let mut pool = ADDRESS_POOL.lock().expect("address pool");
+            (Self { addresses: pool.lease()? }, pool.gateways())
+        };
+
+        let bridge_addresses = gateways.to_vec();
+        let iface = format!("veth{pid}");
+        let peer = format!("vethc{pid}");
+        {
+            let peer = peer.clone();
+            run_in_netns(None, move |host| async move {
+                host.add_bridge(BRIDGE, &bridge_addresses).await?;
+                host.add_veth(&iface, &peer, BRIDGE, pid as u32).await
+            })?;
+        }
+
+        let addresses = network.addresses.clone();
+        let gateways: Vec<IpAddr> = gateways.iter().map(|x| x.ip()).collect();
+        run_in_netns(Some(pid), move |cell| async move {
+            cell.configure_veth_peer(&peer, CELL_IFACE, &addresses, &gateways)
+                .await
+        })?;
+
+        info!("Connected pid {pid} to {BRIDGE} with {:?}", network.addresses);
+        Ok(network)
+    }
+
+    /// Leases the addresses of a cell connected by a previous auraed again.
+    pub(crate) fn adopt(addresses: Vec<IpNetwork>) -> Self {
+        ADDRESS_POOL.lock().expect("address pool").reserve(&addresses);
+        Self { addresses }
+    }
+
+    /// The addresses of the cell, with the prefix of their pool.
+    pub fn addresses(&self) -> &[IpNetwork] {
+        &self.addresses
+    }
+}
+
+impl Drop for CellNetwork {
+    fn drop(&mut self) {
+        if let Ok(mut pool) = ADDRESS_POOL.lock() {
+            pool.release(&self.addresses);
+        }
+    }
+}
+
+/// Runs the future on a thread of its own, connected to the network
+/// namespace of the process if any (the host's otherwise), as the namespace
+/// of a thread can be changed without affecting the others.
+fn run_in_netns<F, Fut>(pid: Option<i32>, f: F) -> io::Result<()>
+where
+    F: FnOnce(Network) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<(), NetworkError>>,
+{
+    std::thread::spawn(move || {
+        if let Some(pid) = pid {
+            let netns = File::open(format!("/proc/{pid}/ns/net"))?;
+            nix::sched::setns(netns, CloneFlags::CLONE_NEWNET)?;
+        }
+
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+
+        runtime.block_on(async {
+            let network = Network::connect().map_err(io::Error::other)?;
+            f(network).await.map_err(io::Error::other)
+        })
+    })
+    .join()
+    .map_err(|_| io::Error::other("network thread panicked"))?
+}
+
+/// The addresses leased to the cells. The first address of each pool is
+/// the gateway of the cells, assigned to the bridge.
+#[derive(Debug)]
+struct AddressPool {
+    ipv4: Ipv4Network,
+    ipv6: Ipv6Network,
+    leased: HashSet<IpAddr>,
+}
+
+impl AddressPool {
+    fn new(ipv4: Ipv4Network, ipv6: Ipv6Network) -> Self {
+        Self { ipv4, ipv6, leased: HashSet::new() }
+    }
+
+    fn gateways(&self) -> [IpNetwork; 2] {
+        let ipv4 = Ipv4Addr::from(u32::from(self.ipv4.network()) + 1);
+        let ipv6 = Ipv6Addr::from(u128::from(self.ipv6.network()) + 1);
+
+        [
+            Ipv4Network::new(ipv4, self.ipv4.prefix())
+                .expect("valid prefix")
+                .into(),
+            Ipv6Network::new(ipv6, self.ipv6.prefix())
+                .expect("valid prefix")
+                .into(),
+        ]
+    }
+
+    /// Leases the first free IPv4 and IPv6 addresses.
+    fn lease(&mut self) -> io::Result<Vec<IpNetwork>> {
+        let exhausted = |pool: IpNetwork| {
+            io::Error::new(
+                io::ErrorKind::AddrNotAvailable,
+                format!("no address left in pool {pool}"),
+            )
+        };
+
+        let network = u32::from(self.ipv4.network());
+        let ipv4 = (2..)
+            .map_while(|i| network.checked_add(i).map(Ipv4Addr::from))
+            .take_while(|x| {
+                self.ipv4.contains(*x) && *x != self.ipv4.broadcast()
+            })
+            .find(|x| !self.leased.contains(&IpAddr::V4(*x)))
+            .ok_or_else(|| exhausted(self.ipv4.into()))?;
+
+        let network = u128::from(self.ipv6.network());
+        let ipv6 = (2..)
+            .map_while(|i| network.checked_add(i).map(Ipv6Addr::from))
+            .take_while(|x| self.ipv6.contains(*x))
+            .find(|x| !self.leased.contains(&IpAddr::V6(*x)))
+            .ok_or_else(|| exhausted(self.ipv6.into()))?;
+
+        let addresses = vec![
+            Ipv4Network::new(ipv4, self.ipv4.prefix())
+                .expect("valid prefix")
+                .into(),
+            Ipv6Network::new(ipv6, self.ipv6.prefix())
+                .expect("valid prefix")
+                .into(),
+        ];
+        self.reserve(&addresses);
+
+        Ok(addresses)
+    }
+
+    fn reserve(&mut self, addresses: &[IpNetwork]) {
+        self.leased.extend(addresses.iter().map(|x| x.ip()));
+    }
+
+    fn release(&mut self, addresses: &[IpNetwork]) {
+        for address in addresses {
+            let _ = self.leased.remove(&address.ip());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lease_should_skip_gateways_and_leased_addresses() {
+        let mut pool = AddressPool::new(
+            "10.0.0.0/30".parse().expect("ipv4 network"),
+            "fd00::/64".parse().expect("ipv6 network"),
+        );
+
+        assert_eq!(
+            pool.gateways(),
+            [
+                "10.0.0.1/30".parse::<IpNetwork>().expect("ipv4"),
+                "fd00::1/64".parse::<IpNetwork>().expect("ipv6"),
+            ]
+        );
+
+        let leased = pool.lease().expect("lease");
+        assert_eq!(
+            leased,
+            [
+                "10.0.0.2/30".parse::<IpNetwork>().expect("ipv4"),
+                "fd00::2/64".parse::<IpNetwork>().expect("ipv6"),
+            ]
+        );
+
+        // 10.0.0.3 is the broadcast address of the pool
+        assert_eq!(
+            pool.lease().expect_err("exhausted").kind(),
+            io::ErrorKind::AddrNotAvailable
+        );
+
+        pool.release(&leased);
+        assert_eq!(pool.lease().expect("lease"), leased);
+    }
+}
@@ -0,0 +1,277 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+//! The state of the cells allocated by auraed, persisted to a file so an
+//! auraed restarted after a crash can adopt the cells that are still running.
+
+use super::cells::{
+    Cell, CellName, Cells, CellsCache, CellsError, NestedAuraedState,
+    cgroups::Cgroup,
+};
+use super::validation::ValidatedCell;
+use ::validation::{ValidatedField, ValidatedType};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CellsState {
+    cells: Vec<PersistedCell>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedCell {
+    /// The specification of the cell, as of its last update
+    cell: proto::cells::Cell,
+    nested_auraed: NestedAuraedState,
+    children: Vec<PersistedCell>,
+}
+
+/// Encodes the state of the cells, to be written with [write].
+pub(super) fn encode(cells: &Cells) -> io::Result<Vec<u8>> {
+    let cells = cells
+        .get_all(persisted)
+        .map_err(io::Error::other)?
+        .into_iter()
+        .filter_map(|x| x.ok())
+        .collect();
+
+    Ok(serde_json::to_vec_pretty(&CellsState { cells })?)
+}
+
+/// Writes the encoded state of the cells to the file, replacing it
+/// atomically.
+pub(super) fn write(path: &Path, state: &[u8]) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // A crash while writing must not leave a truncated file behind
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, state)?;
+    fs::rename(tmp, path)
+}
+
+/// Adopts the cells of the file that are still running, and discards the
+/// others. Returns the names of the adopted cells.
+pub(super) fn rehydrate(
+    path: &Path,
+    cells: &mut Cells,
+) -> io::Result<Vec<CellName>> {
+    let CellsState { cells: persisted } = match fs::read(path) {
+        Ok(state) => serde_json::from_slice(&state)?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Default::default(),
+        Err(e) => return Err(e),
+    };
+
+    let mut adopted = vec![];
+    for cell in persisted {
+        adopt(cells, cell, &mut adopted);
+    }
+
+    Ok(adopted)
+}
+
+/// Removes the runtime directories of nested auraed in `dir` that are not
+/// used by any of the cells, e.g., those of the cells discarded by
+/// [rehydrate]. Errors are logged and ignored.
+pub(super) fn remove_unused_runtime_dirs(dir: &Path, cells: &Cells) {
+    let mut used = vec![];
+    if let Ok(persisted) = cells.get_all(persisted) {
+        for cell in persisted.iter().flatten() {
+            runtime_dirs(cell, &mut used);
+        }
+    }
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return,
+        Err(e) => {
+            warn!("failed to read {}: {e}", dir.display());
+            return;
+        }
+    };
+
+    for path in entries.filter_map(|x| x.ok()).map(|x| x.path()) {
+        if !used.contains(&path)
+            && let Err(e) = fs::remove_dir_all(&path)
+        {
+            warn!("failed to remove {}: {e}", path.display());
+        }
+    }
+}
+
+/// Discards the cgroups of the cells that were not adopted, e.g., allocated
+/// by a previous auraed that crashed before persisting them, as cells of the
+/// same names could not be allocated otherwise.
+pub(super) fn discard_orphaned_cgroups(adopted: &[CellName]) {
+    discard_orphaned_children(None, adopted);
+}
+
+fn discard_orphaned_children(parent: Option<&CellName>, adopted: &[CellName]) {
+    for cell_name in Cgroup::children(parent) {
+        if adopted.contains(&cell_name) {
+            discard_orphaned_children(Some(&cell_name), adopted);
+        } else {
+            warn!("discarding orphaned cgroup of cell {cell_name}");
+            discard_orphan(&cell_name);
+        }
+    }
+}
+
+fn discard_orphan(cell_name: &CellName) {
+    // The cgroup of a cell can only be deleted once its children are
+    for child in Cgroup::children(Some(cell_name)) {
+        discard_orphan(&child);
+    }
+
+    Cell::discard_orphan(cell_name);
+}
+
+fn runtime_dirs(cell: &PersistedCell, dirs: &mut Vec<PathBuf>) {
+    dirs.extend(cell.nested_auraed.runtime_dir.clone());
+    for child in &cell.children {
+        runtime_dirs(child, dirs);
+    }
+}
+
+fn persisted(cell: &Cell) -> Result<PersistedCell, CellsError> {
+    let Some(nested_auraed) = cell.nested_auraed_state() else {
+        return Err(CellsError::CellNotAllocated {
+            cell_name: cell.name().clone(),
+        });
+    };
+
+    let children = CellsCache::get_all(cell, persisted)?
+        .into_iter()
+        .filter_map(|x| x.ok())
+        .collect();
+
+    Ok(PersistedCell { cell: cell.into(), nested_auraed, children })
+}
+
+/// Adopts the cell, then its children. A cell that can not be adopted is
+/// discarded along with its children.
+fn adopt(
+    cells: &mut Cells,
+    persisted: PersistedCell,
+    adopted: &mut Vec<CellName>,
+) {
+    let adoption = match ValidatedCell::validate(persisted.cell.clone(), None) {
+        Ok(cell) => {
+            let cell_name = cell.name.clone();
+            cells
+                .adopt(
+                    cell_name.clone(),
+                    cell.into(),
+                    persisted.nested_auraed.clone(),
+                )
+                .map(|_| cell_name)
+                .map_err(|e| e.to_string())
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    match adoption {
+        Ok(cell_name) => {
+            adopted.push(cell_name);
+            for child in persisted.children {
+                adopt(cells, child, adopted);
+            }
+        }
+        Err(e) => {
+            warn!("discarding cell '{}': {e}", persisted.cell.name);
+            discard(&persisted);
+        }
+    }
+}
+
+fn discard(persisted: &PersistedCell) {
+    // The cgroup of a cell can only be deleted once its children are
+    for child in &persisted.children {
+        discard(child);
+    }
+
+    match CellName::validate(Some(persisted.cell.name.clone()), "name", None) {
+        Ok(cell_name) => Cell::discard(&cell_name, &persisted.nested_auraed),
+        Err(e) => {
+            warn!("failed to discard cell '{}': {e}", persisted.cell.name)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_unused_runtime_dirs_should_remove_the_dirs_of_no_cell() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let unused = dir.path().join("unused");
+        fs::create_dir_all(unused.join("log-segments")).expect("dir");
+
+        remove_unused_runtime_dirs(dir.path(), &Cells::default());
+
+        assert!(!unused.exists());
+    }
+
+    #[test]
+    fn rehydrate_should_discard_cells_that_are_not_running() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("cells.json");
+
+        // A nested auraed that exited along with the previous auraed
+        let mut process =
+            std::process::Command::new("true").spawn().expect("spawn");
+        let pid = process.id() as i32;
+        let _ = process.wait().expect("wait");
+
+        let socket = dir.path().join("aurae-test.sock");
+        fs::write(&socket, "").expect("socket");
+
+        let state = CellsState {
+            cells: vec![PersistedCell {
+                cell: proto::cells::Cell {
+                    name: format!("ae-test-{}", uuid::Uuid::new_v4()),
+                    ..Default::default()
+                },
+                nested_auraed: NestedAuraedState {
+                    pid,
+                    socket: socket.clone(),
+                    addresses: vec![],
+                    runtime_dir: None,
+                },
+                children: vec![],
+            }],
+        };
+        fs::write(&path, serde_json::to_vec(&state).expect("serialize"))
+            .expect("write");
+
+        let mut cells = Cells::default();
+        let adopted = rehydrate(&path, &mut cells).expect("rehydrate");
+
+        assert!(adopted.is_empty());
+        assert!(!socket.exists());
+
+        write(&path, &encode(&cells).expect("encode")).expect("write");
+        let state: CellsState =
+            serde_json::from_slice(&fs::read(&path).expect("read"))
+                .expect("deserialize");
+        assert!(state.cells.is_empty());
+    }
+}
@@ -42,16 +42,29 @@ impl From<CellsServiceError> for Status {
         error!("{msg}");
         match err {
             CellsServiceError::CellsError(e) => match e {
-                CellsError::CgroupIsNotACell { .. } => {
+                CellsError::CgroupIsNotACell { .. }
+                | CellsError::IsolationControlsCannotBeUpdated { .. }
+                | CellsError::CellPaused { .. } => {
                     Status::failed_precondition(msg)
                 }
+                CellsError::UnorderedMemoryBoundaries { .. } => {
+                    Status::invalid_argument(msg)
+                }
                 CellsError::CellExists { .. } => Status::already_exists(msg),
                 CellsError::CellNotFound { .. }
                 | CellsError::CgroupNotFound { .. } => Status::not_found(msg),
                 CellsError::FailedToAllocateCell { .. }
+                | CellsError::FailedToAdoptCell { .. }
                 | CellsError::AbortedAllocateCell { .. }
                 | CellsError::FailedToKillCellChildren { .. }
-                | CellsError::FailedToFreeCell { .. } => Status::internal(msg),
+                | CellsError::FailedToFreeCell { .. }
+                | CellsError::FailedToUpdateCell { .. }
+                | CellsError::FailedToPauseCell { .. }
+                | CellsError::FailedToResumeCell { .. }
+                | CellsError::FailedToReadCellStats { .. }
+                | CellsError::FailedToWatchCellPressure { .. } => {
+                    Status::internal(msg)
+                }
                 CellsError::CellNotAllocated { cell_name } => {
                     CellsServiceError::CellsError(CellsError::CellNotFound {
                         cell_name,
@@ -66,8 +79,12 @@ impl From<CellsServiceError> for Status {
                 ExecutablesError::ExecutableNotFound { .. } => {
                     Status::not_found(msg)
                 }
+                ExecutablesError::ExecutableNotRunning { .. } => {
+                    Status::failed_precondition(msg)
+                }
                 ExecutablesError::FailedToStartExecutable { .. }
-                | ExecutablesError::FailedToStopExecutable { .. } => {
+                | ExecutablesError::FailedToStopExecutable { .. }
+                | ExecutablesError::FailedToSignalExecutable { .. } => {
                     Status::internal(msg)
                 }
             },
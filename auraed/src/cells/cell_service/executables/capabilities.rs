@@ -0,0 +1,216 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use caps::{Capability, CapsHashSet};
+use nix::libc;
+use nix::sys::prctl;
+use nix::unistd::{self, Gid, Uid};
+use std::io;
+use tokio::process::Command;
+
+/// The capabilities in every set of a process, unless others are requested.
+/// Same as the capabilities of the pod sandbox (see `AuraeOCIBuilder`).
+pub const DEFAULT_CAPABILITIES: [Capability; 3] = [
+    Capability::CAP_AUDIT_WRITE,
+    Capability::CAP_KILL,
+    Capability::CAP_NET_BIND_SERVICE,
+];
+
+/// The Linux capabilities of the processes of an
+/// [Executable](super::Executable).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Capabilities {
+    /// The process keeps every capability of auraed
+    Privileged,
+    /// The process is limited to the [CapabilitySets], and can not gain
+    /// privileges (no_new_privileges)
+    Restricted(CapabilitySets),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilitySets {
+    pub bounding: CapsHashSet,
+    pub effective: CapsHashSet,
+    pub permitted: CapsHashSet,
+    pub inheritable: CapsHashSet,
+    pub ambient: CapsHashSet,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        let set = CapsHashSet::from(DEFAULT_CAPABILITIES);
+        Self::Restricted(CapabilitySets {
+            bounding: set.clone(),
+            effective: set.clone(),
+            permitted: set.clone(),
+            inheritable: set.clone(),
+            ambient: set,
+        })
+    }
+}
+
+impl Capabilities {
+    /// Sets the user and group of the process, and adds a `pre_exec` hook
+    /// applying the capabilities to it. Must be called before adding hooks
+    /// that restrict the process further (e.g., seccomp filters).
+    pub fn install(
+        &self,
+        command: &mut Command,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) {
+        let Self::Restricted(sets) = self else {
+            if let Some(uid) = uid {
+                let _ = command.uid(uid);
+            }
+            if let Some(gid) = gid {
+                let _ = command.gid(gid);
+            }
+            return;
+        };
+
+        // The permitted capabilities are cleared when the user is changed by
+        // the command, so the hook changes it after setting KEEPCAPS.
+        // The hook runs between fork and exec, where allocating could
+        // deadlock, so what it applies is computed beforehand.
+        let raw = sets.to_raw();
+        unsafe {
+            let _ = command.pre_exec(move || raw.apply(uid, gid));
+        }
+    }
+}
+
+impl CapabilitySets {
+    fn to_raw(&self) -> RawCapabilitySets {
+        RawCapabilitySets {
+            bounding_drops: caps::runtime::thread_all_supported()
+                .difference(&self.bounding)
+                .map(|x| x.index())
+                .collect(),
+            effective: bitmask(&self.effective),
+            permitted: bitmask(&self.permitted),
+            inheritable: bitmask(&self.inheritable),
+            ambient: self.ambient.iter().map(|x| x.index()).collect(),
+        }
+    }
+}
+
+/// The [CapabilitySets] as the kernel takes them.
+#[derive(Debug, Clone)]
+struct RawCapabilitySets {
+    /// The capabilities dropped from the bounding set
+    bounding_drops: Vec<u8>,
+    effective: u64,
+    permitted: u64,
+    inheritable: u64,
+    ambient: Vec<u8>,
+}
+
+impl RawCapabilitySets {
+    /// Applies the capabilities to the calling thread, with system calls
+    /// only, as it is called in `pre_exec`.
+    fn apply(&self, uid: Option<u32>, gid: Option<u32>) -> io::Result<()> {
+        // Dropping from the bounding set requires CAP_SETPCAP in the
+        // effective set, which is cleared when changing the user
+        for cap in &self.bounding_drops {
+            raw_prctl(libc::PR_CAPBSET_DROP, libc::c_ulong::from(*cap), 0)?;
+        }
+
+        if let Some(gid) = gid {
+            unistd::setgid(Gid::from_raw(gid))?;
+        }
+        if let Some(uid) = uid {
+            prctl::set_keepcaps(true)?;
+            // Like the command would, drop the supplementary groups of auraed
+            unistd::setgroups(&[])?;
+            unistd::setuid(Uid::from_raw(uid))?;
+        }
+
+        capset(self.effective, self.permitted, self.inheritable)?;
+
+        // The ambient set must remain a subset of both the permitted and
+        // inheritable sets, so it is raised last
+        let (ambient, clear_all, raise) = (
+            libc::PR_CAP_AMBIENT,
+            libc::PR_CAP_AMBIENT_CLEAR_ALL as libc::c_ulong,
+            libc::PR_CAP_AMBIENT_RAISE as libc::c_ulong,
+        );
+        raw_prctl(ambient, clear_all, 0)?;
+        for cap in &self.ambient {
+            raw_prctl(ambient, raise, libc::c_ulong::from(*cap))?;
+        }
+
+        prctl::set_no_new_privs()?;
+        Ok(())
+    }
+}
+
+/// The version of the capget(2) and capset(2) structs with 64 bit sets.
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+#[repr(C)]
+struct CapUserHeader {
+    version: u32,
+    pid: libc::c_int,
+}
+
+#[repr(C)]
+struct CapUserData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+fn bitmask(caps: &CapsHashSet) -> u64 {
+    caps.iter().fold(0, |mask, cap| mask | cap.bitmask())
+}
+
+/// Sets the effective, permitted and inheritable sets of the calling thread
+/// at once.
+fn capset(effective: u64, permitted: u64, inheritable: u64) -> io::Result<()> {
+    let mut header =
+        CapUserHeader { version: LINUX_CAPABILITY_VERSION_3, pid: 0 };
+    // The lower, then the upper, 32 bits of the sets
+    let data = [0, 32].map(|shift| CapUserData {
+        effective: (effective >> shift) as u32,
+        permitted: (permitted >> shift) as u32,
+        inheritable: (inheritable >> shift) as u32,
+    });
+
+    // SAFETY: header and data are valid for the duration of the call
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_capset,
+            std::ptr::addr_of_mut!(header),
+            data.as_ptr(),
+        )
+    };
+    if ret == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+fn raw_prctl(
+    option: libc::c_int,
+    arg2: libc::c_ulong,
+    arg3: libc::c_ulong,
+) -> io::Result<()> {
+    // SAFETY: the options used take no pointer
+    if unsafe { libc::prctl(option, arg2, arg3, 0, 0) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
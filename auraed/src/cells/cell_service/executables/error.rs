@@ -35,4 +35,13 @@ pub enum ExecutablesError {
         executable_name: ExecutableName,
         source: io::Error,
     },
+    #[error("executable '{executable_name}' is not running")]
+    ExecutableNotRunning { executable_name: ExecutableName },
+    #[error(
+        "executable '{executable_name}' failed to receive signal: {source}"
+    )]
+    FailedToSignalExecutable {
+        executable_name: ExecutableName,
+        source: io::Error,
+    },
 }
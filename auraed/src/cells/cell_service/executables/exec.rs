@@ -0,0 +1,440 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use super::{Capabilities, Rlimit};
+use crate::cells::cell_service::seccomp::{SeccompProfile, process_filters};
+use nix::libc;
+use nix::pty::{Winsize, openpty};
+use std::os::fd::{AsRawFd, OwnedFd};
+use std::pin::Pin;
+use std::task::{Context, Poll, ready};
+use std::{io, process::Stdio};
+use tokio::io::unix::AsyncFd;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Written to a terminal instead of closing it, which would hang up the
+/// process (Ctrl-D).
+const END_OF_TRANSMISSION: u8 = 0x04;
+
+nix::ioctl_write_ptr_bad!(set_window_size, libc::TIOCSWINSZ, Winsize);
+
+/// The size of the pseudo terminal of an [ExecProcess].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerminalSize {
+    pub rows: u16,
+    pub columns: u16,
+}
+
+impl From<TerminalSize> for Winsize {
+    fn from(value: TerminalSize) -> Self {
+        Self {
+            ws_row: value.rows,
+            ws_col: value.columns,
+            ws_xpixel: 0,
+            ws_ypixel: 0,
+        }
+    }
+}
+
+/// How the stdio of an [ExecProcess] is attached, and how it is confined.
+#[derive(Debug, Clone, Default)]
+pub struct ExecSpec {
+    /// Allocate a pseudo terminal of the given size, shared by stdin, stdout
+    /// and stderr.
+    pub tty: Option<TerminalSize>,
+    /// Attach the stdin of the process to [ExecProcess::stdin].
+    pub interactive: bool,
+    /// The seccomp profile installed before the process is executed.
+    pub seccomp_profile: Option<SeccompProfile>,
+    /// The capabilities of the process.
+    pub capabilities: Capabilities,
+    /// The resource limits of the process.
+    pub rlimits: Vec<Rlimit>,
+}
+
+/// A chunk of the output of an [ExecProcess], as it was read.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ExecOutput {
+    Stdout(Vec<u8>),
+    Stderr(Vec<u8>),
+}
+
+/// A process attached to the caller, rather than to log channels like an
+/// [Executable](super::Executable). It is killed when dropped.
+#[derive(Debug)]
+pub struct ExecProcess {
+    pub child: Child,
+    /// Only set if the process is interactive
+    pub stdin: Option<ExecStdin>,
+    /// Only set if the process has a pseudo terminal
+    pub terminal: Option<ExecTerminal>,
+    /// The tasks reading the output of the process
+    readers: Vec<JoinHandle<()>>,
+}
+
+/// The stdin of an interactive [ExecProcess].
+#[derive(Debug)]
+pub enum ExecStdin {
+    Pipe(ChildStdin),
+    Terminal(PtyMaster),
+}
+
+impl ExecStdin {
+    pub async fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            Self::Pipe(stdin) => stdin.write_all(bytes).await,
+            Self::Terminal(stdin) => {
+                stdin.write_all(bytes).await?;
+                stdin.flush().await
+            }
+        }
+    }
+
+    /// Closes the stdin, so the process reads an end of file.
+    pub async fn close(self) -> io::Result<()> {
+        match self {
+            Self::Pipe(_) => Ok(()),
+            Self::Terminal(mut stdin) => {
+                stdin.write_all(&[END_OF_TRANSMISSION]).await?;
+                stdin.flush().await
+            }
+        }
+    }
+}
+
+/// The controller side of the pseudo terminal of an [ExecProcess].
+#[derive(Debug)]
+pub struct ExecTerminal(OwnedFd);
+
+impl ExecTerminal {
+    /// Resizes the terminal, and the process receives a SIGWINCH.
+    pub fn resize(&self, size: TerminalSize) -> io::Result<()> {
+        let size = Winsize::from(size);
+        let _ = unsafe { set_window_size(self.0.as_raw_fd(), &size) }?;
+        Ok(())
+    }
+}
+
+/// The controller side of a pseudo terminal, read and written without
+/// blocking, so the task reading it can be aborted.
+#[derive(Debug)]
+pub struct PtyMaster(AsyncFd<OwnedFd>);
+
+impl PtyMaster {
+    fn new(fd: OwnedFd) -> io::Result<Self> {
+        // SAFETY: F_GETFL and F_SETFL take no pointer. The flag is set on the
+        // open file, so it is shared by the duplicates of fd.
+        let flags = unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_GETFL) };
+        if flags < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let flags = flags | libc::O_NONBLOCK;
+        if unsafe { libc::fcntl(fd.as_raw_fd(), libc::F_SETFL, flags) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(Self(AsyncFd::new(fd)?))
+    }
+}
+
+impl AsyncRead for PtyMaster {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        loop {
+            let mut guard = ready!(self.0.poll_read_ready(cx))?;
+            let unfilled = buf.initialize_unfilled();
+            // SAFETY: unfilled is valid for writes of its length
+            match guard.try_io(|fd| {
+                syscall_result(unsafe {
+                    libc::read(
+                        fd.as_raw_fd(),
+                        unfilled.as_mut_ptr().cast(),
+                        unfilled.len(),
+                    )
+                })
+            }) {
+                Ok(result) => {
+                    buf.advance(result?);
+                    return Poll::Ready(Ok(()));
+                }
+                Err(_would_block) => continue,
+            }
+        }
+    }
+}
+
+impl AsyncWrite for PtyMaster {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        loop {
+            let mut guard = ready!(self.0.poll_write_ready(cx))?;
+            // SAFETY: buf is valid for reads of its length
+            match guard.try_io(|fd| {
+                syscall_result(unsafe {
+                    libc::write(fd.as_raw_fd(), buf.as_ptr().cast(), buf.len())
+                })
+            }) {
+                Ok(result) => return Poll::Ready(result),
+                Err(_would_block) => continue,
+            }
+        }
+    }
+
+    fn poll_flush(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// The number of bytes read or written, or the error of the syscall.
+fn syscall_result(n: isize) -> io::Result<usize> {
+    usize::try_from(n).map_err(|_| io::Error::last_os_error())
+}
+
+impl ExecProcess {
+    /// Spawns the process, and returns it with the receiver of its output.
+    /// The receiver is closed once all the output has been read.
+    pub fn spawn(
+        command: &mut Command,
+        spec: ExecSpec,
+        uid: Option<u32>,
+        gid: Option<u32>,
+    ) -> io::Result<(Self, mpsc::Receiver<ExecOutput>)> {
+        let ExecSpec {
+            tty,
+            interactive,
+            seccomp_profile,
+            capabilities,
+            rlimits,
+        } = spec;
+        let seccomp = process_filters(seccomp_profile.as_ref())
+            .map_err(io::Error::other)?;
+
+        let _ = command.kill_on_drop(true);
+        Rlimit::install(&rlimits, command);
+        capabilities.install(command, uid, gid);
+
+        let (tx, rx) = mpsc::channel(16);
+
+        let process = match tty {
+            Some(size) => {
+                let pty = openpty(Some(&size.into()), None)?;
+
+                let _ = command
+                    .stdin(Stdio::from(pty.slave.try_clone()?))
+                    .stdout(Stdio::from(pty.slave.try_clone()?))
+                    .stderr(Stdio::from(pty.slave));
+
+                // The terminal must be the controlling terminal of a new
+                // session, so the process gets signals from it (e.g., SIGINT)
+                unsafe {
+                    let _ = command.pre_exec(|| {
+                        let _ = nix::unistd::setsid()?;
+                        if libc::ioctl(0, libc::TIOCSCTTY as _, 0) < 0 {
+                            return Err(io::Error::last_os_error());
+                        }
+                        Ok(())
+                    });
+                }
+                if let Some(seccomp) = &seccomp {
+                    seccomp.install(command);
+                }
+
+                let child = command.spawn()?;
+
+                // Close our copies of the terminal, so reading from it ends
+                // once the process (and its children) closed theirs
+                let _ = command
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null());
+
+                let output = PtyMaster::new(pty.master.try_clone()?)?;
+                let stdin = interactive
+                    .then(|| pty.master.try_clone())
+                    .transpose()?
+                    .map(PtyMaster::new)
+                    .transpose()?
+                    .map(ExecStdin::Terminal);
+
+                Self {
+                    child,
+                    stdin,
+                    terminal: Some(ExecTerminal(pty.master)),
+                    readers: vec![forward_bytes(
+                        output,
+                        tx,
+                        ExecOutput::Stdout,
+                    )],
+                }
+            }
+            None => {
+                let stdin =
+                    if interactive { Stdio::piped() } else { Stdio::null() };
+                let _ = command
+                    .stdin(stdin)
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped());
+                if let Some(seccomp) = &seccomp {
+                    seccomp.install(command);
+                }
+
+                let mut child = command.spawn()?;
+
+                let stdin = child.stdin.take().map(ExecStdin::Pipe);
+                let stdout = forward_bytes(
+                    child.stdout.take().expect("stdout"),
+                    tx.clone(),
+                    ExecOutput::Stdout,
+                );
+                let stderr = forward_bytes(
+                    child.stderr.take().expect("stderr"),
+                    tx,
+                    ExecOutput::Stderr,
+                );
+
+                Self {
+                    child,
+                    stdin,
+                    terminal: None,
+                    readers: vec![stdout, stderr],
+                }
+            }
+        };
+
+        Ok((process, rx))
+    }
+}
+
+impl Drop for ExecProcess {
+    fn drop(&mut self) {
+        self.readers.iter().for_each(|x| x.abort());
+    }
+}
+
+fn forward_bytes<R: AsyncRead + Unpin + Send + 'static>(
+    mut reader: R,
+    tx: mpsc::Sender<ExecOutput>,
+    output: fn(Vec<u8>) -> ExecOutput,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut buf = vec![0u8; 4096];
+        loop {
+            // A terminal reports EIO once the process closed it
+            let n = match reader.read(&mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            if tx.send(output(buf[..n].to_vec())).await.is_err() {
+                break;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn read_all(mut rx: mpsc::Receiver<ExecOutput>) -> Vec<ExecOutput> {
+        let mut output = vec![];
+        while let Some(x) = rx.recv().await {
+            output.push(x);
+        }
+        output
+    }
+
+    #[tokio::test]
+    async fn exec_should_forward_stdin_and_output() {
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c").arg("cat; echo done >&2; exit 3");
+
+        let (mut process, rx) = ExecProcess::spawn(
+            &mut command,
+            ExecSpec {
+                tty: None,
+                interactive: true,
+                seccomp_profile: None,
+                capabilities: Capabilities::Privileged,
+                rlimits: vec![],
+            },
+            None,
+            None,
+        )
+        .expect("spawn");
+
+        let mut stdin = process.stdin.take().expect("interactive");
+        stdin.write(b"hello\n").await.expect("write stdin");
+        stdin.close().await.expect("close stdin");
+
+        let exit_status = process.child.wait().await.expect("wait");
+
+        assert_eq!(exit_status.code(), Some(3));
+        let output = read_all(rx).await;
+        assert!(output.contains(&ExecOutput::Stdout(b"hello\n".to_vec())));
+        assert!(output.contains(&ExecOutput::Stderr(b"done\n".to_vec())));
+    }
+
+    #[tokio::test]
+    async fn exec_should_allocate_terminal() {
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c").arg("stty size; test -t 0");
+
+        let (mut process, rx) = ExecProcess::spawn(
+            &mut command,
+            ExecSpec {
+                tty: Some(TerminalSize { rows: 24, columns: 80 }),
+                interactive: false,
+                seccomp_profile: None,
+                capabilities: Capabilities::Privileged,
+                rlimits: vec![],
+            },
+            None,
+            None,
+        )
+        .expect("spawn");
+
+        let exit_status = process.child.wait().await.expect("wait");
+
+        assert!(exit_status.success());
+        let output: Vec<u8> = read_all(rx)
+            .await
+            .into_iter()
+            .flat_map(|x| match x {
+                ExecOutput::Stdout(x) => x,
+                ExecOutput::Stderr(_) => panic!("stderr with a terminal"),
+            })
+            .collect();
+        assert_eq!(String::from_utf8_lossy(&output).trim(), "24 80");
+    }
+}
@@ -12,18 +12,33 @@
  * Copyright 2022 - 2024, the aurae contributors                              *
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
-use super::{ExecutableName, ExecutableSpec};
-use crate::logging::log_channel::LogChannel;
+
+use super::{
+    Capabilities, ExecutableName, ExecutableSpec, RestartSpec, Rlimit, StopSpec,
+};
+use crate::cells::cell_service::seccomp::{SeccompProfile, process_filters};
+use crate::logging::{
+    log_channel::{LogChannel, LogChannelConfig, flush_writes},
+    log_file::{LogFile, LogFileConfig},
+};
+use backoff::backoff::Backoff;
+use nix::sys::signal::{self, Signal};
 use nix::unistd::Pid;
 use std::{
     ffi::OsString,
     io,
     process::{ExitStatus, Stdio},
+    time::{Duration, SystemTime},
 };
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::{Child, Command};
+use tokio::sync::{oneshot, watch};
 use tokio::task::JoinHandle;
-use tracing::info_span;
+use tracing::{error, info, info_span, warn};
+
+/// How long the output of an exited process is still forwarded for, as the
+/// processes it spawned may keep its stdout and stderr open.
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
 
 // TODO: decide if we're going to use the description or not.  Remove if not.
 #[allow(dead_code)]
@@ -33,121 +48,222 @@ pub struct Executable {
     pub description: String,
     pub stdout: LogChannel,
     pub stderr: LogChannel,
+    restart: RestartSpec,
+    /// The [Pid] of the process started by [Executable::start].
+    initial_pid: Option<Pid>,
+    status: watch::Receiver<ExecutableStatus>,
     state: ExecutableState,
 }
 
+/// The status of an [Executable], as tracked by its supervisor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecutableStatus {
+    pub state: ProcessState,
+    /// The [Pid] of the running process, if any
+    pub pid: Option<Pid>,
+    /// When the last process was started
+    pub started_at: Option<SystemTime>,
+    /// The number of times the process has been restarted
+    pub restart_count: u32,
+    /// The [ExitStatus] of the last process that exited
+    pub last_exit_status: Option<ExitStatus>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProcessState {
+    #[default]
+    NotStarted,
+    Running,
+    /// The process has exited, and is waiting to be restarted
+    Restarting,
+    /// The process has exited, and will not be restarted
+    Exited,
+}
+
 #[derive(Debug)]
 enum ExecutableState {
     Init {
         command: Command,
+        seccomp_profile: Option<SeccompProfile>,
+        capabilities: Capabilities,
+        rlimits: Vec<Rlimit>,
+        status: watch::Sender<ExecutableStatus>,
     },
     Started {
         #[allow(unused)]
         program: OsString,
         #[allow(unused)]
         args: Vec<OsString>,
-        child: Child,
-        stdout: JoinHandle<()>,
-        stderr: JoinHandle<()>,
+        /// Signals the supervisor to stop the process, and stop restarting it
+        stop: Option<oneshot::Sender<StopSpec>>,
+        supervisor: JoinHandle<io::Result<ExitStatus>>,
     },
     Stopped(ExitStatus),
 }
 
 impl Executable {
-    pub fn new<T: Into<ExecutableSpec>>(spec: T) -> Self {
-        let ExecutableSpec { name, description, command } = spec.into();
-        let state = ExecutableState::Init { command };
-        let stdout = LogChannel::new(format!("{name}::stdout"));
-        let stderr = LogChannel::new(format!("{name}::stderr"));
-        Self { name, description, stdout, stderr, state }
+    pub fn new<T: Into<ExecutableSpec>>(
+        spec: T,
+        log_config: &LogChannelConfig,
+        log_files: Option<&LogFileConfig>,
+    ) -> Self {
+        let ExecutableSpec {
+            name,
+            description,
+            command,
+            restart,
+            seccomp_profile,
+            capabilities,
+            rlimits,
+        } = spec.into();
+        let (status_tx, status) = watch::channel(ExecutableStatus::default());
+        let state = ExecutableState::Init {
+            command,
+            seccomp_profile,
+            capabilities,
+            rlimits,
+            status: status_tx,
+        };
+        let mut stdout =
+            LogChannel::with_config(format!("{name}::stdout"), log_config);
+        let mut stderr =
+            LogChannel::with_config(format!("{name}::stderr"), log_config);
+
+        // stdout and stderr share the log file of the executable
+        if let Some(config) = log_files {
+            let path = config.path_for(&name.to_string());
+            match LogFile::open(path, config) {
+                Ok(file) => {
+                    stdout = stdout.with_file(file.clone());
+                    stderr = stderr.with_file(file);
+                }
+                Err(e) => warn!("failed to open log file of {name}: {e}"),
+            }
+        }
+
+        Self {
+            name,
+            description,
+            stdout,
+            stderr,
+            restart,
+            initial_pid: None,
+            status,
+            state,
+        }
     }
 
-    /// Starts the underlying process.
+    /// Starts the underlying process, and a supervisor task that restarts it
+    /// according to its [RestartSpec].
     /// Does nothing if [Executable] has previously been started.
     pub fn start(
         &mut self,
         uid: Option<u32>,
         gid: Option<u32>,
     ) -> io::Result<()> {
-        let ExecutableState::Init { command } = &mut self.state else {
+        let ExecutableState::Init {
+            command,
+            seccomp_profile,
+            capabilities,
+            rlimits,
+            ..
+        } = &mut self.state
+        else {
             return Ok(());
         };
 
-        let mut command = command
+        let command = command
             .kill_on_drop(true)
-            .current_dir("/")
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
-        if let Some(uid) = uid {
-            command = command.uid(uid);
+        Rlimit::install(rlimits, command);
+        capabilities.install(command, uid, gid);
+        // The restarted processes are confined by the same filters
+        if let Some(filters) = process_filters(seccomp_profile.as_ref())
+            .map_err(io::Error::other)?
+        {
+            filters.install(command);
         }
-        if let Some(gid) = gid {
-            command = command.gid(gid);
-        }
-        let mut child = command.spawn()?;
 
-        let log_channel = self.stdout.clone();
-        let stdout = child.stdout.take().expect("stdout");
-        let span = info_span!("running process", name = ?self.name);
-        let stdout = tokio::spawn(async move {
-            let log_channel = log_channel;
-            let mut span = Some(span);
-            let mut stdout = BufReader::new(stdout).lines();
-            while let Ok(Some(line)) = stdout.next_line().await {
-                let entered_span = span.take().expect("span").entered();
-                //info!(level = "info", channel = log_channel.name, line);
-                // if std::env::var("AER").is_ok() {
-                //     println!("{line}");
-                // }
-                log_channel.send(line);
-                span = Some(entered_span.exit());
-            }
-        });
+        let process =
+            Process::spawn(command, &self.name, &self.stdout, &self.stderr)?;
 
-        let log_channel = self.stderr.clone();
-        let stderr = child.stderr.take().expect("stderr");
-        let span = info_span!("running process", name = ?self.name);
-        let stderr = tokio::spawn(async move {
-            let log_channel = log_channel;
-            let mut span = Some(span);
-            let mut stderr = BufReader::new(stderr).lines();
-            while let Ok(Some(line)) = stderr.next_line().await {
-                let entered_span = span.take().expect("span").entered();
-                // info!(level = "error", channel = log_channel.name, line);
-                // if std::env::var("AER").is_ok() {
-                //     println!("{line}");
-                // }
-                log_channel.send(line);
-                span = Some(entered_span.exit());
-            }
+        let program = command.as_std().get_program().to_os_string();
+        let args =
+            command.as_std().get_args().map(|arg| arg.to_os_string()).collect();
+
+        self.initial_pid = process.pid();
+
+        // The supervisor takes the command, as it needs it to restart the process
+        let ExecutableState::Init { command, status, .. } = std::mem::replace(
+            &mut self.state,
+            ExecutableState::Stopped(ExitStatus::default()),
+        ) else {
+            unreachable!("executable is not started");
+        };
+
+        status.send_modify(|status| {
+            status.state = ProcessState::Running;
+            status.pid = process.pid();
+            status.started_at = Some(SystemTime::now());
         });
 
+        let supervisor = Supervisor {
+            name: self.name.clone(),
+            command,
+            restart: self.restart,
+            status,
+            stdout: self.stdout.clone(),
+            stderr: self.stderr.clone(),
+        };
+
+        let (stop, stopped) = oneshot::channel();
+        let supervisor = tokio::spawn(supervisor.run(process, stopped));
+
         self.state = ExecutableState::Started {
-            program: command.as_std().get_program().to_os_string(),
-            args: command
-                .as_std()
-                .get_args()
-                .map(|arg| arg.to_os_string())
-                .collect(),
-            child,
-            stdout,
-            stderr,
+            program,
+            args,
+            stop: Some(stop),
+            supervisor,
         };
 
         Ok(())
     }
 
-    /// Stops the executable and returns the [ExitStatus].
+    /// Stops the executable as described by the [StopSpec], and returns the
+    /// [ExitStatus].
     /// If the executable has never been started, returns [None].
-    pub async fn kill(&mut self) -> io::Result<Option<ExitStatus>> {
+    pub async fn stop(
+        &mut self,
+        spec: StopSpec,
+    ) -> io::Result<Option<ExitStatus>> {
         Ok(match &mut self.state {
             ExecutableState::Init { .. } => None,
-            ExecutableState::Started { child, stdout, stderr, .. } => {
-                child.kill().await?;
-                let exit_status = child.wait().await?;
-                let _ = tokio::join!(stdout, stderr);
+            ExecutableState::Started { stop, supervisor, .. } => {
+                // The supervisor may have already exited, if the process is
+                // not restarted, in which case there is nothing to stop.
+                if let Some(stop) = stop.take() {
+                    let _ = stop.send(spec);
+                }
+                let result = supervisor
+                    .await
+                    .map_err(io::Error::other)
+                    .and_then(|result| result);
+                // A completed supervisor can not be awaited again, so the
+                // executable is stopped even if the supervisor failed.
+                let exit_status = match &result {
+                    Ok(exit_status) => *exit_status,
+                    Err(_) => self
+                        .status
+                        .borrow()
+                        .last_exit_status
+                        .unwrap_or_default(),
+                };
                 self.state = ExecutableState::Stopped(exit_status);
-                Some(exit_status)
+                // So an executable started with the same name follows on from
+                // the sequence of the output in the log file
+                flush_writes().await;
+                Some(result?)
             }
             ExecutableState::Stopped(status) => Some(*status),
         })
@@ -155,11 +271,233 @@ impl Executable {
 
     /// Returns the [Pid] while [Executable] is running, otherwise returns [None].
     pub fn pid(&self) -> io::Result<Option<Pid>> {
-        let ExecutableState::Started { child: process, .. } = &self.state
-        else {
-            return Ok(None);
+        Ok(self.status.borrow().pid)
+    }
+
+    /// Returns the [Pid] of the process started by [Executable::start], or
+    /// [None] if [Executable] has never been started. Restarted processes
+    /// get a new [Pid], but keep sending their output to the same log channels.
+    pub fn initial_pid(&self) -> Option<Pid> {
+        self.initial_pid
+    }
+
+    /// Returns the [ExecutableStatus] as last updated by the supervisor.
+    pub fn status(&self) -> ExecutableStatus {
+        *self.status.borrow()
+    }
+
+    /// Returns a receiver that is notified every time the supervisor updates
+    /// the [ExecutableStatus]. The state is [ProcessState::Exited] before the
+    /// supervisor drops the sender.
+    pub fn watch_status(&self) -> watch::Receiver<ExecutableStatus> {
+        self.status.clone()
+    }
+}
+
+/// A running process of an [Executable], and the tasks sending its output to
+/// the log channels.
+#[derive(Debug)]
+struct Process {
+    child: Child,
+    stdout: JoinHandle<()>,
+    stderr: JoinHandle<()>,
+}
+
+impl Process {
+    fn spawn(
+        command: &mut Command,
+        name: &ExecutableName,
+        stdout: &LogChannel,
+        stderr: &LogChannel,
+    ) -> io::Result<Self> {
+        let mut child = command.spawn()?;
+
+        let stdout = forward_lines(
+            child.stdout.take().expect("stdout"),
+            stdout.clone(),
+            name,
+        );
+        let stderr = forward_lines(
+            child.stderr.take().expect("stderr"),
+            stderr.clone(),
+            name,
+        );
+
+        Ok(Self { child, stdout, stderr })
+    }
+
+    fn pid(&self) -> Option<Pid> {
+        self.child.id().map(|id| Pid::from_raw(id as i32))
+    }
+
+    /// Sends the signal of the [StopSpec], and escalates to SIGKILL if the
+    /// process has not exited within the grace period.
+    async fn stop(
+        mut self,
+        spec: StopSpec,
+        name: &ExecutableName,
+    ) -> io::Result<ExitStatus> {
+        let StopSpec { signal, grace_period } = spec;
+
+        let graceful = match self.pid() {
+            // The process may exit before the signal is delivered (ESRCH)
+            Some(pid) if signal != Signal::SIGKILL => {
+                let _ = signal::kill(pid, signal);
+                tokio::time::timeout(grace_period, self.child.wait()).await.ok()
+            }
+            _ => None,
+        };
+
+        let exit_status = match graceful {
+            Some(exit_status) => exit_status?,
+            None => {
+                if signal != Signal::SIGKILL {
+                    warn!(
+                        "executable {name} did not exit within {grace_period:?} after {signal}, sending SIGKILL"
+                    );
+                }
+                self.child.kill().await?;
+                self.child.wait().await?
+            }
         };
 
-        Ok(process.id().map(|id| Pid::from_raw(id as i32)))
+        self.drain_output(name).await;
+        Ok(exit_status)
+    }
+
+    /// Waits for the output of the exited process to be forwarded, and stops
+    /// forwarding it after [OUTPUT_DRAIN_TIMEOUT].
+    async fn drain_output(self, name: &ExecutableName) {
+        let (stdout, stderr) =
+            (self.stdout.abort_handle(), self.stderr.abort_handle());
+        let forwarded = tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, async {
+            tokio::join!(self.stdout, self.stderr)
+        })
+        .await;
+
+        if forwarded.is_err() {
+            warn!(
+                "output of executable {name} still open {OUTPUT_DRAIN_TIMEOUT:?} after it exited, no longer forwarding it"
+            );
+            stdout.abort();
+            stderr.abort();
+        }
+    }
+}
+
+fn forward_lines<R: AsyncRead + Unpin + Send + 'static>(
+    reader: R,
+    log_channel: LogChannel,
+    name: &ExecutableName,
+) -> JoinHandle<()> {
+    let span = info_span!("running process", name = ?name);
+    tokio::spawn(async move {
+        let mut span = Some(span);
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let entered_span = span.take().expect("span").entered();
+            log_channel.send(line);
+            span = Some(entered_span.exit());
+        }
+    })
+}
+
+/// Awaits the process of an [Executable], and restarts it according to the
+/// [RestartSpec].
+struct Supervisor {
+    name: ExecutableName,
+    command: Command,
+    restart: RestartSpec,
+    status: watch::Sender<ExecutableStatus>,
+    stdout: LogChannel,
+    stderr: LogChannel,
+}
+
+impl Supervisor {
+    /// Runs until the process exits and is not restarted, or until `stop` is
+    /// signaled (or dropped with the [Executable]), in which case the process
+    /// is stopped. Returns the [ExitStatus] of the last process.
+    async fn run(
+        mut self,
+        process: Process,
+        stop: oneshot::Receiver<StopSpec>,
+    ) -> io::Result<ExitStatus> {
+        let exit_status = self.supervise(process, stop).await;
+
+        self.status.send_modify(|status| {
+            status.state = ProcessState::Exited;
+            status.pid = None;
+            if let Ok(exit_status) = &exit_status {
+                status.last_exit_status = Some(*exit_status);
+            }
+        });
+
+        exit_status
+    }
+
+    async fn supervise(
+        &mut self,
+        mut process: Process,
+        mut stop: oneshot::Receiver<StopSpec>,
+    ) -> io::Result<ExitStatus> {
+        let mut backoff = self.restart.backoff();
+
+        loop {
+            let exit_status = tokio::select! {
+                exit_status = process.child.wait() => exit_status?,
+                spec = &mut stop => {
+                    // If the executable was dropped, the process is killed
+                    let spec = spec.unwrap_or(StopSpec {
+                        signal: Signal::SIGKILL,
+                        grace_period: Duration::ZERO,
+                    });
+                    return process.stop(spec, &self.name).await;
+                }
+            };
+            process.drain_output(&self.name).await;
+
+            let restart_count = self.status.borrow().restart_count;
+            if !self.restart.should_restart(exit_status, restart_count) {
+                return Ok(exit_status);
+            }
+
+            self.status.send_modify(|status| {
+                status.state = ProcessState::Restarting;
+                status.pid = None;
+                status.last_exit_status = Some(exit_status);
+            });
+
+            let delay =
+                backoff.next_backoff().unwrap_or(self.restart.max_backoff);
+            info!(
+                "restarting executable {} in {delay:?} ({exit_status})",
+                self.name
+            );
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = &mut stop => return Ok(exit_status),
+            }
+
+            process = match Process::spawn(
+                &mut self.command,
+                &self.name,
+                &self.stdout,
+                &self.stderr,
+            ) {
+                Ok(process) => process,
+                Err(e) => {
+                    error!("failed to restart executable {}: {e}", self.name);
+                    return Ok(exit_status);
+                }
+            };
+
+            self.status.send_modify(|status| {
+                status.state = ProcessState::Running;
+                status.pid = process.pid();
+                status.started_at = Some(SystemTime::now());
+                status.restart_count += 1;
+            });
+        }
     }
 }
@@ -15,7 +15,10 @@
 
 use super::{
     Executable, ExecutableName, ExecutableSpec, ExecutablesError, Result,
+    StopSpec,
 };
+use crate::logging::{log_channel::LogChannelConfig, log_file::LogFileConfig};
+use nix::sys::signal::Signal;
 use std::{collections::HashMap, process::ExitStatus};
 
 type Cache = HashMap<ExecutableName, Executable>;
@@ -24,9 +27,20 @@ type Cache = HashMap<ExecutableName, Executable>;
 #[derive(Debug, Default)]
 pub struct Executables {
     cache: Cache,
+    /// The config of the log channels of the executables
+    log_config: LogChannelConfig,
+    /// Where the log files of the executables are written, if anywhere
+    log_files: Option<LogFileConfig>,
 }
 
 impl Executables {
+    pub fn new(
+        log_config: LogChannelConfig,
+        log_files: Option<LogFileConfig>,
+    ) -> Self {
+        Self { cache: Default::default(), log_config, log_files }
+    }
+
     pub fn start<T: Into<ExecutableSpec>>(
         &mut self,
         executable_spec: T,
@@ -44,7 +58,11 @@ impl Executables {
         }
 
         let executable_name = executable_spec.name.clone();
-        let mut executable = Executable::new(executable_spec);
+        let mut executable = Executable::new(
+            executable_spec,
+            &self.log_config,
+            self.log_files.as_ref(),
+        );
 
         // start the exe before we add it to the cache, as otherwise a failure leads to the
         // executable remaining in the cache and start cannot be called again.
@@ -72,60 +90,94 @@ impl Executables {
         Ok(executable)
     }
 
-    pub async fn stop(
-        &mut self,
+    /// Returns all the executables, including the ones that have exited.
+    pub fn iter(&self) -> impl Iterator<Item = &Executable> {
+        self.cache.values()
+    }
+
+    /// Sends `signal` to the running process of the executable.
+    pub fn signal(
+        &self,
         executable_name: &ExecutableName,
-    ) -> Result<ExitStatus> {
-        let Some(executable) = self.cache.get_mut(executable_name) else {
-            return Err(ExecutablesError::ExecutableNotFound {
+        signal: Signal,
+    ) -> Result<()> {
+        let executable = self.get(executable_name)?;
+
+        // There is no process while the executable waits to be restarted,
+        // or after it has exited for good
+        let Some(pid) = executable.pid().map_err(|e| {
+            ExecutablesError::FailedToSignalExecutable {
+                executable_name: executable_name.clone(),
+                source: e,
+            }
+        })?
+        else {
+            return Err(ExecutablesError::ExecutableNotRunning {
                 executable_name: executable_name.clone(),
             });
         };
 
-        let exit_status = executable.kill().await.map_err(|e| {
-            ExecutablesError::FailedToStopExecutable {
+        nix::sys::signal::kill(pid, signal).map_err(|e| {
+            ExecutablesError::FailedToSignalExecutable {
                 executable_name: executable_name.clone(),
-                source: e,
+                source: e.into(),
             }
-        })?;
+        })
+    }
 
-        let Some(exit_status) = exit_status else {
-            // Exes that never started return None
-            let executable =
-                self.cache.remove(executable_name).expect("exe in cache");
+    /// Removes the executable from the cache, and returns a future that stops
+    /// it as described by the [StopSpec]. The future does not borrow the cache,
+    /// so it can be awaited without blocking the other executables for the
+    /// grace period.
+    pub fn stop(
+        &mut self,
+        executable_name: &ExecutableName,
+        stop_spec: StopSpec,
+    ) -> Result<impl Future<Output = Result<ExitStatus>> + use<>> {
+        let Some(mut executable) = self.cache.remove(executable_name) else {
             return Err(ExecutablesError::ExecutableNotFound {
-                executable_name: executable.name,
+                executable_name: executable_name.clone(),
             });
         };
 
-        let _ = self.cache.remove(executable_name).ok_or_else(|| {
-            // get_mut would have already thrown this error, so we should never reach here
-            ExecutablesError::ExecutableNotFound {
-                executable_name: executable_name.clone(),
-            }
-        })?;
+        Ok(async move {
+            let exit_status =
+                executable.stop(stop_spec).await.map_err(|e| {
+                    ExecutablesError::FailedToStopExecutable {
+                        executable_name: executable.name.clone(),
+                        source: e,
+                    }
+                })?;
 
-        Ok(exit_status)
+            // Exes that never started return None
+            exit_status.ok_or(ExecutablesError::ExecutableNotFound {
+                executable_name: executable.name,
+            })
+        })
     }
 
     /// Stops all executables concurrently
-    pub async fn broadcast_stop(&mut self) {
-        let mut names = vec![];
-        for exe in self.cache.values_mut() {
-            let _ = exe.kill().await;
-            names.push(exe.name.clone())
-        }
+    pub async fn broadcast_stop(&mut self, stop_spec: StopSpec) {
+        let _ = futures::future::join_all(
+            self.cache.values_mut().map(|exe| exe.stop(stop_spec)),
+        )
+        .await;
 
-        for name in names {
-            let _ = self.cache.remove(&name);
-        }
+        self.cache.clear();
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cells::cell_service::executables::{
+        Capabilities, ProcessState, RestartPolicy, RestartSpec, Rlimit,
+    };
+    use crate::cells::cell_service::seccomp::SeccompProfile;
+    use crate::logging::log_channel::LogReplay;
     use std::os::unix::process::ExitStatusExt;
+    use std::time::Duration;
+    use test_helpers::*;
     use tokio::process::Command;
 
     fn spec_for(name: &ExecutableName) -> ExecutableSpec {
@@ -136,9 +188,61 @@ mod tests {
             name: name.clone(),
             description: format!("test executable {name}"),
             command,
+            restart: Default::default(),
+            seccomp_profile: None,
+            capabilities: Capabilities::Privileged,
+            rlimits: vec![],
         }
     }
 
+    /// Runs the shell script as an executable to its exit, and returns its
+    /// exit code and the lines it printed on stdout.
+    async fn run_script(
+        script: &str,
+        capabilities: Capabilities,
+        rlimits: Vec<Rlimit>,
+    ) -> (Option<i32>, Vec<String>) {
+        let mut executables = Executables::default();
+        let exe_name = ExecutableName::new(format!(
+            "unit-test-exe-{}",
+            uuid::Uuid::new_v4()
+        ));
+
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c");
+        let _ = command.arg(script);
+        let spec = ExecutableSpec {
+            name: exe_name.clone(),
+            description: format!("test executable {exe_name}"),
+            command,
+            restart: Default::default(),
+            seccomp_profile: None,
+            capabilities,
+            rlimits,
+        };
+
+        let executable =
+            executables.start(spec, None, None).expect("start executable");
+        let mut status = executable.watch_status();
+
+        let status = tokio::time::timeout(
+            Duration::from_secs(5),
+            status.wait_for(|status| status.state == ProcessState::Exited),
+        )
+        .await
+        .expect("executable exited")
+        .map(|status| *status)
+        .expect("supervisor reported exit");
+
+        // The output is forwarded before the exit is reported
+        let (stdout, _) =
+            executable.stdout.subscribe_from(LogReplay::FromBeginning).await;
+        (
+            status.last_exit_status.and_then(|status| status.code()),
+            stdout.into_iter().map(|x| x.line).collect(),
+        )
+    }
+
     #[tokio::test]
     async fn start_should_cache_pid_and_reject_duplicates() {
         let mut executables = Executables::default();
@@ -161,11 +265,345 @@ mod tests {
             "expected ExecutableExists error, got {err:?}"
         );
 
-        let status =
-            executables.stop(&exe_name).await.expect("stop executable");
+        let status = executables
+            .stop(&exe_name, StopSpec::default())
+            .expect("executable")
+            .await
+            .expect("stop executable");
+        assert!(
+            status.success() || status.signal() == Some(15),
+            "expected graceful stop or SIGTERM, got status {status:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn supervisor_should_restart_failed_executable() {
+        let mut executables = Executables::default();
+        let exe_name = ExecutableName::new(format!(
+            "unit-test-exe-{}",
+            uuid::Uuid::new_v4()
+        ));
+
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c");
+        let _ = command.arg("exit 3");
+        let spec = ExecutableSpec {
+            name: exe_name.clone(),
+            description: format!("test executable {exe_name}"),
+            command,
+            restart: RestartSpec {
+                policy: RestartPolicy::OnFailure,
+                max_restarts: Some(2),
+                backoff: Duration::from_millis(10),
+                max_backoff: Duration::from_millis(10),
+            },
+            seccomp_profile: None,
+            capabilities: Capabilities::Privileged,
+            rlimits: vec![],
+        };
+
+        let _ = executables.start(spec, None, None).expect("start executable");
+
+        // Wait for the supervisor to give up after the last restart
+        let status = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let status =
+                    executables.get(&exe_name).expect("executable").status();
+                if status.restart_count == 2 && status.pid.is_none() {
+                    break status;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("executable restarted twice");
+
+        assert_eq!(
+            status.last_exit_status.and_then(|status| status.code()),
+            Some(3)
+        );
+
+        let status = executables
+            .stop(&exe_name, StopSpec::default())
+            .expect("executable")
+            .await
+            .expect("stop executable");
+        assert_eq!(status.code(), Some(3));
+    }
+
+    #[tokio::test]
+    async fn watch_status_should_report_exit() {
+        let mut executables = Executables::default();
+        let exe_name = ExecutableName::new(format!(
+            "unit-test-exe-{}",
+            uuid::Uuid::new_v4()
+        ));
+
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c");
+        let _ = command.arg("exit 7");
+        let spec = ExecutableSpec {
+            name: exe_name.clone(),
+            description: format!("test executable {exe_name}"),
+            command,
+            restart: Default::default(),
+            seccomp_profile: None,
+            capabilities: Capabilities::Privileged,
+            rlimits: vec![],
+        };
+
+        let executable =
+            executables.start(spec, None, None).expect("start executable");
+        let mut status = executable.watch_status();
+        assert!(status.borrow().started_at.is_some());
+
+        let status = tokio::time::timeout(
+            Duration::from_secs(5),
+            status.wait_for(|status| status.state == ProcessState::Exited),
+        )
+        .await
+        .expect("executable exited")
+        .map(|status| *status)
+        .expect("supervisor reported exit");
+
+        assert_eq!(status.pid, None);
+        assert_eq!(
+            status.last_exit_status.and_then(|status| status.code()),
+            Some(7)
+        );
+    }
+
+    #[tokio::test]
+    async fn start_should_install_seccomp_profile() {
+        let mut executables = Executables::default();
+        let exe_name = ExecutableName::new(format!(
+            "unit-test-exe-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let dir = std::env::temp_dir().join(exe_name.to_string());
+
+        let mut command = Command::new("mkdir");
+        let _ = command.arg(&dir);
+        let profile = SeccompProfile::parse(String::from(
+            r#"{
+                "defaultAction": "SCMP_ACT_ALLOW",
+                "syscalls": [
+                    {
+                        "names": ["mkdir", "mkdirat"],
+                        "action": "SCMP_ACT_ERRNO"
+                    }
+                ]
+            }"#,
+        ))
+        .expect("profile");
+        let spec = ExecutableSpec {
+            name: exe_name.clone(),
+            description: format!("test executable {exe_name}"),
+            command,
+            restart: Default::default(),
+            seccomp_profile: Some(profile),
+            capabilities: Capabilities::Privileged,
+            rlimits: vec![],
+        };
+
+        let executable =
+            executables.start(spec, None, None).expect("start executable");
+        let mut status = executable.watch_status();
+
+        let status = tokio::time::timeout(
+            Duration::from_secs(5),
+            status.wait_for(|status| status.state == ProcessState::Exited),
+        )
+        .await
+        .expect("executable exited")
+        .map(|status| *status)
+        .expect("supervisor reported exit");
+
+        assert_ne!(
+            status.last_exit_status.and_then(|status| status.code()),
+            Some(0)
+        );
+        assert!(!dir.exists());
+    }
+
+    #[tokio::test]
+    async fn start_should_restrict_capabilities() {
+        skip_if_not_root!("start_should_restrict_capabilities");
+
+        let (code, stdout) = run_script(
+            "grep -E '^(Cap[A-Za-z]+|NoNewPrivs):' /proc/self/status",
+            Capabilities::default(),
+            vec![],
+        )
+        .await;
+        assert_eq!(code, Some(0));
+
+        let status: HashMap<&str, &str> = stdout
+            .iter()
+            .filter_map(|x| x.split_once(':'))
+            .map(|(key, value)| (key, value.trim()))
+            .collect();
+        // CAP_KILL (5), CAP_NET_BIND_SERVICE (10) and CAP_AUDIT_WRITE (29)
+        for set in ["CapInh", "CapPrm", "CapEff", "CapBnd", "CapAmb"] {
+            assert_eq!(status.get(set), Some(&"0000000020000420"), "{set}");
+        }
+        assert_eq!(status.get("NoNewPrivs"), Some(&"1"));
+    }
+
+    #[tokio::test]
+    async fn start_should_set_rlimits() {
+        let (code, stdout) = run_script(
+            "ulimit -n; ulimit -Hn",
+            Capabilities::Privileged,
+            vec![Rlimit {
+                resource: nix::sys::resource::Resource::RLIMIT_NOFILE,
+                soft: Some(256),
+                hard: Some(512),
+            }],
+        )
+        .await;
+        assert_eq!(code, Some(0));
+        assert_eq!(stdout, ["256", "512"]);
+    }
+
+    #[tokio::test]
+    async fn start_should_keep_the_hard_limit_if_not_set() {
+        let (_, hard) = nix::sys::resource::getrlimit(
+            nix::sys::resource::Resource::RLIMIT_NOFILE,
+        )
+        .expect("getrlimit");
+
+        let (code, stdout) = run_script(
+            "ulimit -n; ulimit -Hn",
+            Capabilities::Privileged,
+            vec![Rlimit {
+                resource: nix::sys::resource::Resource::RLIMIT_NOFILE,
+                soft: Some(256),
+                hard: None,
+            }],
+        )
+        .await;
+        assert_eq!(code, Some(0));
+        assert_eq!(stdout, [String::from("256"), hard.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn stop_should_kill_after_grace_period() {
+        let mut executables = Executables::default();
+        let exe_name = ExecutableName::new(format!(
+            "unit-test-exe-{}",
+            uuid::Uuid::new_v4()
+        ));
+
+        // Ignore SIGTERM, so the grace period must run out
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c");
+        let _ = command.arg("trap '' TERM; while true; do sleep 1; done");
+        let spec = ExecutableSpec {
+            name: exe_name.clone(),
+            description: format!("test executable {exe_name}"),
+            command,
+            restart: Default::default(),
+            seccomp_profile: None,
+            capabilities: Capabilities::Privileged,
+            rlimits: vec![],
+        };
+
+        let _ = executables.start(spec, None, None).expect("start executable");
+        // Give the shell time to install the trap
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        let status = executables
+            .stop(
+                &exe_name,
+                StopSpec {
+                    signal: nix::sys::signal::Signal::SIGTERM,
+                    grace_period: Duration::from_millis(200),
+                },
+            )
+            .expect("executable")
+            .await
+            .expect("stop executable");
+        assert_eq!(status.signal(), Some(9));
+    }
+
+    #[tokio::test]
+    async fn stop_should_not_wait_for_output_held_open_by_children() {
+        let mut executables = Executables::default();
+        let exe_name = ExecutableName::new(format!(
+            "unit-test-exe-{}",
+            uuid::Uuid::new_v4()
+        ));
+
+        // The background sleep outlives the shell, and keeps its stdout open
+        let mut command = Command::new("sh");
+        let _ = command.arg("-c");
+        let _ = command.arg("sleep 30 & sleep 30");
+        let spec = ExecutableSpec {
+            name: exe_name.clone(),
+            description: format!("test executable {exe_name}"),
+            command,
+            restart: Default::default(),
+            seccomp_profile: None,
+            capabilities: Capabilities::Privileged,
+            rlimits: vec![],
+        };
+
+        let _ = executables.start(spec, None, None).expect("start executable");
+        let stop = executables
+            .stop(
+                &exe_name,
+                StopSpec {
+                    signal: nix::sys::signal::Signal::SIGTERM,
+                    grace_period: Duration::from_secs(1),
+                },
+            )
+            .expect("executable");
+
+        let status = tokio::time::timeout(Duration::from_secs(10), stop)
+            .await
+            .expect("stopped without waiting for the output")
+            .expect("stop executable");
+        assert_eq!(status.signal(), Some(15));
+    }
+
+    #[tokio::test]
+    async fn signal_should_reach_running_executable() {
+        let mut executables = Executables::default();
+        let exe_name = ExecutableName::new(format!(
+            "unit-test-exe-{}",
+            uuid::Uuid::new_v4()
+        ));
+
+        let _ = executables
+            .start(spec_for(&exe_name), None, None)
+            .expect("start executable");
+
+        executables
+            .signal(&exe_name, Signal::SIGUSR1)
+            .expect("signal executable");
+
+        // The default action of SIGUSR1 is to terminate the process
+        let status = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let status =
+                    executables.get(&exe_name).expect("executable").status();
+                if let Some(exit_status) = status.last_exit_status {
+                    break exit_status;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("executable exited");
+        assert_eq!(status.signal(), Some(Signal::SIGUSR1 as i32));
+
+        let err = executables
+            .signal(&exe_name, Signal::SIGUSR1)
+            .expect_err("signal exited executable");
         assert!(
-            status.success() || status.signal() == Some(9),
-            "expected graceful stop or SIGKILL, got status {status:?}"
+            matches!(err, ExecutablesError::ExecutableNotRunning { .. }),
+            "expected ExecutableNotRunning error, got {err:?}"
         );
     }
 }
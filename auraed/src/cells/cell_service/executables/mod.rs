@@ -13,20 +13,43 @@
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
 
+use super::seccomp::SeccompProfile;
+pub use capabilities::{Capabilities, CapabilitySets, DEFAULT_CAPABILITIES};
 pub use error::{ExecutablesError, Result};
-pub use executable::Executable;
+pub use exec::{
+    ExecOutput, ExecProcess, ExecSpec, ExecStdin, ExecTerminal, TerminalSize,
+};
+pub use executable::{Executable, ExecutableStatus, ProcessState};
 pub use executable_name::ExecutableName;
 pub use executables::Executables;
+pub use restart::{
+    DEFAULT_MAX_RESTART_BACKOFF, DEFAULT_RESTART_BACKOFF, RestartPolicy,
+    RestartSpec,
+};
+pub use rlimit::Rlimit;
+pub use stop::{
+    DEFAULT_STOP_GRACE_PERIOD, DEFAULT_STOP_SIGNAL, MAX_STOP_GRACE_PERIOD,
+    StopSpec,
+};
 use tokio::process::Command;
 
+mod capabilities;
 mod error;
+mod exec;
 mod executable;
 mod executable_name;
 #[allow(clippy::module_inception)]
 mod executables;
+mod restart;
+mod rlimit;
+mod stop;
 
 pub struct ExecutableSpec {
     pub name: ExecutableName,
     pub description: String,
     pub command: Command,
+    pub restart: RestartSpec,
+    pub seccomp_profile: Option<SeccompProfile>,
+    pub capabilities: Capabilities,
+    pub rlimits: Vec<Rlimit>,
 }
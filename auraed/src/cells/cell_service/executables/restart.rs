@@ -0,0 +1,147 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use backoff::ExponentialBackoff;
+use std::process::ExitStatus;
+use std::time::Duration;
+
+/// Delay before the first restart, when the request does not set one.
+pub const DEFAULT_RESTART_BACKOFF: Duration = Duration::from_millis(100);
+/// Upper bound of the delay between restarts, when the request does not set one.
+pub const DEFAULT_MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+
+/// When the process of an executable is restarted after it exits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    #[default]
+    Never,
+    /// Only if the process did not exit successfully (e.g., non-zero exit
+    /// code or killed by a signal).
+    OnFailure,
+    Always,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartSpec {
+    pub policy: RestartPolicy,
+    /// Unlimited if [None]
+    pub max_restarts: Option<u32>,
+    /// Delay before the first restart, doubled after every restart
+    pub backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RestartSpec {
+    fn default() -> Self {
+        Self {
+            policy: RestartPolicy::default(),
+            max_restarts: None,
+            backoff: DEFAULT_RESTART_BACKOFF,
+            max_backoff: DEFAULT_MAX_RESTART_BACKOFF,
+        }
+    }
+}
+
+impl RestartSpec {
+    /// Returns true if a process that exited with `exit_status` should be
+    /// restarted, given the number of times it has already been restarted.
+    pub fn should_restart(
+        &self,
+        exit_status: ExitStatus,
+        restart_count: u32,
+    ) -> bool {
+        let restart = match self.policy {
+            RestartPolicy::Never => false,
+            RestartPolicy::OnFailure => !exit_status.success(),
+            RestartPolicy::Always => true,
+        };
+
+        restart && self.max_restarts.is_none_or(|max| restart_count < max)
+    }
+
+    /// The delays between restarts. Never runs out, as the number of
+    /// restarts is limited by `max_restarts` instead.
+    pub fn backoff(&self) -> ExponentialBackoff {
+        backoff::ExponentialBackoffBuilder::new()
+            .with_initial_interval(self.backoff)
+            .with_multiplier(2.0)
+            .with_randomization_factor(0.0)
+            .with_max_interval(self.max_backoff)
+            .with_max_elapsed_time(None)
+            .build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use backoff::backoff::Backoff;
+    use std::os::unix::process::ExitStatusExt;
+
+    fn exited(code: i32) -> ExitStatus {
+        ExitStatus::from_raw(code << 8)
+    }
+
+    fn killed(signal: i32) -> ExitStatus {
+        ExitStatus::from_raw(signal)
+    }
+
+    #[test]
+    fn should_restart_must_follow_policy() {
+        let spec = |policy| RestartSpec { policy, ..Default::default() };
+
+        let never = spec(RestartPolicy::Never);
+        assert!(!never.should_restart(exited(0), 0));
+        assert!(!never.should_restart(exited(1), 0));
+
+        let on_failure = spec(RestartPolicy::OnFailure);
+        assert!(!on_failure.should_restart(exited(0), 0));
+        assert!(on_failure.should_restart(exited(1), 0));
+        assert!(on_failure.should_restart(killed(9), 0));
+
+        let always = spec(RestartPolicy::Always);
+        assert!(always.should_restart(exited(0), 0));
+        assert!(always.should_restart(exited(1), 0));
+    }
+
+    #[test]
+    fn should_restart_must_respect_max_restarts() {
+        let spec = RestartSpec {
+            policy: RestartPolicy::Always,
+            max_restarts: Some(2),
+            ..Default::default()
+        };
+
+        assert!(spec.should_restart(exited(0), 0));
+        assert!(spec.should_restart(exited(0), 1));
+        assert!(!spec.should_restart(exited(0), 2));
+    }
+
+    #[test]
+    fn backoff_must_double_up_to_max() {
+        let spec = RestartSpec {
+            policy: RestartPolicy::Always,
+            max_restarts: None,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+        };
+
+        let mut backoff = spec.backoff();
+        assert_eq!(backoff.next_backoff(), Some(Duration::from_millis(100)));
+        assert_eq!(backoff.next_backoff(), Some(Duration::from_millis(200)));
+        assert_eq!(backoff.next_backoff(), Some(Duration::from_millis(300)));
+        assert_eq!(backoff.next_backoff(), Some(Duration::from_millis(300)));
+    }
+}
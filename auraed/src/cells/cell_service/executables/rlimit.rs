@@ -0,0 +1,55 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use nix::libc::rlim_t;
+use nix::sys::resource::{self, Resource};
+use tokio::process::Command;
+
+/// A resource limit of the processes of an [Executable](super::Executable).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rlimit {
+    pub resource: Resource,
+    /// The hard limit if [None]
+    pub soft: Option<u64>,
+    /// The current hard limit if [None], as raising it requires
+    /// CAP_SYS_RESOURCE
+    pub hard: Option<u64>,
+}
+
+impl Rlimit {
+    /// Adds a `pre_exec` hook setting the limits with setrlimit(2). Must be
+    /// called before dropping the capabilities of the process, as raising a
+    /// hard limit requires CAP_SYS_RESOURCE.
+    pub fn install(rlimits: &[Rlimit], command: &mut Command) {
+        if rlimits.is_empty() {
+            return;
+        }
+
+        let rlimits = rlimits.to_vec();
+        unsafe {
+            let _ = command.pre_exec(move || {
+                for Rlimit { resource, soft, hard } in &rlimits {
+                    let hard = match hard {
+                        Some(hard) => *hard as rlim_t,
+                        None => resource::getrlimit(*resource)?.1,
+                    };
+                    let soft = soft.map_or(hard, |x| x as rlim_t);
+                    resource::setrlimit(*resource, soft, hard)?;
+                }
+                Ok(())
+            });
+        }
+    }
+}
@@ -0,0 +1,42 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use nix::sys::signal::Signal;
+use std::time::Duration;
+
+/// Signal sent to stop an executable, when the request does not set one.
+pub const DEFAULT_STOP_SIGNAL: Signal = Signal::SIGTERM;
+/// Time given to an executable to exit after the stop signal, when the
+/// request does not set one.
+pub const DEFAULT_STOP_GRACE_PERIOD: Duration = Duration::from_secs(10);
+/// The longest time an executable can be given to exit after the stop signal.
+pub const MAX_STOP_GRACE_PERIOD: Duration = Duration::from_secs(300);
+
+/// How the process of an executable is stopped: `signal` is sent first, and
+/// SIGKILL follows if the process has not exited within `grace_period`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StopSpec {
+    pub signal: Signal,
+    pub grace_period: Duration,
+}
+
+impl Default for StopSpec {
+    fn default() -> Self {
+        Self {
+            signal: DEFAULT_STOP_SIGNAL,
+            grace_period: DEFAULT_STOP_GRACE_PERIOD,
+        }
+    }
+}
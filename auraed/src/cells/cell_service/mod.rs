@@ -13,11 +13,15 @@
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
 pub use cell_service::CellService;
+pub use cells::CellName;
 use error::Result;
+pub use executables::ExecutableName;
 
 #[allow(clippy::module_inception)]
 mod cell_service;
 mod cells;
+mod cells_state;
 mod error;
 mod executables;
+mod seccomp;
 mod validation;
@@ -0,0 +1,375 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+//! Seccomp profiles, in the JSON format of the `linux.seccomp` field of the
+//! OCI runtime spec, compiled to BPF filters with seccompiler.
+//! Docs: https://github.com/opencontainers/runtime-spec/blob/main/config-linux.md#seccomp
+
+use crate::AURAED_RUNTIME;
+use oci_spec::runtime::{
+    LinuxSeccomp, LinuxSeccompAction, LinuxSeccompOperator, LinuxSyscall,
+};
+use seccompiler::{
+    BpfProgram, SeccompAction, SeccompCmpArgLen, SeccompCmpOp,
+    SeccompCondition, SeccompFilter, SeccompRule, TargetArch,
+};
+use std::collections::BTreeMap;
+use std::io;
+use thiserror::Error;
+use tokio::process::Command;
+use tracing::debug;
+
+mod syscalls;
+
+/// The name of the built-in profile.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// The built-in profile, which allows every syscall but the ones that are
+/// not namespaced (e.g., `reboot`, `settimeofday`) or escape the
+/// isolation of a cell (e.g., `mount`, `setns`, `ptrace`, and `unshare` or
+/// `clone` creating namespaces).
+/// Unlike the default profile of Docker, it is a denylist: the syscalls it
+/// does not know of are allowed. `clone3` fails with ENOSYS, as its flags
+/// can not be filtered, so the C libraries fall back to `clone`.
+const DEFAULT_PROFILE_JSON: &str = include_str!("default.json");
+
+/// The errno returned by SCMP_ACT_ERRNO if the profile does not set one,
+/// like runc does.
+const DEFAULT_ERRNO: u32 = nix::libc::EPERM as u32;
+
+#[derive(Debug, Error)]
+pub enum SeccompError {
+    #[error("invalid seccomp profile: {0}")]
+    InvalidProfile(#[from] serde_json::Error),
+    #[error("seccomp action {0:?} is not supported")]
+    UnsupportedAction(LinuxSeccompAction),
+    #[error("failed to compile seccomp profile: {0}")]
+    FailedToCompile(String),
+}
+
+/// A seccomp profile of a cell or an executable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeccompProfile {
+    /// The profile as it was given ("default" or JSON)
+    source: String,
+    spec: LinuxSeccomp,
+}
+
+impl SeccompProfile {
+    /// Parses the built-in profile if `profile` is "default", or the profile
+    /// in the JSON format of the OCI runtime spec otherwise. Fields of other
+    /// formats (e.g., `includes` of Docker) are ignored.
+    pub fn parse(profile: String) -> Result<Self, SeccompError> {
+        let json = if profile == DEFAULT_PROFILE {
+            DEFAULT_PROFILE_JSON
+        } else {
+            &profile
+        };
+        let spec = serde_json::from_str(json)?;
+        Ok(Self { source: profile, spec })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// Compiles the profile to BPF programs for the architecture auraed is
+    /// built for. Syscalls that are unknown, or do not exist on the
+    /// architecture, are ignored like libseccomp does.
+    ///
+    /// A BPF program has a single action for the syscalls it matches, so the
+    /// profile is compiled to a program per action, which are stacked: the
+    /// kernel takes the most restrictive action of the programs.
+    ///  - The first program allows the syscalls matched by any rule, and
+    ///    takes the default action otherwise.
+    ///  - The other programs take their action for the syscalls matched by
+    ///    their rules, and allow the rest.
+    pub fn compile(&self) -> Result<SeccompFilters, SeccompError> {
+        let arch: TargetArch = std::env::consts::ARCH
+            .try_into()
+            .map_err(|e| SeccompError::FailedToCompile(format!("{e}")))?;
+
+        let default_action = seccomp_action(
+            self.spec.default_action(),
+            self.spec.default_errno_ret(),
+        )?;
+
+        let mut matched = SyscallRules::default();
+        let mut by_action: Vec<(SeccompAction, SyscallRules)> = vec![];
+        for syscall in self.spec.syscalls().iter().flatten() {
+            let action = seccomp_action(syscall.action(), syscall.errno_ret())?;
+            let rule = seccomp_rule(syscall)?;
+
+            let i = match by_action.iter().position(|(x, _)| *x == action) {
+                Some(i) => i,
+                None => {
+                    by_action.push((action.clone(), SyscallRules::default()));
+                    by_action.len() - 1
+                }
+            };
+            let rules = &mut by_action[i].1;
+
+            for name in syscall.names() {
+                let Some(nr) = syscalls::number(name) else {
+                    debug!(
+                        "ignoring unknown syscall {name} of seccomp profile"
+                    );
+                    continue;
+                };
+                matched.insert(nr, rule.clone());
+                if action != SeccompAction::Allow {
+                    rules.insert(nr, rule.clone());
+                }
+            }
+        }
+
+        let mut filters = vec![];
+        if default_action != SeccompAction::Allow {
+            filters.push(matched.compile(
+                default_action,
+                SeccompAction::Allow,
+                arch,
+            )?);
+        }
+        for (action, rules) in by_action {
+            if action != SeccompAction::Allow {
+                filters.push(rules.compile(
+                    SeccompAction::Allow,
+                    action,
+                    arch,
+                )?);
+            }
+        }
+
+        Ok(SeccompFilters(filters))
+    }
+}
+
+/// Compiles the filters of a process spawned by auraed: the ones of the
+/// profiles of the cell auraed is nested in, if any (see
+/// [crate::AuraedRuntime::cell_seccomp_profiles]), then the ones of the
+/// profile of the process.
+/// Returns [None] if no syscalls are filtered.
+pub fn process_filters(
+    profile: Option<&SeccompProfile>,
+) -> Result<Option<SeccompFilters>, SeccompError> {
+    let cell_profiles = AURAED_RUNTIME
+        .get()
+        .map(|x| x.cell_seccomp_profiles.as_slice())
+        .unwrap_or_default();
+
+    let mut programs = vec![];
+    for profile in cell_profiles.iter() {
+        let profile = SeccompProfile::parse(profile.clone())?;
+        programs.extend(profile.compile()?.0);
+    }
+    if let Some(profile) = profile {
+        programs.extend(profile.compile()?.0);
+    }
+
+    Ok((!programs.is_empty()).then_some(SeccompFilters(programs)))
+}
+
+/// The BPF programs of a [SeccompProfile].
+#[derive(Debug, Clone)]
+pub struct SeccompFilters(Vec<BpfProgram>);
+
+impl SeccompFilters {
+    /// Installs the programs on the calling thread, which is meant to be
+    /// called in `pre_exec`. The programs are inherited by the children of
+    /// the process, and can not be removed.
+    /// The `no_new_privs` bit is set, so executing a set-user-id program
+    /// does not grant its privileges.
+    pub fn apply(&self) -> io::Result<()> {
+        for filter in &self.0 {
+            seccompiler::apply_filter(filter)
+                .map_err(|e| io::Error::other(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Applies the programs in `pre_exec` of the command, after the
+    /// closures registered before, so they do not filter their syscalls.
+    pub fn install(&self, command: &mut Command) {
+        let filters = self.clone();
+        unsafe {
+            let _ = command.pre_exec(move || filters.apply());
+        }
+    }
+}
+
+/// The rules of the syscalls matched by a program, where a syscall without
+/// rules is matched regardless of its arguments.
+#[derive(Debug, Default)]
+struct SyscallRules(BTreeMap<i64, Option<Vec<SeccompRule>>>);
+
+impl SyscallRules {
+    fn insert(&mut self, nr: i64, rule: Option<SeccompRule>) {
+        let rules = self.0.entry(nr).or_insert_with(|| Some(vec![]));
+        match (rules, rule) {
+            (Some(rules), Some(rule)) => rules.push(rule),
+            // matched regardless of its arguments
+            (rules, None) => *rules = None,
+            (None, Some(_)) => {}
+        }
+    }
+
+    fn compile(
+        self,
+        mismatch_action: SeccompAction,
+        match_action: SeccompAction,
+        arch: TargetArch,
+    ) -> Result<BpfProgram, SeccompError> {
+        // seccompiler matches a syscall with an empty list of rules
+        // regardless of its arguments
+        let rules = self
+            .0
+            .into_iter()
+            .map(|(nr, rules)| (nr, rules.unwrap_or_default()))
+            .collect();
+
+        SeccompFilter::new(rules, mismatch_action, match_action, arch)
+            .and_then(BpfProgram::try_from)
+            .map_err(|e| SeccompError::FailedToCompile(format!("{e}")))
+    }
+}
+
+fn seccomp_action(
+    action: LinuxSeccompAction,
+    errno_ret: Option<u32>,
+) -> Result<SeccompAction, SeccompError> {
+    Ok(match action {
+        LinuxSeccompAction::ScmpActAllow => SeccompAction::Allow,
+        LinuxSeccompAction::ScmpActErrno => {
+            SeccompAction::Errno(errno_ret.unwrap_or(DEFAULT_ERRNO))
+        }
+        LinuxSeccompAction::ScmpActKill
+        | LinuxSeccompAction::ScmpActKillThread => SeccompAction::KillThread,
+        LinuxSeccompAction::ScmpActKillProcess => SeccompAction::KillProcess,
+        LinuxSeccompAction::ScmpActLog => SeccompAction::Log,
+        LinuxSeccompAction::ScmpActTrace => {
+            SeccompAction::Trace(errno_ret.unwrap_or_default())
+        }
+        LinuxSeccompAction::ScmpActTrap => SeccompAction::Trap,
+        action => return Err(SeccompError::UnsupportedAction(action)),
+    })
+}
+
+/// Returns the rule of the arguments of the syscall, which must all match,
+/// or [None] if the syscall is matched regardless of its arguments.
+fn seccomp_rule(
+    syscall: &LinuxSyscall,
+) -> Result<Option<SeccompRule>, SeccompError> {
+    let Some(args) = syscall.args().as_ref().filter(|x| !x.is_empty()) else {
+        return Ok(None);
+    };
+
+    let conditions = args
+        .iter()
+        .map(|arg| {
+            use LinuxSeccompOperator as Op;
+
+            let index = u8::try_from(arg.index()).map_err(|_| {
+                SeccompError::FailedToCompile(format!(
+                    "invalid argument index {}",
+                    arg.index()
+                ))
+            })?;
+            let op = match arg.op() {
+                Op::ScmpCmpNe => SeccompCmpOp::Ne,
+                Op::ScmpCmpLt => SeccompCmpOp::Lt,
+                Op::ScmpCmpLe => SeccompCmpOp::Le,
+                Op::ScmpCmpEq => SeccompCmpOp::Eq,
+                Op::ScmpCmpGe => SeccompCmpOp::Ge,
+                Op::ScmpCmpGt => SeccompCmpOp::Gt,
+                Op::ScmpCmpMaskedEq => SeccompCmpOp::MaskedEq(arg.value()),
+            };
+            // the value of SCMP_CMP_MASKED_EQ is the mask, and valueTwo the
+            // value the masked argument is compared with
+            let value = match arg.op() {
+                Op::ScmpCmpMaskedEq => arg.value_two().unwrap_or_default(),
+                _ => arg.value(),
+            };
+
+            SeccompCondition::new(index, SeccompCmpArgLen::Qword, op, value)
+                .map_err(|e| SeccompError::FailedToCompile(format!("{e}")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    SeccompRule::new(conditions)
+        .map(Some)
+        .map_err(|e| SeccompError::FailedToCompile(format!("{e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_profile_must_compile() {
+        let profile = SeccompProfile::parse(DEFAULT_PROFILE.into())
+            .expect("default profile");
+        assert_eq!(profile.as_str(), DEFAULT_PROFILE);
+
+        // the default action is to allow, so only the programs of the EPERM
+        // and ENOSYS errnos are left
+        let filters = profile.compile().expect("compile");
+        assert_eq!(filters.0.len(), 2);
+    }
+
+    #[test]
+    fn compile_must_stack_a_program_per_action() {
+        let profile = SeccompProfile::parse(
+            r#"{
+                "defaultAction": "SCMP_ACT_ERRNO",
+                "syscalls": [
+                    {
+                        "names": ["read", "write", "not_a_syscall"],
+                        "action": "SCMP_ACT_ALLOW"
+                    },
+                    {
+                        "names": ["personality"],
+                        "action": "SCMP_ACT_LOG",
+                        "args": [
+                            { "index": 0, "value": 8, "op": "SCMP_CMP_EQ" }
+                        ]
+                    }
+                ]
+            }"#
+            .into(),
+        )
+        .expect("profile");
+
+        let filters = profile.compile().expect("compile");
+        assert_eq!(filters.0.len(), 2);
+    }
+
+    #[test]
+    fn parse_must_reject_invalid_profiles() {
+        assert!(matches!(
+            SeccompProfile::parse("not json".into()),
+            Err(SeccompError::InvalidProfile(_))
+        ));
+
+        let notify = SeccompProfile::parse(
+            r#"{ "defaultAction": "SCMP_ACT_NOTIFY" }"#.into(),
+        )
+        .expect("profile");
+        assert!(matches!(
+            notify.compile(),
+            Err(SeccompError::UnsupportedAction(_))
+        ));
+    }
+}
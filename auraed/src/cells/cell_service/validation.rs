@@ -17,16 +17,35 @@ use super::cells::{
     cgroups::{
         self, CgroupSpec, Limit, Protection, Weight,
         cpuset::{Cpus, Mems},
+        io::Device,
+        pids::PidsMax,
+        pressure::{PressureKind, PressureResource},
     },
 };
-use super::executables::ExecutableName;
+use super::executables::{
+    CapabilitySets, DEFAULT_MAX_RESTART_BACKOFF, DEFAULT_RESTART_BACKOFF,
+    DEFAULT_STOP_GRACE_PERIOD, DEFAULT_STOP_SIGNAL, ExecutableName,
+    MAX_STOP_GRACE_PERIOD, RestartPolicy, RestartSpec,
+};
+use super::seccomp::SeccompProfile;
 use crate::cells::cell_service::cells::CellName;
+use caps::{Capability, CapsHashSet};
+use nix::sys::signal::Signal;
 use proto::cells::{
-    Cell, CellServiceAllocateRequest, CellServiceFreeRequest,
-    CellServiceStartRequest, CellServiceStopRequest, CpuController,
-    CpusetController, Executable, MemoryController,
+    Capabilities, Cell, CellServiceAllocateRequest, CellServiceExecRequest,
+    CellServiceFreeRequest, CellServiceListExecutablesRequest,
+    CellServicePauseRequest, CellServiceResumeRequest,
+    CellServiceSignalRequest, CellServiceStartRequest, CellServiceStatsRequest,
+    CellServiceStopRequest, CellServiceUpdateRequest, CellServiceWaitRequest,
+    CellServiceWatchPressureRequest, CellServiceWatchStatsRequest,
+    CpuController, CpusetController, EnvironmentVariable, Executable,
+    IdMapping, IoController, IoDeviceController, MemoryController, Mount,
+    MountKind, MountPropagation, PidsController, Rlimit, RlimitKind, RootFs,
+    TerminalSize,
 };
 use std::ffi::OsString;
+use std::path::{Component, PathBuf};
+use std::time::Duration;
 use tokio::process::Command;
 use validation::{ValidatedType, ValidationError};
 use validation_macros::ValidatedType;
@@ -59,6 +78,29 @@ impl CellServiceAllocateRequestTypeValidator
     }
 }
 
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceUpdateRequest {
+    #[field_type(Option<Cell>)]
+    pub cell: ValidatedCell,
+}
+
+impl CellServiceUpdateRequestTypeValidator
+    for CellServiceUpdateRequestValidator
+{
+    fn validate_cell(
+        cell: Option<Cell>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<ValidatedCell, ValidationError> {
+        let cell = validation::required(cell, field_name, parent_name)?;
+
+        ValidatedCell::validate(
+            cell,
+            Some(&validation::field_name(field_name, parent_name)),
+        )
+    }
+}
+
 #[derive(ValidatedType, Debug, Clone)]
 pub struct ValidatedCell {
     #[field_type(String)]
@@ -74,11 +116,35 @@ pub struct ValidatedCell {
     #[field_type(Option<MemoryController>)]
     pub memory: Option<ValidatedMemoryController>,
 
+    #[field_type(Option<IoController>)]
+    pub io: Option<ValidatedIoController>,
+
+    #[field_type(Option<PidsController>)]
+    pub pids: Option<ValidatedPidsController>,
+
     #[validate(none)]
     pub isolate_process: bool,
 
     #[validate(none)]
     pub isolate_network: bool,
+
+    #[validate(none)]
+    pub isolate_user: bool,
+
+    #[field_type(Vec<IdMapping>)]
+    pub uid_mappings: Vec<super::cells::IdMapping>,
+
+    #[field_type(Vec<IdMapping>)]
+    pub gid_mappings: Vec<super::cells::IdMapping>,
+
+    #[field_type(Option<RootFs>)]
+    pub rootfs: Option<ValidatedRootFs>,
+
+    #[field_type(Vec<Mount>)]
+    pub mounts: Vec<super::cells::Mount>,
+
+    #[field_type(Option<String>)]
+    pub seccomp_profile: Option<SeccompProfile>,
 }
 
 impl CellTypeValidator for CellValidator {
@@ -126,6 +192,321 @@ impl CellTypeValidator for CellValidator {
             Some(&*validation::field_name(field_name, parent_name)),
         )?))
     }
+
+    fn validate_io(
+        io: Option<IoController>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<ValidatedIoController>, ValidationError> {
+        let Some(io) = io else {
+            return Ok(None);
+        };
+
+        Ok(Some(ValidatedIoController::validate(
+            io,
+            Some(&*validation::field_name(field_name, parent_name)),
+        )?))
+    }
+
+    fn validate_pids(
+        pids: Option<PidsController>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<ValidatedPidsController>, ValidationError> {
+        let Some(pids) = pids else {
+            return Ok(None);
+        };
+
+        Ok(Some(ValidatedPidsController::validate(
+            pids,
+            Some(&*validation::field_name(field_name, parent_name)),
+        )?))
+    }
+
+    fn validate_uid_mappings(
+        uid_mappings: Vec<IdMapping>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Vec<super::cells::IdMapping>, ValidationError> {
+        validate_id_mappings(uid_mappings, field_name, parent_name)
+    }
+
+    fn validate_gid_mappings(
+        gid_mappings: Vec<IdMapping>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Vec<super::cells::IdMapping>, ValidationError> {
+        validate_id_mappings(gid_mappings, field_name, parent_name)
+    }
+
+    fn validate_rootfs(
+        rootfs: Option<RootFs>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<ValidatedRootFs>, ValidationError> {
+        // aer always sends a root filesystem, empty if not set
+        let Some(rootfs) =
+            rootfs.filter(|x| !x.path.is_empty() || x.upper_dir.is_some())
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(ValidatedRootFs::validate(
+            rootfs,
+            Some(&*validation::field_name(field_name, parent_name)),
+        )?))
+    }
+
+    fn validate_mounts(
+        mounts: Vec<Mount>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Vec<super::cells::Mount>, ValidationError> {
+        let field_name = validation::field_name(field_name, parent_name);
+
+        mounts
+            .into_iter()
+            .enumerate()
+            .map(|(i, mount)| {
+                validate_mount(mount, Some(&format!("{field_name}[{i}]")))
+            })
+            .collect()
+    }
+
+    fn validate_seccomp_profile(
+        seccomp_profile: Option<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<SeccompProfile>, ValidationError> {
+        validate_seccomp_profile(seccomp_profile, field_name, parent_name)
+    }
+
+    fn post_validate(
+        output: &ValidatedCell,
+        parent_name: Option<&str>,
+    ) -> Result<(), ValidationError> {
+        // The root filesystem is pivoted into, and the mounts are mounted, in
+        // the mount namespace
+        for (field_name, is_set) in [
+            ("rootfs", output.rootfs.is_some()),
+            ("mounts", !output.mounts.is_empty()),
+        ] {
+            if is_set && !output.isolate_process {
+                return Err(ValidationError::Invalid {
+                    field: validation::field_name(field_name, parent_name),
+                });
+            }
+        }
+
+        // The nested auraed runs as the root of the user namespace
+        for (field_name, mappings) in [
+            ("uid_mappings", &output.uid_mappings),
+            ("gid_mappings", &output.gid_mappings),
+        ] {
+            let field = validation::field_name(field_name, parent_name);
+            match (output.isolate_user, mappings.is_empty()) {
+                (true, true) => {
+                    return Err(ValidationError::Required { field });
+                }
+                (true, false)
+                    if !mappings.iter().any(|x| x.container_id == 0) =>
+                {
+                    return Err(ValidationError::Invalid { field });
+                }
+                (false, false) => {
+                    return Err(ValidationError::Invalid { field });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Validates the ranges of ids of a cell mapped to ids of the host.
+/// Mappings with a size of 0 are ignored.
+fn validate_id_mappings(
+    mappings: Vec<IdMapping>,
+    field_name: &str,
+    parent_name: Option<&str>,
+) -> Result<Vec<super::cells::IdMapping>, ValidationError> {
+    let field_name = validation::field_name(field_name, parent_name);
+
+    let mut validated: Vec<super::cells::IdMapping> = vec![];
+    for (i, IdMapping { container_id, host_id, size }) in
+        mappings.into_iter().enumerate()
+    {
+        if size == 0 {
+            continue;
+        }
+
+        let parent_name = format!("{field_name}[{i}]");
+
+        // the last id of a range must be a valid id
+        for (name, id) in [("container_id", container_id), ("host_id", host_id)]
+        {
+            if id.checked_add(size - 1).is_none() {
+                return Err(ValidationError::Invalid {
+                    field: validation::field_name(name, Some(&parent_name)),
+                });
+            }
+        }
+
+        // the ranges of the cell must not overlap
+        let end = u64::from(container_id) + u64::from(size);
+        if validated.iter().any(|x| {
+            u64::from(container_id)
+                < u64::from(x.container_id) + u64::from(x.size)
+                && u64::from(x.container_id) < end
+        }) {
+            return Err(ValidationError::Invalid {
+                field: validation::field_name(
+                    "container_id",
+                    Some(&parent_name),
+                ),
+            });
+        }
+
+        validated.push(super::cells::IdMapping { container_id, host_id, size });
+    }
+
+    Ok(validated)
+}
+
+/// Validates the mount of a cell, whose fields depend on its kind.
+fn validate_mount(
+    mount: Mount,
+    parent_name: Option<&str>,
+) -> Result<super::cells::Mount, ValidationError> {
+    let Mount { target, kind, source, read_only, propagation, size_bytes } =
+        mount;
+
+    let invalid = |field_name| ValidationError::Invalid {
+        field: validation::field_name(field_name, parent_name),
+    };
+
+    let target = validate_absolute_path(target, "target", parent_name)?;
+
+    let kind = match validation::valid_enum(kind, "kind", parent_name)? {
+        MountKind::Unspecified => {
+            return Err(ValidationError::Required {
+                field: validation::field_name("kind", parent_name),
+            });
+        }
+        MountKind::Bind if size_bytes != 0 => {
+            return Err(invalid("size_bytes"));
+        }
+        MountKind::Bind => super::cells::MountKind::Bind {
+            source: validate_absolute_path(source, "source", parent_name)?,
+        },
+        MountKind::Tmpfs if !source.is_empty() => {
+            return Err(invalid("source"));
+        }
+        MountKind::Tmpfs => super::cells::MountKind::Tmpfs {
+            size: (size_bytes != 0).then_some(size_bytes),
+        },
+    };
+
+    use super::cells::MountPropagation as Propagation;
+    let propagation = match validation::valid_enum(
+        propagation,
+        "propagation",
+        parent_name,
+    )? {
+        MountPropagation::Unspecified | MountPropagation::Rprivate => {
+            Propagation::RPrivate
+        }
+        MountPropagation::Private => Propagation::Private,
+        MountPropagation::Slave => Propagation::Slave,
+        MountPropagation::Rslave => Propagation::RSlave,
+        MountPropagation::Shared => Propagation::Shared,
+        MountPropagation::Rshared => Propagation::RShared,
+    };
+
+    Ok(super::cells::Mount { target, kind, read_only, propagation })
+}
+
+/// Validates an absolute path, which must not escape its parent directories
+/// with `..`.
+fn validate_absolute_path(
+    path: String,
+    field_name: &str,
+    parent_name: Option<&str>,
+) -> Result<PathBuf, ValidationError> {
+    if path.is_empty() {
+        return Err(ValidationError::Required {
+            field: validation::field_name(field_name, parent_name),
+        });
+    }
+
+    let path = PathBuf::from(path);
+    if !path.is_absolute()
+        || path.components().any(|x| x == Component::ParentDir)
+    {
+        return Err(ValidationError::Invalid {
+            field: validation::field_name(field_name, parent_name),
+        });
+    }
+
+    Ok(path)
+}
+
+/// Validates a seccomp profile, which must compile for the architecture of
+/// auraed.
+fn validate_seccomp_profile(
+    profile: Option<String>,
+    field_name: &str,
+    parent_name: Option<&str>,
+) -> Result<Option<SeccompProfile>, ValidationError> {
+    let Some(profile) = profile else {
+        return Ok(None);
+    };
+
+    let profile = SeccompProfile::parse(profile).and_then(|profile| {
+        let _ = profile.compile()?;
+        Ok(profile)
+    });
+
+    profile.map(Some).map_err(|_| ValidationError::Invalid {
+        field: validation::field_name(field_name, parent_name),
+    })
+}
+
+#[derive(ValidatedType, Debug, Clone)]
+pub struct ValidatedRootFs {
+    #[field_type(String)]
+    pub path: PathBuf,
+
+    #[field_type(Option<String>)]
+    pub upper_dir: Option<PathBuf>,
+}
+
+impl RootFsTypeValidator for RootFsValidator {
+    fn validate_path(
+        path: String,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<PathBuf, ValidationError> {
+        validate_absolute_path(path, field_name, parent_name)
+    }
+
+    fn validate_upper_dir(
+        upper_dir: Option<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<PathBuf>, ValidationError> {
+        upper_dir
+            .map(|x| validate_absolute_path(x, field_name, parent_name))
+            .transpose()
+    }
+}
+
+impl From<ValidatedRootFs> for super::cells::RootFs {
+    fn from(value: ValidatedRootFs) -> Self {
+        let ValidatedRootFs { path, upper_dir } = value;
+        Self { path, upper_dir }
+    }
 }
 
 impl From<ValidatedCell> for super::cells::CellSpec {
@@ -135,8 +516,16 @@ impl From<ValidatedCell> for super::cells::CellSpec {
             cpu,
             cpuset,
             memory,
+            io,
+            pids,
             isolate_process,
             isolate_network,
+            isolate_user,
+            uid_mappings,
+            gid_mappings,
+            rootfs,
+            mounts,
+            seccomp_profile,
         } = x;
 
         Self {
@@ -144,8 +533,19 @@ impl From<ValidatedCell> for super::cells::CellSpec {
                 cpu: cpu.map(|x| x.into()),
                 cpuset: cpuset.map(|x| x.into()),
                 memory: memory.map(|x| x.into()),
+                io: io.map(|x| x.into()),
+                pids: pids.map(|x| x.into()),
+            },
+            iso_ctl: IsolationControls {
+                isolate_process,
+                isolate_network,
+                isolate_user,
+                uid_mappings,
+                gid_mappings,
+                rootfs: rootfs.map(|x| x.into()),
+                mounts,
+                seccomp_profile,
             },
-            iso_ctl: IsolationControls { isolate_process, isolate_network },
         }
     }
 }
@@ -210,14 +610,174 @@ pub struct ValidatedMemoryController {
     #[field_type(Option<i64>)]
     #[validate(opt)]
     pub max: Option<Limit>,
+
+    #[field_type(Option<i64>)]
+    #[validate(opt)]
+    pub swap_max: Option<Limit>,
+
+    #[field_type(Option<i64>)]
+    #[validate(opt)]
+    pub swap_high: Option<Limit>,
 }
 
-impl MemoryControllerTypeValidator for MemoryControllerValidator {}
+impl MemoryControllerTypeValidator for MemoryControllerValidator {
+    fn post_validate(
+        output: &ValidatedMemoryController,
+        parent_name: Option<&str>,
+    ) -> Result<(), ValidationError> {
+        // The boundaries that are set must satisfy: min <= low <= high <= max
+        let memory = cgroups::memory::MemoryController::from(output.clone());
+        let Some(cgroups::memory::UnorderedBoundary {
+            name,
+            value,
+            lower_name,
+            lower_value,
+        }) = memory.unordered_boundary()
+        else {
+            return Ok(());
+        };
+
+        validation::minimum_value(
+            value,
+            lower_value,
+            &format!("bytes ({lower_name})"),
+            name,
+            parent_name,
+        )
+    }
+}
 
 impl From<ValidatedMemoryController> for cgroups::memory::MemoryController {
     fn from(value: ValidatedMemoryController) -> Self {
-        let ValidatedMemoryController { min, low, high, max } = value;
-        Self { min, low, high, max }
+        let ValidatedMemoryController {
+            min,
+            low,
+            high,
+            max,
+            swap_max,
+            swap_high,
+        } = value;
+        Self { min, low, high, max, swap_max, swap_high }
+    }
+}
+
+#[derive(ValidatedType, Debug, Clone)]
+pub struct ValidatedPidsController {
+    #[field_type(Option<i64>)]
+    #[validate(opt)]
+    pub max: Option<PidsMax>,
+}
+
+impl PidsControllerTypeValidator for PidsControllerValidator {}
+
+impl From<ValidatedPidsController> for cgroups::pids::PidsController {
+    fn from(value: ValidatedPidsController) -> Self {
+        let ValidatedPidsController { max } = value;
+        Self { max }
+    }
+}
+
+#[derive(ValidatedType, Debug, Clone)]
+pub struct ValidatedIoController {
+    #[field_type(Vec<IoDeviceController>)]
+    pub devices: Vec<ValidatedIoDeviceController>,
+}
+
+impl IoControllerTypeValidator for IoControllerValidator {
+    fn validate_devices(
+        devices: Vec<IoDeviceController>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Vec<ValidatedIoDeviceController>, ValidationError> {
+        let field_name = validation::field_name(field_name, parent_name);
+
+        let devices = devices
+            .into_iter()
+            .enumerate()
+            .map(|(i, device)| {
+                ValidatedIoDeviceController::validate(
+                    device,
+                    Some(&format!("{field_name}[{i}]")),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // a device may only be listed once
+        for (i, device) in devices.iter().enumerate() {
+            if devices[..i].iter().any(|x| x.device == device.device) {
+                return Err(ValidationError::Invalid {
+                    field: format!("{field_name}[{i}].device"),
+                });
+            }
+        }
+
+        Ok(devices)
+    }
+}
+
+impl From<ValidatedIoController> for cgroups::io::IoController {
+    fn from(value: ValidatedIoController) -> Self {
+        let ValidatedIoController { devices } = value;
+        Self { devices: devices.into_iter().map(|x| x.into()).collect() }
+    }
+}
+
+#[derive(ValidatedType, Debug, Clone)]
+pub struct ValidatedIoDeviceController {
+    #[field_type(String)]
+    #[validate]
+    pub device: Device,
+
+    #[field_type(Option<u64>)]
+    pub weight: Option<u16>,
+
+    #[validate(none)]
+    pub rbps: Option<u64>,
+
+    #[validate(none)]
+    pub wbps: Option<u64>,
+
+    #[validate(none)]
+    pub riops: Option<u64>,
+
+    #[validate(none)]
+    pub wiops: Option<u64>,
+}
+
+impl IoDeviceControllerTypeValidator for IoDeviceControllerValidator {
+    fn validate_weight(
+        weight: Option<u64>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<u16>, ValidationError> {
+        let Some(weight) = weight else {
+            return Ok(None);
+        };
+
+        validation::minimum_value(weight, 1, "unit", field_name, parent_name)?;
+        validation::maximum_value(
+            weight,
+            1000,
+            "units",
+            field_name,
+            parent_name,
+        )?;
+
+        Ok(Some(weight as u16))
+    }
+}
+
+impl From<ValidatedIoDeviceController> for cgroups::io::IoDeviceController {
+    fn from(value: ValidatedIoDeviceController) -> Self {
+        let ValidatedIoDeviceController {
+            device,
+            weight,
+            rbps,
+            wbps,
+            riops,
+            wiops,
+        } = value;
+        Self { device, weight, rbps, wbps, riops, wiops }
     }
 }
 
@@ -266,70 +826,948 @@ pub struct ValidatedCellServiceStopRequest {
     #[field_type(String)]
     #[validate]
     pub executable_name: ExecutableName,
+    #[field_type(Option<i32>)]
+    pub signal: Signal,
+    #[field_type(Option<u64>)]
+    pub grace_period_ms: Duration,
 }
 
-impl CellServiceStopRequestTypeValidator for CellServiceStopRequestValidator {}
-
-#[derive(ValidatedType, Debug, PartialEq, Eq)]
-pub struct ValidatedExecutable {
-    #[field_type(String)]
-    #[validate(create)]
-    pub name: ExecutableName,
-
-    #[field_type(String)]
-    pub command: OsString,
+impl CellServiceStopRequestTypeValidator for CellServiceStopRequestValidator {
+    fn validate_signal(
+        signal: Option<i32>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Signal, ValidationError> {
+        let Some(signal) = signal else {
+            return Ok(DEFAULT_STOP_SIGNAL);
+        };
 
-    // TODO: `#[validate(none)] is used to skip validation. Actually validate when restrictions are known.
-    #[validate(none)]
-    pub description: String,
-}
+        Signal::try_from(signal).map_err(|_| ValidationError::Invalid {
+            field: validation::field_name(field_name, parent_name),
+        })
+    }
 
-impl ExecutableTypeValidator for ExecutableValidator {
-    fn validate_command(
-        command: String,
+    fn validate_grace_period_ms(
+        grace_period_ms: Option<u64>,
         field_name: &str,
         parent_name: Option<&str>,
-    ) -> Result<OsString, ValidationError> {
-        let command = validation::required_not_empty(
-            Some(command),
+    ) -> Result<Duration, ValidationError> {
+        let Some(grace_period_ms) = grace_period_ms else {
+            return Ok(DEFAULT_STOP_GRACE_PERIOD);
+        };
+
+        validation::maximum_value(
+            grace_period_ms,
+            MAX_STOP_GRACE_PERIOD.as_millis() as u64,
+            "milliseconds",
             field_name,
             parent_name,
         )?;
 
-        Ok(OsString::from(command))
+        Ok(Duration::from_millis(grace_period_ms))
     }
 }
 
-impl From<ValidatedExecutable> for super::executables::ExecutableSpec {
-    fn from(x: ValidatedExecutable) -> Self {
-        let ValidatedExecutable { name, command, description } = x;
-
-        let mut c = Command::new("sh");
-        let _ = c.args([OsString::from("-c"), command]);
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceListExecutablesRequest {
+    #[field_type(Option<String>)]
+    #[validate(opt)]
+    pub cell_name: Option<CellName>,
+}
 
-        // We are checking that command has an arg to assure ourselves that `command.arg`
-        // mutates command, and is not making a clone to return
-        assert_eq!(c.as_std().get_args().len(), 2);
+impl CellServiceListExecutablesRequestTypeValidator
+    for CellServiceListExecutablesRequestValidator
+{
+}
 
-        Self { name, command: c, description }
-    }
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceWaitRequest {
+    #[field_type(Option<String>)]
+    #[validate(opt)]
+    pub cell_name: Option<CellName>,
+    #[field_type(String)]
+    #[validate]
+    pub executable_name: ExecutableName,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl CellServiceWaitRequestTypeValidator for CellServiceWaitRequestValidator {}
+
+/// The signals that can be sent to an executable with a
+/// [CellServiceSignalRequest].
+const ALLOWED_SIGNALS: &[Signal] = &[
+    Signal::SIGHUP,
+    Signal::SIGINT,
+    Signal::SIGQUIT,
+    Signal::SIGKILL,
+    Signal::SIGUSR1,
+    Signal::SIGUSR2,
+    Signal::SIGTERM,
+    Signal::SIGCONT,
+    Signal::SIGSTOP,
+    Signal::SIGWINCH,
+];
 
-    #[test]
-    fn test_cell_type_empty_cpu_valid() {
-        let validated =
-            CellValidator::validate_cpu(None, "field", Some("parent"));
-        assert!(validated.is_ok());
-        assert!(validated.unwrap().is_none());
-    }
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceSignalRequest {
+    #[field_type(Option<String>)]
+    #[validate(opt)]
+    pub cell_name: Option<CellName>,
+    #[field_type(String)]
+    #[validate]
+    pub executable_name: ExecutableName,
+    #[field_type(i32)]
+    pub signal: Signal,
+}
 
-    #[test]
-    fn test_cell_type_cpu_valid() {
-        let validated = CellValidator::validate_cpu(
+impl CellServiceSignalRequestTypeValidator
+    for CellServiceSignalRequestValidator
+{
+    fn validate_signal(
+        signal: i32,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Signal, ValidationError> {
+        match Signal::try_from(signal) {
+            Ok(signal) if ALLOWED_SIGNALS.contains(&signal) => Ok(signal),
+            _ => Err(ValidationError::Invalid {
+                field: validation::field_name(field_name, parent_name),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceExecRequest {
+    #[field_type(Option<String>)]
+    #[validate(opt)]
+    pub cell_name: Option<CellName>,
+    #[field_type(Option<Executable>)]
+    pub executable: ValidatedExecutable,
+    #[validate(none)]
+    pub uid: Option<u32>,
+    #[validate(none)]
+    pub gid: Option<u32>,
+    #[validate(none)]
+    pub tty: bool,
+    #[validate(none)]
+    pub interactive: bool,
+    #[validate(none)]
+    pub stdin: Vec<u8>,
+    #[validate(none)]
+    pub close_stdin: bool,
+    #[field_type(Option<TerminalSize>)]
+    pub terminal_size: Option<ValidatedTerminalSize>,
+}
+
+impl CellServiceExecRequestTypeValidator for CellServiceExecRequestValidator {
+    fn validate_executable(
+        executable: Option<Executable>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<ValidatedExecutable, ValidationError> {
+        let executable =
+            validation::required(executable, field_name, parent_name)?;
+        ValidatedExecutable::validate(
+            executable,
+            Some(&*validation::field_name(field_name, parent_name)),
+        )
+    }
+
+    fn validate_terminal_size(
+        terminal_size: Option<TerminalSize>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<ValidatedTerminalSize>, ValidationError> {
+        let Some(terminal_size) = terminal_size else {
+            return Ok(None);
+        };
+
+        Ok(Some(ValidatedTerminalSize::validate(
+            terminal_size,
+            Some(&*validation::field_name(field_name, parent_name)),
+        )?))
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValidatedType)]
+pub struct ValidatedTerminalSize {
+    #[field_type(u32)]
+    pub rows: u16,
+    #[field_type(u32)]
+    pub columns: u16,
+}
+
+impl TerminalSizeTypeValidator for TerminalSizeValidator {
+    fn validate_rows(
+        rows: u32,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<u16, ValidationError> {
+        validation::maximum_value(
+            rows,
+            u16::MAX as u32,
+            "rows",
+            field_name,
+            parent_name,
+        )?;
+
+        Ok(rows as u16)
+    }
+
+    fn validate_columns(
+        columns: u32,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<u16, ValidationError> {
+        validation::maximum_value(
+            columns,
+            u16::MAX as u32,
+            "columns",
+            field_name,
+            parent_name,
+        )?;
+
+        Ok(columns as u16)
+    }
+}
+
+impl From<ValidatedTerminalSize> for super::executables::TerminalSize {
+    fn from(value: ValidatedTerminalSize) -> Self {
+        let ValidatedTerminalSize { rows, columns } = value;
+        Self { rows, columns }
+    }
+}
+
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceStatsRequest {
+    #[field_type(String)]
+    #[validate]
+    pub cell_name: CellName,
+}
+
+impl CellServiceStatsRequestTypeValidator for CellServiceStatsRequestValidator {}
+
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServicePauseRequest {
+    #[field_type(String)]
+    #[validate]
+    pub cell_name: CellName,
+}
+
+impl CellServicePauseRequestTypeValidator for CellServicePauseRequestValidator {}
+
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceResumeRequest {
+    #[field_type(String)]
+    #[validate]
+    pub cell_name: CellName,
+}
+
+impl CellServiceResumeRequestTypeValidator
+    for CellServiceResumeRequestValidator
+{
+}
+
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceWatchStatsRequest {
+    #[field_type(String)]
+    #[validate]
+    pub cell_name: CellName,
+
+    pub interval_ms: Option<u64>,
+}
+
+impl CellServiceWatchStatsRequestTypeValidator
+    for CellServiceWatchStatsRequestValidator
+{
+    fn validate_interval_ms(
+        interval_ms: Option<u64>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<u64>, ValidationError> {
+        let Some(interval_ms) = interval_ms else {
+            return Ok(None);
+        };
+
+        validation::minimum_value(
+            interval_ms,
+            100,
+            "milliseconds",
+            field_name,
+            parent_name,
+        )?;
+
+        Ok(Some(interval_ms))
+    }
+}
+
+#[derive(Debug, ValidatedType)]
+pub struct ValidatedCellServiceWatchPressureRequest {
+    #[field_type(String)]
+    #[validate]
+    pub cell_name: CellName,
+
+    #[field_type(i32)]
+    pub resource: PressureResource,
+
+    #[field_type(i32)]
+    pub kind: PressureKind,
+
+    pub stall_ms: u64,
+
+    pub window_ms: u64,
+}
+
+impl CellServiceWatchPressureRequestTypeValidator
+    for CellServiceWatchPressureRequestValidator
+{
+    fn validate_resource(
+        resource: i32,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<PressureResource, ValidationError> {
+        match validation::valid_enum(resource, field_name, parent_name)? {
+            proto::cells::PressureResource::Cpu => Ok(PressureResource::Cpu),
+            proto::cells::PressureResource::Memory => {
+                Ok(PressureResource::Memory)
+            }
+            proto::cells::PressureResource::Io => Ok(PressureResource::Io),
+            proto::cells::PressureResource::Unspecified => {
+                Err(ValidationError::Required {
+                    field: validation::field_name(field_name, parent_name),
+                })
+            }
+        }
+    }
+
+    fn validate_kind(
+        kind: i32,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<PressureKind, ValidationError> {
+        match validation::valid_enum(kind, field_name, parent_name)? {
+            proto::cells::PressureKind::Some => Ok(PressureKind::Some),
+            proto::cells::PressureKind::Full => Ok(PressureKind::Full),
+            proto::cells::PressureKind::Unspecified => {
+                Err(ValidationError::Required {
+                    field: validation::field_name(field_name, parent_name),
+                })
+            }
+        }
+    }
+
+    fn validate_stall_ms(
+        stall_ms: u64,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<u64, ValidationError> {
+        validation::minimum_value(
+            stall_ms,
+            1,
+            "milliseconds",
+            field_name,
+            parent_name,
+        )?;
+
+        Ok(stall_ms)
+    }
+
+    fn validate_window_ms(
+        window_ms: u64,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<u64, ValidationError> {
+        // The kernel only accepts windows from 500ms to 10s
+        validation::minimum_value(
+            window_ms,
+            500,
+            "milliseconds",
+            field_name,
+            parent_name,
+        )?;
+        validation::maximum_value(
+            window_ms,
+            10_000,
+            "milliseconds",
+            field_name,
+            parent_name,
+        )?;
+
+        Ok(window_ms)
+    }
+
+    fn post_validate(
+        output: &ValidatedCellServiceWatchPressureRequest,
+        parent_name: Option<&str>,
+    ) -> Result<(), ValidationError> {
+        validation::maximum_value(
+            output.stall_ms,
+            output.window_ms,
+            "milliseconds (window_ms)",
+            "stall_ms",
+            parent_name,
+        )
+    }
+}
+
+/// How the command of an executable is executed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CommandMode {
+    /// The command is executed by a shell (`sh -c <command>`).
+    #[default]
+    Shell,
+    /// The command is the program, executed with the arguments as is.
+    Exec,
+}
+
+#[derive(ValidatedType, Debug, PartialEq, Eq)]
+pub struct ValidatedExecutable {
+    #[field_type(String)]
+    #[validate(create)]
+    pub name: ExecutableName,
+
+    #[field_type(String)]
+    pub command: OsString,
+
+    // TODO: `#[validate(none)] is used to skip validation. Actually validate when restrictions are known.
+    #[validate(none)]
+    pub description: String,
+
+    #[field_type(Vec<String>)]
+    pub args: Vec<OsString>,
+
+    #[field_type(Vec<EnvironmentVariable>)]
+    pub env: Vec<(OsString, OsString)>,
+
+    #[validate(none)]
+    pub clear_env: bool,
+
+    #[field_type(Option<String>)]
+    pub working_dir: Option<PathBuf>,
+
+    #[field_type(i32)]
+    pub restart_policy: RestartPolicy,
+
+    #[validate(none)]
+    pub max_restarts: Option<u32>,
+
+    #[field_type(Option<u64>)]
+    pub restart_backoff_ms: Duration,
+
+    #[field_type(Option<u64>)]
+    pub max_restart_backoff_ms: Duration,
+
+    #[field_type(Option<String>)]
+    pub seccomp_profile: Option<SeccompProfile>,
+
+    #[field_type(Option<Capabilities>)]
+    pub capabilities: super::executables::Capabilities,
+
+    #[field_type(Vec<Rlimit>)]
+    pub rlimits: Vec<super::executables::Rlimit>,
+
+    #[field_type(i32)]
+    pub command_mode: CommandMode,
+}
+
+impl ExecutableTypeValidator for ExecutableValidator {
+    fn validate_command(
+        command: String,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<OsString, ValidationError> {
+        let command = validation::required_not_empty(
+            Some(command),
+            field_name,
+            parent_name,
+        )?;
+
+        Ok(OsString::from(command))
+    }
+
+    fn validate_args(
+        args: Vec<String>,
+        _field_name: &str,
+        _parent_name: Option<&str>,
+    ) -> Result<Vec<OsString>, ValidationError> {
+        Ok(args.into_iter().map(OsString::from).collect())
+    }
+
+    fn validate_env(
+        env: Vec<EnvironmentVariable>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Vec<(OsString, OsString)>, ValidationError> {
+        env.into_iter()
+            .enumerate()
+            .map(|(i, EnvironmentVariable { name, value })| {
+                let parent_name = validation::field_name(
+                    &format!("{field_name}[{i}]"),
+                    parent_name,
+                );
+
+                let name = validation::required_not_empty(
+                    Some(name),
+                    "name",
+                    Some(&parent_name),
+                )?;
+
+                if name.contains(['=', '\0']) {
+                    return Err(ValidationError::Invalid {
+                        field: validation::field_name(
+                            "name",
+                            Some(&parent_name),
+                        ),
+                    });
+                }
+
+                if value.contains('\0') {
+                    return Err(ValidationError::Invalid {
+                        field: validation::field_name(
+                            "value",
+                            Some(&parent_name),
+                        ),
+                    });
+                }
+
+                Ok((OsString::from(name), OsString::from(value)))
+            })
+            .collect()
+    }
+
+    fn validate_working_dir(
+        working_dir: Option<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<PathBuf>, ValidationError> {
+        let Some(working_dir) = working_dir else {
+            return Ok(None);
+        };
+
+        let working_dir = PathBuf::from(working_dir);
+        if !working_dir.is_absolute() {
+            return Err(ValidationError::Invalid {
+                field: validation::field_name(field_name, parent_name),
+            });
+        }
+
+        Ok(Some(working_dir))
+    }
+
+    fn validate_command_mode(
+        command_mode: i32,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<CommandMode, ValidationError> {
+        use proto::cells::CommandMode as Mode;
+
+        match validation::valid_enum(command_mode, field_name, parent_name)? {
+            Mode::Unspecified | Mode::Shell => Ok(CommandMode::Shell),
+            Mode::Exec => Ok(CommandMode::Exec),
+        }
+    }
+
+    fn validate_restart_policy(
+        restart_policy: i32,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<RestartPolicy, ValidationError> {
+        use proto::cells::RestartPolicy as Policy;
+
+        match validation::valid_enum(restart_policy, field_name, parent_name)? {
+            Policy::Unspecified | Policy::Never => Ok(RestartPolicy::Never),
+            Policy::OnFailure => Ok(RestartPolicy::OnFailure),
+            Policy::Always => Ok(RestartPolicy::Always),
+        }
+    }
+
+    fn validate_restart_backoff_ms(
+        restart_backoff_ms: Option<u64>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Duration, ValidationError> {
+        let Some(restart_backoff_ms) = restart_backoff_ms else {
+            return Ok(DEFAULT_RESTART_BACKOFF);
+        };
+
+        validation::minimum_value(
+            restart_backoff_ms,
+            1,
+            "milliseconds",
+            field_name,
+            parent_name,
+        )?;
+
+        Ok(Duration::from_millis(restart_backoff_ms))
+    }
+
+    fn validate_max_restart_backoff_ms(
+        max_restart_backoff_ms: Option<u64>,
+        _field_name: &str,
+        _parent_name: Option<&str>,
+    ) -> Result<Duration, ValidationError> {
+        Ok(max_restart_backoff_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_MAX_RESTART_BACKOFF))
+    }
+
+    fn validate_seccomp_profile(
+        seccomp_profile: Option<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Option<SeccompProfile>, ValidationError> {
+        validate_seccomp_profile(seccomp_profile, field_name, parent_name)
+    }
+
+    fn validate_capabilities(
+        capabilities: Option<Capabilities>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<super::executables::Capabilities, ValidationError> {
+        // aer always sends capabilities, empty if not set
+        let Some(capabilities) = capabilities.filter(|x| {
+            x.privileged
+                || [
+                    &x.bounding,
+                    &x.effective,
+                    &x.permitted,
+                    &x.inheritable,
+                    &x.ambient,
+                ]
+                .iter()
+                .any(|set| !set.is_empty())
+        }) else {
+            return Ok(Default::default());
+        };
+
+        Ok(ValidatedCapabilities::validate(
+            capabilities,
+            Some(&*validation::field_name(field_name, parent_name)),
+        )?
+        .into())
+    }
+
+    fn validate_rlimits(
+        rlimits: Vec<Rlimit>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<Vec<super::executables::Rlimit>, ValidationError> {
+        let field_name = validation::field_name(field_name, parent_name);
+
+        let mut validated: Vec<super::executables::Rlimit> = vec![];
+        // aer always sends a resource limit, empty if not set
+        for (i, rlimit) in rlimits
+            .into_iter()
+            .enumerate()
+            .filter(|(_, x)| *x != Rlimit::default())
+        {
+            let parent_name = format!("{field_name}[{i}]");
+            let rlimit = validate_rlimit(rlimit, Some(&parent_name))?;
+
+            // Each resource can only be limited once
+            if validated.iter().any(|x| x.resource == rlimit.resource) {
+                return Err(ValidationError::Invalid {
+                    field: validation::field_name("kind", Some(&parent_name)),
+                });
+            }
+
+            validated.push(rlimit);
+        }
+
+        Ok(validated)
+    }
+
+    fn post_validate(
+        output: &ValidatedExecutable,
+        parent_name: Option<&str>,
+    ) -> Result<(), ValidationError> {
+        // The arguments would be silently ignored by the shell
+        if output.command_mode == CommandMode::Shell && !output.args.is_empty()
+        {
+            return Err(ValidationError::Invalid {
+                field: validation::field_name("args", parent_name),
+            });
+        }
+
+        validation::minimum_value(
+            output.max_restart_backoff_ms.as_millis(),
+            output.restart_backoff_ms.as_millis(),
+            "milliseconds (restart_backoff_ms)",
+            "max_restart_backoff_ms",
+            parent_name,
+        )
+    }
+}
+
+impl From<ValidatedExecutable> for super::executables::ExecutableSpec {
+    fn from(x: ValidatedExecutable) -> Self {
+        let ValidatedExecutable {
+            name,
+            command,
+            description,
+            args,
+            env,
+            clear_env,
+            working_dir,
+            restart_policy,
+            max_restarts,
+            restart_backoff_ms,
+            max_restart_backoff_ms,
+            seccomp_profile,
+            capabilities,
+            rlimits,
+            command_mode,
+        } = x;
+
+        let mut c = match command_mode {
+            CommandMode::Shell => {
+                let mut c = Command::new("sh");
+                let _ = c.args([OsString::from("-c"), command]);
+
+                // We are checking that command has an arg to assure ourselves that `command.arg`
+                // mutates command, and is not making a clone to return
+                assert_eq!(c.as_std().get_args().len(), 2);
+                c
+            }
+            CommandMode::Exec => {
+                // The program and arguments are passed as is, without a shell
+                let mut c = Command::new(command);
+                let _ = c.args(args);
+                c
+            }
+        };
+
+        if clear_env {
+            let _ = c.env_clear();
+        }
+        let _ = c.envs(env);
+        let _ =
+            c.current_dir(working_dir.unwrap_or_else(|| PathBuf::from("/")));
+
+        let restart = RestartSpec {
+            policy: restart_policy,
+            max_restarts,
+            backoff: restart_backoff_ms,
+            max_backoff: max_restart_backoff_ms,
+        };
+
+        Self {
+            name,
+            command: c,
+            description,
+            restart,
+            seccomp_profile,
+            capabilities,
+            rlimits,
+        }
+    }
+}
+
+/// Validates a resource limit of an executable, whose soft limit can not
+/// exceed its hard limit.
+fn validate_rlimit(
+    rlimit: Rlimit,
+    parent_name: Option<&str>,
+) -> Result<super::executables::Rlimit, ValidationError> {
+    use nix::sys::resource::Resource;
+
+    let Rlimit { kind, soft, hard } = rlimit;
+
+    let resource = match validation::valid_enum(kind, "kind", parent_name)? {
+        RlimitKind::Unspecified => {
+            return Err(ValidationError::Required {
+                field: validation::field_name("kind", parent_name),
+            });
+        }
+        RlimitKind::As => Resource::RLIMIT_AS,
+        RlimitKind::Core => Resource::RLIMIT_CORE,
+        RlimitKind::Cpu => Resource::RLIMIT_CPU,
+        RlimitKind::Data => Resource::RLIMIT_DATA,
+        RlimitKind::Fsize => Resource::RLIMIT_FSIZE,
+        RlimitKind::Locks => Resource::RLIMIT_LOCKS,
+        RlimitKind::Memlock => Resource::RLIMIT_MEMLOCK,
+        RlimitKind::Msgqueue => Resource::RLIMIT_MSGQUEUE,
+        RlimitKind::Nice => Resource::RLIMIT_NICE,
+        RlimitKind::Nofile => Resource::RLIMIT_NOFILE,
+        RlimitKind::Nproc => Resource::RLIMIT_NPROC,
+        RlimitKind::Rss => Resource::RLIMIT_RSS,
+        RlimitKind::Rtprio => Resource::RLIMIT_RTPRIO,
+        RlimitKind::Rttime => Resource::RLIMIT_RTTIME,
+        RlimitKind::Sigpending => Resource::RLIMIT_SIGPENDING,
+        RlimitKind::Stack => Resource::RLIMIT_STACK,
+    };
+
+    // The soft limit is the hard limit if not set, and the hard limit is
+    // only known once the process is started if not set
+    if let (Some(soft), Some(hard)) = (soft, hard) {
+        validation::maximum_value(soft, hard, "(hard)", "soft", parent_name)?;
+    }
+
+    Ok(super::executables::Rlimit { resource, soft, hard })
+}
+
+#[derive(ValidatedType, Debug, Clone)]
+pub struct ValidatedCapabilities {
+    #[validate(none)]
+    pub privileged: bool,
+
+    #[field_type(Vec<String>)]
+    pub bounding: CapsHashSet,
+
+    #[field_type(Vec<String>)]
+    pub effective: CapsHashSet,
+
+    #[field_type(Vec<String>)]
+    pub permitted: CapsHashSet,
+
+    #[field_type(Vec<String>)]
+    pub inheritable: CapsHashSet,
+
+    #[field_type(Vec<String>)]
+    pub ambient: CapsHashSet,
+}
+
+impl CapabilitiesTypeValidator for CapabilitiesValidator {
+    fn validate_bounding(
+        bounding: Vec<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<CapsHashSet, ValidationError> {
+        validate_capability_set(bounding, field_name, parent_name)
+    }
+
+    fn validate_effective(
+        effective: Vec<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<CapsHashSet, ValidationError> {
+        validate_capability_set(effective, field_name, parent_name)
+    }
+
+    fn validate_permitted(
+        permitted: Vec<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<CapsHashSet, ValidationError> {
+        validate_capability_set(permitted, field_name, parent_name)
+    }
+
+    fn validate_inheritable(
+        inheritable: Vec<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<CapsHashSet, ValidationError> {
+        validate_capability_set(inheritable, field_name, parent_name)
+    }
+
+    fn validate_ambient(
+        ambient: Vec<String>,
+        field_name: &str,
+        parent_name: Option<&str>,
+    ) -> Result<CapsHashSet, ValidationError> {
+        validate_capability_set(ambient, field_name, parent_name)
+    }
+
+    fn post_validate(
+        output: &ValidatedCapabilities,
+        parent_name: Option<&str>,
+    ) -> Result<(), ValidationError> {
+        // A privileged process keeps every capability
+        if output.privileged {
+            let sets = [
+                ("bounding", &output.bounding),
+                ("effective", &output.effective),
+                ("permitted", &output.permitted),
+                ("inheritable", &output.inheritable),
+                ("ambient", &output.ambient),
+            ];
+            if let Some((field_name, _)) =
+                sets.into_iter().find(|(_, set)| !set.is_empty())
+            {
+                return Err(ValidationError::Invalid {
+                    field: validation::field_name(field_name, parent_name),
+                });
+            }
+        }
+
+        // The kernel refuses to set the capabilities otherwise (capset(2))
+        for (field_name, set, superset) in [
+            ("effective", &output.effective, &output.permitted),
+            ("inheritable", &output.inheritable, &output.bounding),
+            ("ambient", &output.ambient, &output.permitted),
+            ("ambient", &output.ambient, &output.inheritable),
+        ] {
+            if !set.is_subset(superset) {
+                return Err(ValidationError::Invalid {
+                    field: validation::field_name(field_name, parent_name),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl From<ValidatedCapabilities> for super::executables::Capabilities {
+    fn from(value: ValidatedCapabilities) -> Self {
+        let ValidatedCapabilities {
+            privileged,
+            bounding,
+            effective,
+            permitted,
+            inheritable,
+            ambient,
+        } = value;
+
+        if privileged {
+            return Self::Privileged;
+        }
+
+        Self::Restricted(CapabilitySets {
+            bounding,
+            effective,
+            permitted,
+            inheritable,
+            ambient,
+        })
+    }
+}
+
+/// Validates a set of capability names (e.g., "CAP_KILL").
+fn validate_capability_set(
+    names: Vec<String>,
+    field_name: &str,
+    parent_name: Option<&str>,
+) -> Result<CapsHashSet, ValidationError> {
+    names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            name.parse::<Capability>().map_err(|_| ValidationError::Invalid {
+                field: validation::field_name(
+                    &format!("{field_name}[{i}]"),
+                    parent_name,
+                ),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_type_empty_cpu_valid() {
+        let validated =
+            CellValidator::validate_cpu(None, "field", Some("parent"));
+        assert!(validated.is_ok());
+        assert!(validated.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_cell_type_cpu_valid() {
+        let validated = CellValidator::validate_cpu(
             Some(CpuController { weight: Some(1000), max: None, period: None }),
             "field",
             Some("parent"),
@@ -354,27 +1792,225 @@ mod tests {
     }
 
     #[test]
-    fn test_cell_type_cpu_weight_too_large() {
-        let validated = CellValidator::validate_cpu(
-            Some(CpuController {
-                weight: Some(10001),
-                max: None,
-                period: None,
-            }),
+    fn test_cell_type_cpu_weight_too_large() {
+        let validated = CellValidator::validate_cpu(
+            Some(CpuController {
+                weight: Some(10001),
+                max: None,
+                period: None,
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_cpu_max_too_small() {
+        let validated = CellValidator::validate_cpu(
+            Some(CpuController {
+                weight: Some(1000),
+                max: Some(-1),
+                period: None,
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_cpuset_valid() {
+        let validated = CellValidator::validate_cpuset(
+            Some(CpusetController {
+                cpus: Some(String::from("1,2-4")),
+                mems: Some(String::from("1-4")),
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_ok());
+        let inner = validated.unwrap();
+        assert!(inner.is_some());
+        let controller = inner.unwrap();
+        assert_eq!(controller.cpus, Some(Cpus::new(String::from("1,2-4"))));
+        assert_eq!(controller.mems, Some(Mems::new(String::from("1-4"))));
+    }
+
+    #[test]
+    fn test_cell_type_cpuset_invalid_cpus() {
+        let validated = CellValidator::validate_cpuset(
+            Some(CpusetController {
+                cpus: Some(String::from("foo")),
+                mems: Some(String::from("1-4")),
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_cpuset_invalid_mems() {
+        let validated = CellValidator::validate_cpuset(
+            Some(CpusetController {
+                cpus: Some(String::from("1,2-4")),
+                mems: Some(String::from("1..4")),
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_memory_valid() {
+        let validated = CellValidator::validate_memory(
+            Some(MemoryController {
+                min: None,
+                low: Some(1000),
+                high: None,
+                max: Some(10000),
+                swap_max: None,
+                swap_high: None,
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_ok());
+        let inner = validated.unwrap();
+        assert!(inner.is_some());
+        let controller = inner.unwrap();
+        assert_eq!(controller.min, None);
+        assert_eq!(controller.low, Some(Protection::new(1000)));
+        assert_eq!(controller.high, None);
+        assert_eq!(controller.max, Some(Limit::new(10000)));
+    }
+
+    #[test]
+    fn test_cell_type_memory_low_too_small() {
+        let validated = CellValidator::validate_memory(
+            Some(MemoryController {
+                min: None,
+                low: Some(-1),
+                high: None,
+                max: Some(10000),
+                swap_max: None,
+                swap_high: None,
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_memory_max_too_small() {
+        let validated = CellValidator::validate_memory(
+            Some(MemoryController {
+                min: None,
+                low: Some(1000),
+                high: None,
+                max: Some(-1),
+                swap_max: None,
+                swap_high: None,
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_memory_all_limits_valid() {
+        let validated = CellValidator::validate_memory(
+            Some(MemoryController {
+                min: Some(1000),
+                low: Some(2000),
+                high: Some(2000),
+                max: Some(10000),
+                swap_max: Some(0),
+                swap_high: Some(5000),
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_ok());
+        let controller = validated.unwrap().unwrap();
+        assert_eq!(controller.min, Some(Protection::new(1000)));
+        assert_eq!(controller.high, Some(Limit::new(2000)));
+        assert_eq!(controller.swap_max, Some(Limit::new(0)));
+        assert_eq!(controller.swap_high, Some(Limit::new(5000)));
+    }
+
+    #[test]
+    fn test_cell_type_memory_low_above_high() {
+        let validated = CellValidator::validate_memory(
+            Some(MemoryController {
+                min: None,
+                low: Some(3000),
+                high: Some(2000),
+                max: None,
+                swap_max: None,
+                swap_high: None,
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_memory_min_above_max() {
+        let validated = CellValidator::validate_memory(
+            Some(MemoryController {
+                min: Some(20000),
+                low: None,
+                high: None,
+                max: Some(10000),
+                swap_max: None,
+                swap_high: None,
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_memory_swap_max_too_small() {
+        let validated = CellValidator::validate_memory(
+            Some(MemoryController {
+                min: None,
+                low: None,
+                high: None,
+                max: None,
+                swap_max: Some(-1),
+                swap_high: None,
+            }),
+            "field",
+            Some("parent"),
+        );
+        assert!(validated.is_err());
+    }
+
+    #[test]
+    fn test_cell_type_pids_valid() {
+        let validated = CellValidator::validate_pids(
+            Some(PidsController { max: Some(100) }),
             "field",
             Some("parent"),
         );
-        assert!(validated.is_err());
+        assert!(validated.is_ok());
+        let inner = validated.unwrap();
+        assert!(inner.is_some());
+        assert_eq!(inner.unwrap().max, Some(PidsMax::new(100)));
     }
 
     #[test]
-    fn test_cell_type_cpu_max_too_small() {
-        let validated = CellValidator::validate_cpu(
-            Some(CpuController {
-                weight: Some(1000),
-                max: Some(-1),
-                period: None,
-            }),
+    fn test_cell_type_pids_max_too_small() {
+        let validated = CellValidator::validate_pids(
+            Some(PidsController { max: Some(0) }),
             "field",
             Some("parent"),
         );
@@ -382,11 +2018,17 @@ mod tests {
     }
 
     #[test]
-    fn test_cell_type_cpuset_valid() {
-        let validated = CellValidator::validate_cpuset(
-            Some(CpusetController {
-                cpus: Some(String::from("1,2-4")),
-                mems: Some(String::from("1-4")),
+    fn test_cell_type_io_valid() {
+        let validated = CellValidator::validate_io(
+            Some(IoController {
+                devices: vec![IoDeviceController {
+                    device: String::from("8:0"),
+                    weight: Some(100),
+                    rbps: Some(1048576),
+                    wbps: None,
+                    riops: None,
+                    wiops: Some(1000),
+                }],
             }),
             "field",
             Some("parent"),
@@ -395,16 +2037,25 @@ mod tests {
         let inner = validated.unwrap();
         assert!(inner.is_some());
         let controller = inner.unwrap();
-        assert_eq!(controller.cpus, Some(Cpus::new(String::from("1,2-4"))));
-        assert_eq!(controller.mems, Some(Mems::new(String::from("1-4"))));
+        assert_eq!(controller.devices.len(), 1);
+        assert_eq!(controller.devices[0].device, Device::new(8, 0));
+        assert_eq!(controller.devices[0].weight, Some(100));
+        assert_eq!(controller.devices[0].rbps, Some(1048576));
+        assert_eq!(controller.devices[0].wiops, Some(1000));
     }
 
     #[test]
-    fn test_cell_type_cpuset_invalid_cpus() {
-        let validated = CellValidator::validate_cpuset(
-            Some(CpusetController {
-                cpus: Some(String::from("foo")),
-                mems: Some(String::from("1-4")),
+    fn test_cell_type_io_invalid_device() {
+        let validated = CellValidator::validate_io(
+            Some(IoController {
+                devices: vec![IoDeviceController {
+                    device: String::from("sda"),
+                    weight: None,
+                    rbps: Some(1048576),
+                    wbps: None,
+                    riops: None,
+                    wiops: None,
+                }],
             }),
             "field",
             Some("parent"),
@@ -413,11 +2064,17 @@ mod tests {
     }
 
     #[test]
-    fn test_cell_type_cpuset_invalid_mems() {
-        let validated = CellValidator::validate_cpuset(
-            Some(CpusetController {
-                cpus: Some(String::from("1,2-4")),
-                mems: Some(String::from("1..4")),
+    fn test_cell_type_io_weight_too_large() {
+        let validated = CellValidator::validate_io(
+            Some(IoController {
+                devices: vec![IoDeviceController {
+                    device: String::from("8:0"),
+                    weight: Some(1001),
+                    rbps: None,
+                    wbps: None,
+                    riops: None,
+                    wiops: None,
+                }],
             }),
             "field",
             Some("parent"),
@@ -426,55 +2083,193 @@ mod tests {
     }
 
     #[test]
-    fn test_cell_type_memory_valid() {
-        let validated = CellValidator::validate_memory(
-            Some(MemoryController {
-                min: None,
-                low: Some(1000),
-                high: None,
-                max: Some(10000),
-            }),
+    fn test_cell_type_io_duplicate_device() {
+        let device = IoDeviceController {
+            device: String::from("8:0"),
+            weight: None,
+            rbps: Some(1048576),
+            wbps: None,
+            riops: None,
+            wiops: None,
+        };
+        let validated = CellValidator::validate_io(
+            Some(IoController { devices: vec![device.clone(), device] }),
             "field",
             Some("parent"),
         );
-        assert!(validated.is_ok());
-        let inner = validated.unwrap();
-        assert!(inner.is_some());
-        let controller = inner.unwrap();
-        assert_eq!(controller.min, None);
-        assert_eq!(controller.low, Some(Protection::new(1000)));
-        assert_eq!(controller.high, None);
-        assert_eq!(controller.max, Some(Limit::new(10000)));
+        assert!(validated.is_err());
     }
 
     #[test]
-    fn test_cell_type_memory_low_too_small() {
-        let validated = CellValidator::validate_memory(
-            Some(MemoryController {
-                min: None,
-                low: Some(-1),
-                high: None,
-                max: Some(10000),
-            }),
+    fn test_cell_type_id_mappings() {
+        let mapping = |container_id, host_id, size| IdMapping {
+            container_id,
+            host_id,
+            size,
+        };
+
+        // empty mappings are ignored
+        let validated = CellValidator::validate_uid_mappings(
+            vec![mapping(0, 100000, 65536), mapping(0, 0, 0)],
             "field",
             Some("parent"),
         );
-        assert!(validated.is_err());
-    }
+        assert_eq!(
+            validated.expect("valid"),
+            vec![super::super::cells::IdMapping {
+                container_id: 0,
+                host_id: 100000,
+                size: 65536,
+            }]
+        );
 
-    #[test]
-    fn test_cell_type_memory_max_too_small() {
-        let validated = CellValidator::validate_memory(
-            Some(MemoryController {
-                min: None,
-                low: Some(1000),
-                high: None,
-                max: Some(-1),
-            }),
+        let validated = CellValidator::validate_uid_mappings(
+            vec![mapping(0, u32::MAX, 2)],
             "field",
             Some("parent"),
         );
-        assert!(validated.is_err());
+        assert!(matches!(validated, Err(ValidationError::Invalid { .. })));
+
+        let validated = CellValidator::validate_gid_mappings(
+            vec![mapping(0, 100000, 1000), mapping(999, 200000, 1)],
+            "field",
+            Some("parent"),
+        );
+        assert!(matches!(validated, Err(ValidationError::Invalid { .. })));
+    }
+
+    #[test]
+    fn test_cell_isolate_user_requires_root_mappings() {
+        let cell = |isolate_user, uid_container_id| Cell {
+            name: String::from("ae-test"),
+            isolate_user,
+            uid_mappings: vec![IdMapping {
+                container_id: uid_container_id,
+                host_id: 100000,
+                size: 1,
+            }],
+            gid_mappings: vec![IdMapping {
+                container_id: 0,
+                host_id: 100000,
+                size: 1,
+            }],
+            ..Default::default()
+        };
+
+        assert!(ValidatedCell::validate(cell(true, 0), None).is_ok());
+        assert!(matches!(
+            ValidatedCell::validate(cell(true, 1), None),
+            Err(ValidationError::Invalid { field }) if field == "uid_mappings"
+        ));
+        assert!(matches!(
+            ValidatedCell::validate(cell(false, 0), None),
+            Err(ValidationError::Invalid { field }) if field == "uid_mappings"
+        ));
+    }
+
+    #[test]
+    fn test_cell_rootfs() {
+        let cell = |isolate_process, path: &str| Cell {
+            name: String::from("ae-test"),
+            isolate_process,
+            rootfs: Some(RootFs { path: String::from(path), upper_dir: None }),
+            ..Default::default()
+        };
+
+        let validated =
+            ValidatedCell::validate(cell(true, "/images/alpine"), None)
+                .expect("valid");
+        assert_eq!(
+            validated.rootfs.expect("rootfs").path,
+            PathBuf::from("/images/alpine")
+        );
+
+        // aer sends an empty root filesystem when not set
+        let validated =
+            ValidatedCell::validate(cell(false, ""), None).expect("valid");
+        assert!(validated.rootfs.is_none());
+
+        assert!(matches!(
+            ValidatedCell::validate(cell(false, "/images/alpine"), None),
+            Err(ValidationError::Invalid { field }) if field == "rootfs"
+        ));
+        assert!(matches!(
+            ValidatedCell::validate(cell(true, "images/alpine"), None),
+            Err(ValidationError::Invalid { field }) if field == "rootfs.path"
+        ));
+        assert!(matches!(
+            ValidatedCell::validate(cell(true, "/images/../etc"), None),
+            Err(ValidationError::Invalid { field }) if field == "rootfs.path"
+        ));
+    }
+
+    #[test]
+    fn test_cell_mounts() {
+        let cell = |isolate_process, mount| Cell {
+            name: String::from("ae-test"),
+            isolate_process,
+            mounts: vec![mount],
+            ..Default::default()
+        };
+        let bind = |target: &str, source: &str| Mount {
+            target: String::from(target),
+            kind: MountKind::Bind as i32,
+            source: String::from(source),
+            read_only: true,
+            propagation: MountPropagation::Unspecified as i32,
+            size_bytes: 0,
+        };
+
+        let validated = ValidatedCell::validate(
+            cell(true, bind("/data", "/srv/data")),
+            None,
+        )
+        .expect("valid");
+        assert_eq!(
+            validated.mounts,
+            [super::super::cells::Mount {
+                target: PathBuf::from("/data"),
+                kind: super::super::cells::MountKind::Bind {
+                    source: PathBuf::from("/srv/data"),
+                },
+                read_only: true,
+                propagation: super::super::cells::MountPropagation::RPrivate,
+            }]
+        );
+
+        let tmpfs = Mount {
+            target: String::from("/tmp"),
+            kind: MountKind::Tmpfs as i32,
+            size_bytes: 1024,
+            ..Default::default()
+        };
+        assert!(
+            ValidatedCell::validate(cell(true, tmpfs.clone()), None).is_ok()
+        );
+
+        assert!(matches!(
+            ValidatedCell::validate(cell(false, tmpfs.clone()), None),
+            Err(ValidationError::Invalid { field }) if field == "mounts"
+        ));
+        let invalid_field = |cell| match ValidatedCell::validate(cell, None) {
+            Err(ValidationError::Invalid { field }) => field,
+            x => panic!("expected an invalid field, got {x:?}"),
+        };
+        assert_eq!(
+            invalid_field(cell(
+                true,
+                Mount { source: String::from("/srv"), ..tmpfs }
+            )),
+            "mounts[0].source"
+        );
+        assert_eq!(
+            invalid_field(cell(true, bind("/data", "/srv/../etc"))),
+            "mounts[0].source"
+        );
+        assert_eq!(
+            invalid_field(cell(true, bind("data", "/srv/data"))),
+            "mounts[0].target"
+        );
     }
 
     #[test]
@@ -494,6 +2289,18 @@ mod tests {
                 command: String::from(""),
                 name: String::from("name"),
                 description: String::from("description"),
+                args: vec![],
+                env: vec![],
+                clear_env: false,
+                working_dir: None,
+                restart_policy: 0,
+                max_restarts: None,
+                restart_backoff_ms: None,
+                max_restart_backoff_ms: None,
+                seccomp_profile: None,
+                capabilities: None,
+                rlimits: vec![],
+                command_mode: 0,
             }),
             "field",
             Some("parent"),
@@ -508,6 +2315,18 @@ mod tests {
                 command: String::from("command"),
                 name: String::from("name"),
                 description: String::from("description"),
+                args: vec![],
+                env: vec![],
+                clear_env: false,
+                working_dir: None,
+                restart_policy: 0,
+                max_restarts: None,
+                restart_backoff_ms: None,
+                max_restart_backoff_ms: None,
+                seccomp_profile: None,
+                capabilities: None,
+                rlimits: vec![],
+                command_mode: 0,
             }),
             "field",
             Some("parent"),
@@ -519,8 +2338,340 @@ mod tests {
                 name: ExecutableName::new(String::from("name")),
                 description: String::from("description"),
                 command: OsString::from("command"),
+                args: vec![],
+                env: vec![],
+                clear_env: false,
+                working_dir: None,
+                restart_policy: RestartPolicy::Never,
+                max_restarts: None,
+                restart_backoff_ms: DEFAULT_RESTART_BACKOFF,
+                max_restart_backoff_ms: DEFAULT_MAX_RESTART_BACKOFF,
+                seccomp_profile: None,
+                capabilities: Default::default(),
+                rlimits: vec![],
+                command_mode: CommandMode::Shell,
+            },
+        );
+    }
+
+    #[test]
+    fn test_executable_structured_valid() {
+        let executable = |command_mode: proto::cells::CommandMode| {
+            ValidatedExecutable::validate(
+                Executable {
+                    name: String::from("name"),
+                    command: String::from("echo"),
+                    description: String::new(),
+                    args: vec![
+                        String::from("hello world"),
+                        String::from("'$HOME'"),
+                    ],
+                    env: vec![EnvironmentVariable {
+                        name: String::from("GREETING"),
+                        value: String::from("a=b"),
+                    }],
+                    clear_env: true,
+                    working_dir: Some(String::from("/tmp")),
+                    restart_policy: 0,
+                    max_restarts: None,
+                    restart_backoff_ms: None,
+                    max_restart_backoff_ms: None,
+                    seccomp_profile: None,
+                    capabilities: None,
+                    rlimits: vec![],
+                    command_mode: command_mode as i32,
+                },
+                None,
+            )
+        };
+
+        // The arguments would be ignored by the shell
+        assert!(matches!(
+            executable(proto::cells::CommandMode::Unspecified),
+            Err(ValidationError::Invalid { .. })
+        ));
+
+        let validated = executable(proto::cells::CommandMode::Exec);
+        assert!(validated.is_ok());
+        let validated = validated.unwrap();
+        assert_eq!(validated.command_mode, CommandMode::Exec);
+        assert_eq!(
+            validated.args,
+            vec![OsString::from("hello world"), OsString::from("'$HOME'")]
+        );
+        assert_eq!(
+            validated.env,
+            vec![(OsString::from("GREETING"), OsString::from("a=b"))]
+        );
+        assert_eq!(validated.working_dir, Some(PathBuf::from("/tmp")));
+
+        // Arguments are passed to the program as is, without a shell
+        let spec = super::super::executables::ExecutableSpec::from(validated);
+        assert_eq!(spec.command.as_std().get_program(), "echo");
+        assert_eq!(spec.command.as_std().get_args().len(), 2);
+        assert_eq!(
+            spec.command.as_std().get_current_dir(),
+            Some(std::path::Path::new("/tmp"))
+        );
+    }
+
+    #[test]
+    fn test_executable_env_name_with_equals_sign() {
+        let validated = ExecutableValidator::validate_env(
+            vec![EnvironmentVariable {
+                name: String::from("A=B"),
+                value: String::from("value"),
+            }],
+            "env",
+            Some("executable"),
+        );
+        assert!(matches!(validated, Err(ValidationError::Invalid { .. })));
+    }
+
+    #[test]
+    fn test_executable_env_last_duplicate_wins() {
+        let validated = ValidatedExecutable::validate(
+            Executable {
+                name: String::from("name"),
+                command: String::from("command"),
+                description: String::new(),
+                args: vec![],
+                env: ["first", "last"]
+                    .into_iter()
+                    .map(|value| EnvironmentVariable {
+                        name: String::from("GREETING"),
+                        value: String::from(value),
+                    })
+                    .collect(),
+                clear_env: false,
+                working_dir: None,
+                restart_policy: 0,
+                max_restarts: None,
+                restart_backoff_ms: None,
+                max_restart_backoff_ms: None,
+                seccomp_profile: None,
+                capabilities: None,
+                rlimits: vec![],
+                command_mode: 0,
             },
+            None,
+        )
+        .expect("valid executable");
+
+        let spec = super::super::executables::ExecutableSpec::from(validated);
+        let env: Vec<_> = spec.command.as_std().get_envs().collect();
+        assert_eq!(
+            env,
+            vec![(
+                std::ffi::OsStr::new("GREETING"),
+                Some(std::ffi::OsStr::new("last"))
+            )]
+        );
+    }
+
+    #[test]
+    fn test_executable_relative_working_dir() {
+        let validated = ExecutableValidator::validate_working_dir(
+            Some(String::from("tmp")),
+            "working_dir",
+            Some("executable"),
+        );
+        assert!(matches!(validated, Err(ValidationError::Invalid { .. })));
+    }
+
+    #[test]
+    fn test_executable_restart_backoff() {
+        let executable = |restart_backoff_ms, max_restart_backoff_ms| {
+            ValidatedExecutable::validate(
+                Executable {
+                    name: String::from("name"),
+                    command: String::from("command"),
+                    description: String::new(),
+                    args: vec![],
+                    env: vec![],
+                    clear_env: false,
+                    working_dir: None,
+                    restart_policy: proto::cells::RestartPolicy::OnFailure
+                        as i32,
+                    max_restarts: Some(3),
+                    restart_backoff_ms,
+                    max_restart_backoff_ms,
+                    seccomp_profile: None,
+                    capabilities: None,
+                    rlimits: vec![],
+                    command_mode: 0,
+                },
+                None,
+            )
+        };
+
+        let validated = executable(Some(500), None).unwrap();
+        assert_eq!(validated.restart_policy, RestartPolicy::OnFailure);
+        assert_eq!(validated.restart_backoff_ms, Duration::from_millis(500));
+        assert_eq!(
+            validated.max_restart_backoff_ms,
+            DEFAULT_MAX_RESTART_BACKOFF
+        );
+
+        assert!(matches!(
+            executable(Some(0), None),
+            Err(ValidationError::Minimum { .. })
+        ));
+        assert!(matches!(
+            executable(Some(500), Some(100)),
+            Err(ValidationError::Minimum { .. })
+        ));
+    }
+
+    #[test]
+    fn test_seccomp_profile() {
+        let validated = validate_seccomp_profile(
+            Some(String::from("default")),
+            "seccomp_profile",
+            Some("executable"),
+        )
+        .expect("valid");
+        assert_eq!(validated.expect("profile").as_str(), "default");
+
+        let validated = validate_seccomp_profile(
+            Some(String::from(
+                r#"{
+                    "defaultAction": "SCMP_ACT_ERRNO",
+                    "syscalls": [
+                        { "names": ["read"], "action": "SCMP_ACT_ALLOW" }
+                    ]
+                }"#,
+            )),
+            "seccomp_profile",
+            Some("executable"),
+        );
+        assert!(matches!(validated, Ok(Some(_))));
+
+        assert!(matches!(
+            validate_seccomp_profile(
+                Some(String::from("unconfined")),
+                "seccomp_profile",
+                Some("executable"),
+            ),
+            Err(ValidationError::Invalid { field })
+                if field == "executable.seccomp_profile"
+        ));
+    }
+
+    #[test]
+    fn test_executable_capabilities() {
+        let validate = |capabilities| {
+            ExecutableValidator::validate_capabilities(
+                Some(capabilities),
+                "capabilities",
+                Some("executable"),
+            )
+        };
+        let caps = |names: &[&str]| -> Vec<String> {
+            names.iter().map(|x| x.to_string()).collect()
+        };
+
+        // aer sends empty capabilities if none are given
+        assert_eq!(
+            validate(Capabilities::default()).expect("default"),
+            super::super::executables::Capabilities::default()
+        );
+        assert_eq!(
+            validate(Capabilities { privileged: true, ..Default::default() })
+                .expect("privileged"),
+            super::super::executables::Capabilities::Privileged
+        );
+
+        let validated = validate(Capabilities {
+            bounding: caps(&["CAP_CHOWN", "CAP_NET_RAW"]),
+            permitted: caps(&["CAP_NET_RAW"]),
+            effective: caps(&["CAP_NET_RAW"]),
+            ..Default::default()
+        });
+        let Ok(super::super::executables::Capabilities::Restricted(sets)) =
+            validated
+        else {
+            panic!("restricted capabilities");
+        };
+        assert_eq!(
+            sets.effective,
+            CapsHashSet::from([Capability::CAP_NET_RAW])
+        );
+        assert!(sets.ambient.is_empty());
+
+        assert!(matches!(
+            validate(Capabilities {
+                bounding: caps(&["CAP_NOT_A_CAPABILITY"]),
+                ..Default::default()
+            }),
+            Err(ValidationError::Invalid { field })
+                if field == "executable.capabilities.bounding[0]"
+        ));
+        assert!(matches!(
+            validate(Capabilities {
+                effective: caps(&["CAP_NET_RAW"]),
+                ..Default::default()
+            }),
+            Err(ValidationError::Invalid { field })
+                if field == "executable.capabilities.effective"
+        ));
+        assert!(matches!(
+            validate(Capabilities {
+                privileged: true,
+                ambient: caps(&["CAP_KILL"]),
+                ..Default::default()
+            }),
+            Err(ValidationError::Invalid { field })
+                if field == "executable.capabilities.ambient"
+        ));
+    }
+
+    #[test]
+    fn test_executable_rlimits() {
+        let validate = |rlimits| {
+            ExecutableValidator::validate_rlimits(
+                rlimits,
+                "rlimits",
+                Some("executable"),
+            )
+        };
+        let nofile =
+            |soft, hard| Rlimit { kind: RlimitKind::Nofile as i32, soft, hard };
+
+        // aer sends an empty resource limit if none are given
+        assert_eq!(validate(vec![Rlimit::default()]).expect("empty"), vec![]);
+
+        assert_eq!(
+            validate(vec![nofile(Some(4096), Some(65536))]).expect("nofile"),
+            vec![super::super::executables::Rlimit {
+                resource: nix::sys::resource::Resource::RLIMIT_NOFILE,
+                soft: Some(4096),
+                hard: Some(65536),
+            }]
         );
+        assert!(validate(vec![nofile(Some(1024), None)]).is_ok());
+        assert!(validate(vec![nofile(None, Some(4096))]).is_ok());
+
+        assert!(matches!(
+            validate(vec![nofile(Some(65536), Some(4096))]),
+            Err(ValidationError::Maximum { field, .. })
+                if field == "executable.rlimits[0].soft"
+        ));
+        assert!(matches!(
+            validate(vec![Rlimit { kind: 100, soft: None, hard: None }]),
+            Err(ValidationError::Invalid { field })
+                if field == "executable.rlimits[0].kind"
+        ));
+        assert!(matches!(
+            validate(vec![Rlimit { kind: 0, soft: Some(1), hard: None }]),
+            Err(ValidationError::Required { field })
+                if field == "executable.rlimits[0].kind"
+        ));
+        assert!(matches!(
+            validate(vec![nofile(None, None), nofile(None, None)]),
+            Err(ValidationError::Invalid { field })
+                if field == "executable.rlimits[1].kind"
+        ));
     }
 
     #[test]
@@ -545,4 +2696,160 @@ mod tests {
         assert!(validated.is_ok());
         assert_eq!(validated.unwrap(), OsString::from("command"));
     }
+
+    #[test]
+    fn test_cell_service_watch_pressure_request_valid() {
+        let validated = ValidatedCellServiceWatchPressureRequest::validate(
+            CellServiceWatchPressureRequest {
+                cell_name: String::from("ae-test"),
+                resource: proto::cells::PressureResource::Memory as i32,
+                kind: proto::cells::PressureKind::Some as i32,
+                stall_ms: 150,
+                window_ms: 1000,
+            },
+            None,
+        );
+        assert!(validated.is_ok());
+        let validated = validated.unwrap();
+        assert_eq!(validated.resource, PressureResource::Memory);
+        assert_eq!(validated.kind, PressureKind::Some);
+    }
+
+    #[test]
+    fn test_cell_service_watch_pressure_request_unspecified_resource() {
+        let validated = ValidatedCellServiceWatchPressureRequest::validate(
+            CellServiceWatchPressureRequest {
+                cell_name: String::from("ae-test"),
+                resource: proto::cells::PressureResource::Unspecified as i32,
+                kind: proto::cells::PressureKind::Some as i32,
+                stall_ms: 150,
+                window_ms: 1000,
+            },
+            None,
+        );
+        assert!(matches!(validated, Err(ValidationError::Required { .. })));
+    }
+
+    #[test]
+    fn test_cell_service_watch_pressure_request_window_too_large() {
+        let validated = ValidatedCellServiceWatchPressureRequest::validate(
+            CellServiceWatchPressureRequest {
+                cell_name: String::from("ae-test"),
+                resource: proto::cells::PressureResource::Io as i32,
+                kind: proto::cells::PressureKind::Full as i32,
+                stall_ms: 150,
+                window_ms: 10_001,
+            },
+            None,
+        );
+        assert!(matches!(validated, Err(ValidationError::Maximum { .. })));
+    }
+
+    #[test]
+    fn test_cell_service_watch_pressure_request_stall_above_window() {
+        let validated = ValidatedCellServiceWatchPressureRequest::validate(
+            CellServiceWatchPressureRequest {
+                cell_name: String::from("ae-test"),
+                resource: proto::cells::PressureResource::Cpu as i32,
+                kind: proto::cells::PressureKind::Some as i32,
+                stall_ms: 2000,
+                window_ms: 1000,
+            },
+            None,
+        );
+        assert!(matches!(validated, Err(ValidationError::Maximum { .. })));
+    }
+
+    #[test]
+    fn test_cell_service_watch_stats_request_interval_too_small() {
+        assert!(
+            CellServiceWatchStatsRequestValidator::validate_interval_ms(
+                Some(99),
+                "field",
+                Some("parent")
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cell_service_watch_stats_request_interval_valid() {
+        let validated =
+            CellServiceWatchStatsRequestValidator::validate_interval_ms(
+                Some(100),
+                "field",
+                Some("parent"),
+            );
+        assert!(validated.is_ok());
+        assert_eq!(validated.unwrap(), Some(100));
+
+        let validated =
+            CellServiceWatchStatsRequestValidator::validate_interval_ms(
+                None,
+                "field",
+                Some("parent"),
+            );
+        assert!(validated.is_ok());
+        assert_eq!(validated.unwrap(), None);
+    }
+
+    #[test]
+    fn test_cell_service_stop_request_signal() {
+        let stop_request = |signal, grace_period_ms| {
+            ValidatedCellServiceStopRequest::validate(
+                CellServiceStopRequest {
+                    cell_name: None,
+                    executable_name: String::from("name"),
+                    signal,
+                    grace_period_ms,
+                },
+                None,
+            )
+        };
+
+        let validated = stop_request(None, None).unwrap();
+        assert_eq!(validated.signal, Signal::SIGTERM);
+        assert_eq!(validated.grace_period_ms, DEFAULT_STOP_GRACE_PERIOD);
+
+        let validated = stop_request(Some(2), Some(500)).unwrap();
+        assert_eq!(validated.signal, Signal::SIGINT);
+        assert_eq!(validated.grace_period_ms, Duration::from_millis(500));
+
+        assert!(matches!(
+            stop_request(Some(1000), None),
+            Err(ValidationError::Invalid { .. })
+        ));
+        assert!(matches!(
+            stop_request(None, Some(300_001)),
+            Err(ValidationError::Maximum { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cell_service_signal_request_allow_list() {
+        let signal_request = |signal| {
+            ValidatedCellServiceSignalRequest::validate(
+                CellServiceSignalRequest {
+                    cell_name: None,
+                    executable_name: String::from("name"),
+                    signal,
+                },
+                None,
+            )
+        };
+
+        assert_eq!(signal_request(1).unwrap().signal, Signal::SIGHUP);
+        assert_eq!(signal_request(10).unwrap().signal, Signal::SIGUSR1);
+
+        // Not in the allow-list
+        assert!(matches!(
+            signal_request(11),
+            Err(ValidationError::Invalid { .. })
+        ));
+        // Not a signal
+        assert!(matches!(
+            signal_request(0),
+            Err(ValidationError::Invalid { .. })
+        ));
+    }
 }
@@ -12,9 +12,11 @@
  * Copyright 2022 - 2024, the aurae contributors                              *
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
-use tracing::{Level, info};
+use crate::logging::stream_logger::StreamLogger;
+use tracing::{Level, Subscriber, info};
 use tracing_subscriber::{
-    EnvFilter, Layer, layer::SubscriberExt, util::SubscriberInitExt,
+    EnvFilter, Layer, layer::SubscriberExt, registry::LookupSpan,
+    util::SubscriberInitExt,
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -63,6 +65,7 @@ fn init_container_logging(tracing_level: Level) -> Result<(), LoggingError> {
 
     tracing_subscriber::registry()
         .with(stdout_layer)
+        .with(stream_layer(tracing_level))
         .try_init()
         .map_err(|e| e.into())
 }
@@ -94,6 +97,7 @@ fn init_daemon_logging(tracing_level: Level) -> Result<(), LoggingError> {
     tracing_subscriber::registry()
         .with(syslog_layer)
         .with(stdout_layer)
+        .with(stream_layer(tracing_level))
         .try_init()
         .map_err(|e| e.into())
 }
@@ -105,6 +109,7 @@ fn init_stdout_logging(tracing_level: Level) -> Result<(), LoggingError> {
         .compact()
         .with_env_filter(format!("auraed={tracing_level}"))
         .finish()
+        .with(stream_layer(tracing_level))
         .try_init()
         .map_err(|e| e.into())
 }
@@ -114,6 +119,19 @@ fn init_pid1_logging(tracing_level: Level) -> Result<(), LoggingError> {
     tracing_subscriber::fmt()
         .compact()
         .with_env_filter(format!("auraed={tracing_level}"))
+        .finish()
+        .with(stream_layer(tracing_level))
         .try_init()
-        .map_err(|e| LoggingError::SetupFailure { source: e })
+        .map_err(|e| LoggingError::SetupFailure { source: e.into() })
+}
+
+/// Sends the events to the "auraed" log channel, streamed by the observe API.
+fn stream_layer<S>(tracing_level: Level) -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    Layer::with_filter(
+        StreamLogger,
+        EnvFilter::new(format!("auraed={tracing_level}")),
+    )
 }
@@ -28,7 +28,7 @@ use std::io::{BufReader, Read};
 mod fileio;
 mod fs;
 mod logging;
-mod network;
+pub(crate) mod network;
 mod power;
 mod system_runtimes;
 
@@ -16,9 +16,11 @@
 use futures::stream::TryStreamExt;
 use ipnetwork::{IpNetwork, Ipv4Network, Ipv6Network};
 use netlink_packet_route::address::AddressAttribute;
-use netlink_packet_route::link::LinkAttribute;
+use netlink_packet_route::link::{LinkAttribute, LinkMessage};
 use nix::libc::EEXIST;
-use rtnetlink::{Handle, LinkUnspec, RouteMessageBuilder};
+use rtnetlink::{
+    Handle, LinkBridge, LinkUnspec, LinkVeth, RouteMessageBuilder,
+};
 use std::collections::HashMap;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str;
@@ -53,6 +55,28 @@ pub(crate) enum NetworkError {
         route_destination: IpNetwork,
         source: rtnetlink::Error,
     },
+    #[error(
+        "Error adding default route via `{gateway}` for device `{iface}`: {source}"
+    )]
+    ErrorAddingDefaultRoute {
+        iface: String,
+        gateway: IpAddr,
+        source: rtnetlink::Error,
+    },
+    #[error("Failed to add link `{iface}`: {source}")]
+    ErrorAddingLink { iface: String, source: rtnetlink::Error },
+    #[error("Failed to attach link `{iface}` to `{controller}`: {source}")]
+    ErrorSettingController {
+        iface: String,
+        controller: String,
+        source: rtnetlink::Error,
+    },
+    #[error(
+        "Failed to move link `{iface}` to the network namespace of pid {pid}: {source}"
+    )]
+    ErrorMovingLink { iface: String, pid: u32, source: rtnetlink::Error },
+    #[error("Failed to rename link `{iface}` to `{name}`: {source}")]
+    ErrorRenamingLink { iface: String, name: String, source: rtnetlink::Error },
     #[error(transparent)]
     Other(#[from] rtnetlink::Error),
 }
@@ -105,6 +129,68 @@ impl Network {
         }
         info!("==========================");
     }
+
+    /// Creates the bridge with the addresses, unless it exists, and sets it
+    /// up.
+    pub(crate) async fn add_bridge(
+        &self,
+        bridge: &str,
+        addresses: &[IpNetwork],
+    ) -> Result<(), NetworkError> {
+        if get_link_index(&self.0, bridge.to_owned()).await.is_err() {
+            add_link(&self.0, bridge, LinkBridge::new(bridge).build()).await?;
+        }
+
+        for address in addresses {
+            add_address(&self.0, bridge.to_owned(), *address).await?;
+        }
+
+        set_link_up(&self.0, bridge.to_owned()).await
+    }
+
+    /// Creates a veth pair, attaches `iface` to the bridge and sets it up,
+    /// then moves `peer` to the network namespace of the process.
+    pub(crate) async fn add_veth(
+        &self,
+        iface: &str,
+        peer: &str,
+        bridge: &str,
+        pid: u32,
+    ) -> Result<(), NetworkError> {
+        add_link(&self.0, iface, LinkVeth::new(iface, peer).build()).await?;
+        set_controller(&self.0, iface.to_owned(), bridge.to_owned()).await?;
+        set_link_up(&self.0, iface.to_owned()).await?;
+        move_to_netns(&self.0, peer.to_owned(), pid).await
+    }
+
+    /// Configures the peer of a veth pair, once moved to the network
+    /// namespace we are connected to: renames it, adds the addresses, sets it
+    /// up along with the loopback, and routes the traffic through the
+    /// gateways by default.
+    pub(crate) async fn configure_veth_peer(
+        &self,
+        peer: &str,
+        name: &str,
+        addresses: &[IpNetwork],
+        gateways: &[IpAddr],
+    ) -> Result<(), NetworkError> {
+        // Links are renamed while down
+        rename_link(&self.0, peer.to_owned(), name.to_owned()).await?;
+
+        for address in addresses {
+            add_address(&self.0, name.to_owned(), *address).await?;
+        }
+
+        set_link_up(&self.0, "lo".to_owned()).await?;
+        set_link_up(&self.0, name.to_owned()).await?;
+
+        for gateway in gateways {
+            add_default_route(&self.0, name.to_owned(), *gateway).await?;
+        }
+
+        info!("Successfully configured {name}");
+        Ok(())
+    }
 }
 
 async fn configure_loopback(handle: &Handle) -> Result<(), NetworkError> {
@@ -135,6 +221,7 @@ async fn configure_loopback(handle: &Handle) -> Result<(), NetworkError> {
     .await?;
 
     set_link_up(handle, LOOPBACK_DEV.to_owned()).await?;
+    wait_for_link_up(LOOPBACK_DEV);
 
     info!("Successfully configured {}", LOOPBACK_DEV);
     Ok(())
@@ -156,6 +243,7 @@ async fn configure_nic(
     add_address(handle, config.device.clone(), ipv6_addr).await?;
 
     set_link_up(handle, config.device.clone()).await?;
+    wait_for_link_up(&config.device);
 
     add_route_v6(
         handle,
@@ -215,15 +303,19 @@ async fn set_link_up(
         .execute()
         .await
         .map(|_| {
-            // TODO: replace sleep with an await mechanism that checks if device is up (with a timeout)
-            // TODO: https://github.com/aurae-runtime/auraed/issues/40
-            info!("Waiting for link '{iface}' to become up");
-            thread::sleep(Duration::from_secs(3));
-            info!("Waited 3 seconds, assuming link '{iface}' is up");
+            trace!("Set link {iface} up");
         })
         .map_err(|e| NetworkError::ErrorSettingLinkUp { iface, source: e })
 }
 
+fn wait_for_link_up(iface: &str) {
+    // TODO: replace sleep with an await mechanism that checks if device is up (with a timeout)
+    // TODO: https://github.com/aurae-runtime/auraed/issues/40
+    info!("Waiting for link '{iface}' to become up");
+    thread::sleep(Duration::from_secs(3));
+    info!("Waited 3 seconds, assuming link '{iface}' is up");
+}
+
 #[allow(unused)]
 async fn set_link_down(
     handle: &Handle,
@@ -262,6 +354,81 @@ async fn get_link_index(
     }
 }
 
+async fn add_link(
+    handle: &Handle,
+    iface: &str,
+    msg: LinkMessage,
+) -> Result<(), NetworkError> {
+    handle
+        .link()
+        .add(msg)
+        .execute()
+        .await
+        .map(|_| trace!("Added link {iface}"))
+        .map_err(|e| NetworkError::ErrorAddingLink {
+            iface: iface.to_owned(),
+            source: e,
+        })
+}
+
+async fn set_controller(
+    handle: &Handle,
+    iface: String,
+    controller: String,
+) -> Result<(), NetworkError> {
+    let link_index = get_link_index(handle, iface.clone()).await?;
+    let controller_index = get_link_index(handle, controller.clone()).await?;
+    let msg = LinkUnspec::new_with_index(link_index)
+        .controller(controller_index)
+        .build();
+
+    handle
+        .link()
+        .set(msg)
+        .execute()
+        .await
+        .map(|_| trace!("Attached link {iface} to {controller}"))
+        .map_err(|e| NetworkError::ErrorSettingController {
+            iface,
+            controller,
+            source: e,
+        })
+}
+
+async fn move_to_netns(
+    handle: &Handle,
+    iface: String,
+    pid: u32,
+) -> Result<(), NetworkError> {
+    let link_index = get_link_index(handle, iface.clone()).await?;
+    let msg = LinkUnspec::new_with_index(link_index).setns_by_pid(pid).build();
+
+    handle
+        .link()
+        .set(msg)
+        .execute()
+        .await
+        .map(|_| trace!("Moved link {iface} to the network namespace of {pid}"))
+        .map_err(|e| NetworkError::ErrorMovingLink { iface, pid, source: e })
+}
+
+async fn rename_link(
+    handle: &Handle,
+    iface: String,
+    name: String,
+) -> Result<(), NetworkError> {
+    let link_index = get_link_index(handle, iface.clone()).await?;
+    let msg = LinkUnspec::new_with_index(link_index).name(name.clone()).build();
+
+    handle
+        .link()
+        .set(msg)
+        .execute()
+        .await
+        .map(|_| trace!("Renamed link {iface} to {name}"))
+        .map_err(|e| NetworkError::ErrorRenamingLink { iface, name, source: e })
+}
+
 #[allow(unused)]
 async fn add_route_v4(
     handle: &Handle,
@@ -313,6 +480,30 @@ async fn add_route_v6(
     Ok(())
 }
 
+async fn add_default_route(
+    handle: &Handle,
+    iface: String,
+    gateway: IpAddr,
+) -> Result<(), NetworkError> {
+    let link_index = get_link_index(handle, iface.clone()).await?;
+
+    let route = match gateway {
+        IpAddr::V4(gateway) => RouteMessageBuilder::<Ipv4Addr>::new()
+            .output_interface(link_index)
+            .gateway(gateway)
+            .build(),
+        IpAddr::V6(gateway) => RouteMessageBuilder::<Ipv6Addr>::new()
+            .output_interface(link_index)
+            .gateway(gateway)
+            .build(),
+    };
+    handle.route().add(route).execute().await.map_err(|e| {
+        NetworkError::ErrorAddingDefaultRoute { iface, gateway, source: e }
+    })?;
+
+    Ok(())
+}
+
 async fn get_links(
     handle: &Handle,
 ) -> Result<HashMap<u32, String>, NetworkError> {
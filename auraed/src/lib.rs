@@ -69,14 +69,24 @@ use crate::ebpf::{
     SignalSignalGenerateTracepointProgram, TaskstatsExitKProbeProgram,
 };
 use crate::{
-    cells::CellService, cri::oci::AuraeOCIBuilder,
-    cri::runtime_service::RuntimeService, discovery::DiscoveryService,
-    init::Context as AuraeContext, init::SocketStream,
-    logging::log_channel::LogChannel, observe::ObserveService,
+    cells::{CellName, CellService},
+    cri::oci::AuraeOCIBuilder,
+    cri::runtime_service::RuntimeService,
+    discovery::DiscoveryService,
+    init::Context as AuraeContext,
+    init::SocketStream,
+    logging::log_channel::{LogChannel, remove_stale_segments},
+    logging::log_file::{
+        DEFAULT_LOG_FILE_RETENTION, DEFAULT_MAX_LOG_FILE_AGE,
+        DEFAULT_MAX_LOG_FILE_SIZE, LogFile, LogFileConfig,
+    },
+    logging::stream_logger::StreamLogger,
+    observe::ObserveService,
     spawn::spawn_auraed_oci_to,
 };
 use anyhow::{Context, anyhow};
 use aurae_ebpf_shared::{ForkedProcess, ProcessExit, Signal};
+use ipnetwork::{Ipv4Network, Ipv6Network};
 use once_cell::sync::OnceCell;
 use proto::{
     cells::cell_service_server::CellServiceServer,
@@ -86,6 +96,7 @@ use proto::{
     vms::vm_service_server::VmServiceServer,
 };
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncWrite;
 use tokio::task::JoinHandle;
@@ -128,6 +139,25 @@ pub struct AuraedRuntime {
     pub runtime_dir: PathBuf,
     /// Configurable library directory. Defaults to /var/lib/aurae.
     pub library_dir: PathBuf,
+    /// Configurable directory of the log files of executables.
+    /// Defaults to /var/run/aurae/logs.
+    pub logs_dir: PathBuf,
+    /// The size, in bytes, log files are rotated at. Defaults to 10MiB.
+    pub log_file_max_size: u64,
+    /// The age log files are rotated at. Defaults to 24 hours.
+    pub log_file_max_age: Duration,
+    /// The number of rotated files kept of each log file. Defaults to 5.
+    pub log_file_retention: usize,
+    /// The pool the IPv4 addresses of the cells isolating their network are
+    /// assigned from. Its first address is assigned to the bridge of auraed.
+    pub cell_ipv4_pool: Ipv4Network,
+    /// The pool the IPv6 addresses of the cells isolating their network are
+    /// assigned from. Its first address is assigned to the bridge of auraed.
+    pub cell_ipv6_pool: Ipv6Network,
+    /// The seccomp profiles of the cell a nested auraed runs in, and of its
+    /// ancestors, installed on every process it spawns but not on itself, so
+    /// it can allocate nested cells. Empty if not nested.
+    pub cell_seccomp_profiles: Vec<String>,
     // /// Provides logging channels to expose auraed logging via grpc
     //pub log_collector: Arc<LogChannel>,
 }
@@ -144,6 +174,42 @@ impl AuraedRuntime {
     pub(crate) fn default_socket_address(&self) -> PathBuf {
         self.runtime_dir.join("aurae.sock")
     }
+
+    /// The directory of the runtime directories of the nested auraed.
+    pub(crate) fn cells_dir(&self) -> PathBuf {
+        self.runtime_dir.join("cells")
+    }
+
+    /// The directory of the log files of the executables of a cell.
+    /// The directories of nested cells are not nested in the directory of
+    /// their parent, which the parent cell can write to.
+    pub(crate) fn cell_logs_dir(&self, cell_name: &CellName) -> PathBuf {
+        self.logs_dir
+            .join(cell_name.as_inner().to_string_lossy().replace('/', "_"))
+    }
+
+    pub(crate) fn log_segments_dir(&self) -> PathBuf {
+        self.runtime_dir.join("log-segments")
+    }
+
+    pub(crate) fn auraed_log_file(&self) -> PathBuf {
+        self.runtime_dir.join("auraed.log")
+    }
+
+    pub(crate) fn cells_state_file(&self) -> PathBuf {
+        self.library_dir.join("cells.json")
+    }
+
+    /// Configures the log files written in the directory to be rotated as
+    /// configured for the runtime.
+    pub(crate) fn log_file_config(&self, dir: PathBuf) -> LogFileConfig {
+        LogFileConfig {
+            dir,
+            max_size: self.log_file_max_size,
+            max_age: self.log_file_max_age,
+            retention: self.log_file_retention,
+        }
+    }
 }
 
 impl Default for AuraedRuntime {
@@ -156,6 +222,15 @@ impl Default for AuraedRuntime {
             server_key: PathBuf::from("/etc/aurae/pki/server.key"),
             runtime_dir: PathBuf::from("/var/run/aurae"),
             library_dir: PathBuf::from("/var/lib/aurae"),
+            logs_dir: PathBuf::from("/var/run/aurae/logs"),
+            log_file_max_size: DEFAULT_MAX_LOG_FILE_SIZE,
+            log_file_max_age: DEFAULT_MAX_LOG_FILE_AGE,
+            log_file_retention: DEFAULT_LOG_FILE_RETENTION,
+            cell_ipv4_pool: "10.200.0.0/16".parse().expect("ipv4 network"),
+            cell_ipv6_pool: "fd61:7572:6165::/64"
+                .parse()
+                .expect("ipv6 network"),
+            cell_seccomp_profiles: vec![],
         }
     }
 }
@@ -188,6 +263,10 @@ pub async fn run(
             )
         })?;
 
+        // The log segments of a previous auraed were written by channels
+        // that are gone
+        remove_stale_segments(&runtime.log_segments_dir());
+
         // We don't want TLS in cell context
         let mut server = if context != AuraeContext::Cell {
             let server_crt =
@@ -245,14 +324,32 @@ pub async fn run(
         let (health_reporter, health_service) =
             tonic_health::server::health_reporter();
 
-        let observe_service = ObserveService::new(
-            LogChannel::new(String::from("auraed")),
-            perf_events,
-        );
+        let mut aurae_logger = LogChannel::new(String::from("auraed"));
+        // Nested auraed share the runtime directory of the host
+        if context != AuraeContext::Cell {
+            let log_file = runtime.auraed_log_file();
+            let config = runtime.log_file_config(runtime.runtime_dir.clone());
+            match LogFile::open(log_file, &config) {
+                Ok(file) => aurae_logger = aurae_logger.with_file(file),
+                Err(e) => warn!("failed to open auraed log file: {e}"),
+            }
+        }
+
+        // The events traced from now on are also sent to the channel
+        StreamLogger::set_channel(aurae_logger.clone());
+
+        let observe_service = ObserveService::new(aurae_logger, perf_events);
         let observe_service_server =
             ObserveServiceServer::new(observe_service.clone());
 
-        let cell_service = CellService::new(observe_service.clone());
+        let mut cell_service = CellService::new(observe_service.clone());
+        // Cells are only allocated by the auraed on the host, whose library
+        // directory is shared with the nested auraed
+        if matches!(context, AuraeContext::Pid1 | AuraeContext::Daemon) {
+            cell_service =
+                cell_service.with_state_file(runtime.cells_state_file());
+            cell_service.rehydrate().await;
+        }
         let cell_service_server = CellServiceServer::new(cell_service.clone());
         health_reporter.set_serving::<CellServiceServer<CellService>>().await;
 
@@ -13,9 +13,143 @@
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
 
-use super::get_timestamp_sec;
+use super::get_timestamp;
+use super::log_file::LogFile;
 use proto::observe::LogItem;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock, Mutex, mpsc};
 use tokio::sync::broadcast::{self, Receiver, Sender};
+use tracing::warn;
+
+/// The number of items a [LogChannel] keeps in memory by default.
+pub const DEFAULT_LOG_CHANNEL_CAPACITY: usize = 1024;
+/// The number of items written to a segment file before starting a new one.
+pub const DEFAULT_ITEMS_PER_SEGMENT: usize = 4096;
+/// The number of segment files a [LogChannel] keeps by default.
+pub const DEFAULT_MAX_SEGMENTS: usize = 8;
+
+/// The thread the segments and files of all the log channels are written
+/// from, in the order the writes are submitted, so the tasks sending to the
+/// channels do not block on IO.
+static WRITER: LazyLock<mpsc::Sender<Box<dyn FnOnce() + Send>>> =
+    LazyLock::new(|| {
+        let (tx, rx) = mpsc::channel::<Box<dyn FnOnce() + Send>>();
+        let _ = std::thread::Builder::new()
+            .name(String::from("log-writer"))
+            .spawn(move || {
+                for write in rx {
+                    write();
+                }
+            })
+            .expect("log writer thread");
+        tx
+    });
+
+/// Runs the write on the [WRITER] thread.
+fn submit(write: impl FnOnce() + Send + 'static) {
+    // The writer thread never exits
+    let _ = WRITER.send(Box::new(write));
+}
+
+/// Waits for the writes submitted so far to be done, e.g., before reading
+/// a log file.
+pub fn wait_for_writes() {
+    let (tx, rx) = mpsc::sync_channel(1);
+    submit(move || {
+        let _ = tx.send(());
+    });
+    let _ = rx.recv();
+}
+
+/// Waits for the writes submitted so far to be done, without blocking the
+/// calling task.
+pub async fn flush_writes() {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    submit(move || {
+        let _ = tx.send(());
+    });
+    let _ = rx.await;
+}
+
+/// Removes the segments left in the directory by a previous auraed, as the
+/// channels they were written by are gone.
+pub fn remove_stale_segments(dir: &Path) {
+    match fs::remove_dir_all(dir) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            warn!("failed to remove log segments in {}: {e}", dir.display());
+        }
+        _ => {}
+    }
+}
+
+/// Configures how much of its history a [LogChannel] keeps, for subscribers
+/// to replay.
+#[derive(Debug, Clone)]
+pub struct LogChannelConfig {
+    /// The number of the latest items kept in memory. It is also the number
+    /// of items a subscriber can lag behind before missing some.
+    pub capacity: usize,
+    /// The directory under which the items evicted from memory are written,
+    /// in segment files of a directory of the channel's own.
+    /// Evicted items are dropped if not set.
+    pub segments_dir: Option<PathBuf>,
+    /// The number of items written to a segment file before starting a new
+    /// one
+    pub items_per_segment: usize,
+    /// The number of segment files kept, the oldest being removed first
+    pub max_segments: usize,
+}
+
+impl Default for LogChannelConfig {
+    fn default() -> Self {
+        Self {
+            capacity: DEFAULT_LOG_CHANNEL_CAPACITY,
+            segments_dir: None,
+            items_per_segment: DEFAULT_ITEMS_PER_SEGMENT,
+            max_segments: DEFAULT_MAX_SEGMENTS,
+        }
+    }
+}
+
+/// Where a subscriber of a [LogChannel] starts reading.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogReplay {
+    /// Only the items sent after subscribing
+    #[default]
+    Live,
+    /// All the items the channel still has
+    FromBeginning,
+    /// The last n items
+    Tail(usize),
+    /// The items sent at, or after, the timestamp, in seconds and the
+    /// nanoseconds within the second
+    Since(i64, i32),
+}
+
+impl LogReplay {
+    /// The items to replay out of all the items, oldest first.
+    pub fn apply(self, mut items: Vec<LogItem>) -> Vec<LogItem> {
+        match self {
+            LogReplay::Live => vec![],
+            LogReplay::FromBeginning => items,
+            LogReplay::Tail(n) => {
+                items.split_off(items.len().saturating_sub(n))
+            }
+            LogReplay::Since(secs, nanos) => {
+                items.retain(|x| sent_since(x, secs, nanos));
+                items
+            }
+        }
+    }
+}
+
+/// Whether the item was sent at, or after, the timestamp.
+fn sent_since(item: &LogItem, secs: i64, nanos: i32) -> bool {
+    (item.timestamp, item.timestamp_nanos) >= (secs, nanos)
+}
 
 /// Abstraction Layer for one log generating entity
 /// LogChannel provides channels between Log producers and log consumers
@@ -24,14 +158,57 @@ pub struct LogChannel {
     /// The human readable (public) name for this log channel.
     pub name: String,
     tx: Sender<LogItem>,
+    history: Arc<Mutex<History>>,
+    /// The file the items are also written to, if any
+    file: Option<LogFile>,
 }
 
 impl LogChannel {
     /// Constructor creating the channel for log communication
     pub fn new(name: String) -> LogChannel {
-        // TODO: decide for a cap. 40 is arbitrary
-        let (tx, _) = broadcast::channel(40);
-        LogChannel { name, tx }
+        Self::with_config(name, &LogChannelConfig::default())
+    }
+
+    /// Creates the channel, keeping its history as configured.
+    pub fn with_config(name: String, config: &LogChannelConfig) -> LogChannel {
+        let capacity = config.capacity.max(1);
+        let (tx, _) = broadcast::channel(capacity);
+
+        let segments = config.segments_dir.as_ref().and_then(|dir| {
+            Segments::create_in(dir, config)
+                .inspect_err(|e| {
+                    warn!("no log segments for channel {name}: {e}");
+                })
+                .ok()
+                .map(|x| Arc::new(Mutex::new(Some(x))))
+        });
+
+        let history = History {
+            items: VecDeque::with_capacity(capacity),
+            capacity,
+            segments,
+            sequence: 0,
+        };
+
+        LogChannel {
+            name,
+            tx,
+            history: Arc::new(Mutex::new(history)),
+            file: None,
+        }
+    }
+
+    /// Also writes the items sent to the channel to the file, which can be
+    /// shared with other channels.
+    /// The sequence numbers follow on from the items of the channel already
+    /// in the file, e.g., written before auraed restarted. The items of a
+    /// previous channel of the same name must have been written by then, see
+    /// [flush_writes].
+    pub fn with_file(mut self, file: LogFile) -> Self {
+        self.history.lock().expect("log history").sequence =
+            file.last_sequence(&self.name);
+        self.file = Some(file);
+        self
     }
 
     /// Getter for consumer channel
@@ -39,21 +216,262 @@ impl LogChannel {
         self.tx.subscribe()
     }
 
+    /// Subscribes to the channel, and returns the items to replay before the
+    /// received ones. No item is missed or repeated in between.
+    pub async fn subscribe_from(
+        &self,
+        replay: LogReplay,
+    ) -> (Vec<LogItem>, Receiver<LogItem>) {
+        // The history is copied, as sending to the channel waits for it
+        let (snapshot, rx) = {
+            let history = self.history.lock().expect("log history");
+            (history.snapshot(replay), self.tx.subscribe())
+        };
+
+        let items = match snapshot {
+            Snapshot::Replayed(items) => items,
+            Snapshot::Unread { segments, items } => {
+                tokio::task::spawn_blocking(move || {
+                    replay.apply(Segments::read_before(&segments, items))
+                })
+                .await
+                .expect("read log segments")
+            }
+        };
+
+        (items, rx)
+    }
+
     /// Wrapper that sends a log line to the channel
     pub fn send(&self, line: String) {
-        // send returns an Err if there are no receivers. We ignore that.
-        let _ = self.tx.send(LogItem {
+        // The item is sent while holding the history, so subscribers either
+        // replay it or receive it, and items are sent in sequence
+        let mut history = self.history.lock().expect("log history");
+        history.sequence += 1;
+
+        let (timestamp, timestamp_nanos) = get_timestamp();
+        let item = LogItem {
             channel: self.name.clone(),
             line,
-            // TODO: milliseconds type in protobuf requires 128bit type
-            timestamp: get_timestamp_sec(),
+            timestamp,
+            timestamp_nanos,
+            sequence: history.sequence,
+        };
+
+        if let Some(file) = &self.file {
+            let file = file.clone();
+            let item = item.clone();
+            submit(move || file.write(&item));
+        }
+        history.push(item.clone());
+        // send returns an Err if there are no receivers. We ignore that.
+        let _ = self.tx.send(item);
+    }
+}
+
+/// The items of a [LogChannel] kept for replay: the latest ones in memory,
+/// and the ones evicted from memory in segment files.
+#[derive(Debug)]
+struct History {
+    items: VecDeque<LogItem>,
+    capacity: usize,
+    /// Written to from the [WRITER] thread, and set to [None] if a write
+    /// fails
+    segments: Option<Arc<Mutex<Option<Segments>>>>,
+    /// The sequence number of the last item sent
+    sequence: u64,
+}
+
+impl History {
+    fn push(&mut self, item: LogItem) {
+        if self.items.len() >= self.capacity
+            && let Some(evicted) = self.items.pop_front()
+            && let Some(segments) = &self.segments
+        {
+            let segments = segments.clone();
+            submit(move || {
+                let mut segments = segments.lock().expect("log segments");
+                if let Some(x) = segments.as_mut()
+                    && let Err(e) = x.write(&evicted)
+                {
+                    warn!("disabling log segments in {}: {e}", x.dir.display());
+                    *segments = None;
+                }
+            });
+        }
+        self.items.push_back(item);
+    }
+
+    /// The items to replay, or the ones in memory if the segments have to be
+    /// read too.
+    fn snapshot(&self, replay: LogReplay) -> Snapshot {
+        let items = match replay {
+            LogReplay::Live => vec![],
+            LogReplay::Tail(n) if n <= self.items.len() => {
+                self.items.range(self.items.len() - n..).cloned().collect()
+            }
+            // The items on disk are older than the ones in memory
+            LogReplay::Since(secs, nanos)
+                if self
+                    .items
+                    .front()
+                    .is_some_and(|x| !sent_since(x, secs, nanos)) =>
+            {
+                self.items
+                    .iter()
+                    .filter(|x| sent_since(x, secs, nanos))
+                    .cloned()
+                    .collect()
+            }
+            _ => {
+                let items = self.items.iter().cloned().collect();
+                return match &self.segments {
+                    Some(segments) => {
+                        Snapshot::Unread { segments: segments.clone(), items }
+                    }
+                    None => Snapshot::Replayed(replay.apply(items)),
+                };
+            }
+        };
+        Snapshot::Replayed(items)
+    }
+}
+
+/// A copy of the history of a [LogChannel], taken when subscribing to it.
+enum Snapshot {
+    /// The items to replay
+    Replayed(Vec<LogItem>),
+    /// The items in memory, to replay along with the ones in the segments
+    Unread { segments: Arc<Mutex<Option<Segments>>>, items: Vec<LogItem> },
+}
+
+/// Segment files holding the items evicted from the memory of a
+/// [LogChannel], one JSON encoded item per line.
+/// The files are removed along with the channel.
+#[derive(Debug)]
+struct Segments {
+    dir: PathBuf,
+    items_per_segment: usize,
+    max_segments: u64,
+    /// The index of the segment being written
+    current: u64,
+    file: File,
+    /// The number of items written to the current segment
+    written: usize,
+}
+
+impl Segments {
+    fn create_in(parent: &Path, config: &LogChannelConfig) -> io::Result<Self> {
+        let dir = parent.join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&dir)?;
+        let file = Self::open(&dir, 0)?;
+
+        Ok(Self {
+            dir,
+            items_per_segment: config.items_per_segment.max(1),
+            max_segments: config.max_segments.max(1) as u64,
+            current: 0,
+            file,
+            written: 0,
+        })
+    }
+
+    fn path(dir: &Path, index: u64) -> PathBuf {
+        dir.join(format!("{index:020}.jsonl"))
+    }
+
+    fn open(dir: &Path, index: u64) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(Self::path(dir, index))
+    }
+
+    fn write(&mut self, item: &LogItem) -> io::Result<()> {
+        if self.written >= self.items_per_segment {
+            self.rotate()?;
+        }
+
+        let mut line = serde_json::to_vec(item)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.written += 1;
+
+        Ok(())
+    }
+
+    /// Starts a new segment, and removes the oldest one if there are too
+    /// many.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = Self::open(&self.dir, self.current + 1)?;
+        self.current += 1;
+        self.written = 0;
+
+        if let Some(expired) = self.current.checked_sub(self.max_segments) {
+            match fs::remove_file(Self::path(&self.dir, expired)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads the items of all the segments, oldest first.
+    fn read(&self) -> io::Result<Vec<LogItem>> {
+        let first = self.current.saturating_sub(self.max_segments - 1);
+
+        let mut items = vec![];
+        for index in first..=self.current {
+            let file = match File::open(Self::path(&self.dir, index)) {
+                Ok(file) => file,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            for line in BufReader::new(file).lines() {
+                items.push(serde_json::from_str(&line?)?);
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// All the items of the segments older than the items in memory, then
+    /// the items in memory. It blocks until the evicted items are written.
+    fn read_before(
+        segments: &Mutex<Option<Segments>>,
+        items: Vec<LogItem>,
+    ) -> Vec<LogItem> {
+        wait_for_writes();
+
+        let read = match &*segments.lock().expect("log segments") {
+            Some(x) => x.read().map_err(|e| (x.dir.clone(), e)),
+            None => Ok(vec![]),
+        };
+        let mut all = read.unwrap_or_else(|(dir, e)| {
+            warn!("failed to read log segments in {}: {e}", dir.display());
+            vec![]
         });
+
+        // The items evicted after the copy was taken are in the copy too
+        if let Some(first) = items.first() {
+            all.retain(|x| x.sequence < first.sequence);
+        }
+        all.extend(items);
+        all
+    }
+}
+
+impl Drop for Segments {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::logging::log_file::LogFileConfig;
     use log::Level;
     use simplelog::SimpleLogger;
 
@@ -67,6 +485,10 @@ mod tests {
             .expect("failed to initialize logger");
     }
 
+    fn lines(items: Vec<LogItem>) -> Vec<String> {
+        items.into_iter().map(|x| x.line).collect()
+    }
+
     #[tokio::test]
     async fn test_ringbuffer_queue() {
         init_logging();
@@ -89,4 +511,89 @@ mod tests {
         assert!(cur_item.is_some());
         assert_eq!(cur_item.unwrap().line, "bye".to_string());
     }
+
+    #[tokio::test]
+    async fn subscribe_from_should_replay_history() {
+        let segments_dir = tempfile::tempdir().expect("tempdir");
+        let channel = LogChannel::with_config(
+            "Test".into(),
+            &LogChannelConfig {
+                capacity: 2,
+                segments_dir: Some(segments_dir.path().to_path_buf()),
+                items_per_segment: 2,
+                max_segments: 2,
+            },
+        );
+
+        for line in ["1", "2", "3", "4", "5", "6", "7"] {
+            channel.send(line.into());
+        }
+
+        // "1" and "2" were removed along with the oldest segment
+        let (replayed, _) =
+            channel.subscribe_from(LogReplay::FromBeginning).await;
+        assert_eq!(lines(replayed), ["3", "4", "5", "6", "7"]);
+
+        let (replayed, _) = channel.subscribe_from(LogReplay::Tail(3)).await;
+        assert_eq!(lines(replayed), ["5", "6", "7"]);
+
+        let (replayed, mut rx) = channel.subscribe_from(LogReplay::Live).await;
+        assert!(replayed.is_empty());
+        channel.send("8".into());
+        let item = rx.recv().await.expect("item");
+        assert_eq!(item.line, "8");
+        assert_eq!(item.sequence, 8);
+
+        // The segments are removed along with the last write of the channel
+        drop(channel);
+        flush_writes().await;
+        assert_eq!(
+            fs::read_dir(segments_dir.path()).expect("read_dir").count(),
+            0
+        );
+    }
+
+    #[test]
+    fn since_should_compare_the_nanoseconds() {
+        let items = [(41, 900), (42, 100), (42, 200), (43, 0)]
+            .into_iter()
+            .map(|(timestamp, timestamp_nanos)| LogItem {
+                line: format!("{timestamp}.{timestamp_nanos}"),
+                timestamp,
+                timestamp_nanos,
+                ..Default::default()
+            })
+            .collect();
+
+        // Resuming from the last item received does not repeat the items
+        // sent earlier in the same second
+        assert_eq!(
+            lines(LogReplay::Since(42, 200).apply(items)),
+            ["42.200", "43.0"]
+        );
+    }
+
+    #[tokio::test]
+    async fn with_file_should_follow_on_from_the_sequence_in_the_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = LogFileConfig::new(dir.path().to_path_buf());
+        let path = config.path_for("test");
+        let file = LogFile::open(path.clone(), &config).expect("open");
+
+        let stdout =
+            LogChannel::new("test::stdout".into()).with_file(file.clone());
+        let stderr = LogChannel::new("test::stderr".into()).with_file(file);
+        stdout.send("1".into());
+        stdout.send("2".into());
+        stderr.send("1".into());
+        drop((stdout, stderr));
+        flush_writes().await;
+
+        // As after a restart of auraed
+        let file = LogFile::open(path, &config).expect("reopen");
+        let stdout = LogChannel::new("test::stdout".into()).with_file(file);
+        let mut rx = stdout.subscribe();
+        stdout.send("3".into());
+        assert_eq!(rx.recv().await.expect("item").sequence, 3);
+    }
 }
@@ -0,0 +1,300 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use proto::observe::LogItem;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+use tracing::warn;
+
+/// The size a [LogFile] is rotated at by default.
+pub const DEFAULT_MAX_LOG_FILE_SIZE: u64 = 10 * 1024 * 1024;
+/// The age a [LogFile] is rotated at by default.
+pub const DEFAULT_MAX_LOG_FILE_AGE: Duration =
+    Duration::from_secs(24 * 60 * 60);
+/// The number of rotated files of a [LogFile] kept by default.
+pub const DEFAULT_LOG_FILE_RETENTION: usize = 5;
+
+/// Configures where [LogFile]s are written, and when they are rotated.
+#[derive(Debug, Clone)]
+pub struct LogFileConfig {
+    /// The directory of the log files
+    pub dir: PathBuf,
+    /// Rotate a file once it is larger than this, in bytes
+    pub max_size: u64,
+    /// Rotate a file once it was started longer ago than this
+    pub max_age: Duration,
+    /// The number of rotated files kept, the oldest being removed first
+    pub retention: usize,
+}
+
+impl LogFileConfig {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_size: DEFAULT_MAX_LOG_FILE_SIZE,
+            max_age: DEFAULT_MAX_LOG_FILE_AGE,
+            retention: DEFAULT_LOG_FILE_RETENTION,
+        }
+    }
+
+    /// The path of the log file of `name` (e.g., an executable).
+    pub fn path_for(&self, name: &str) -> PathBuf {
+        log_file_path(&self.dir, name)
+    }
+}
+
+/// The path of the log file of `name` in `dir`.
+pub fn log_file_path(dir: &Path, name: &str) -> PathBuf {
+    // The name must not escape the directory
+    dir.join(format!("{}.log", name.replace('/', "_")))
+}
+
+/// A file the items of log channels are appended to, one JSON encoded item
+/// per line. Once rotated, `<name>.log` is renamed to `<name>.log.1`, which
+/// is renamed to `<name>.log.2`, and so on.
+#[derive(Debug, Clone)]
+pub struct LogFile(Arc<Mutex<Inner>>);
+
+#[derive(Debug)]
+struct Inner {
+    path: PathBuf,
+    max_size: u64,
+    max_age: Duration,
+    retention: usize,
+    file: File,
+    size: u64,
+    started_at: SystemTime,
+}
+
+impl LogFile {
+    /// Opens the file, appending to it if it already exists.
+    pub fn open(path: PathBuf, config: &LogFileConfig) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let (file, size, started_at) = open(&path)?;
+
+        Ok(Self(Arc::new(Mutex::new(Inner {
+            path,
+            max_size: config.max_size,
+            max_age: config.max_age,
+            retention: config.retention,
+            file,
+            size,
+            started_at,
+        }))))
+    }
+
+    /// Appends the item to the file, rotating it first if it is due.
+    /// Failures are logged, as the item was already sent to the channel.
+    pub fn write(&self, item: &LogItem) {
+        let mut inner = self.0.lock().expect("log file");
+        if let Err(e) = inner.write(item) {
+            warn!("failed to write log file {}: {e}", inner.path.display());
+        }
+    }
+
+    /// The sequence number of the last item of the channel in the file, or
+    /// in its rotated files, for the channel to follow on from it. 0 if the
+    /// files have no item of the channel.
+    pub fn last_sequence(&self, channel: &str) -> u64 {
+        let path = self.0.lock().expect("log file").path.clone();
+        last_sequence(&path, channel).unwrap_or_else(|e| {
+            warn!("failed to read log file {}: {e}", path.display());
+            0
+        })
+    }
+}
+
+impl Inner {
+    fn write(&mut self, item: &LogItem) -> io::Result<()> {
+        let age = self.started_at.elapsed().unwrap_or_default();
+        if self.size >= self.max_size || age >= self.max_age {
+            self.rotate()?;
+        }
+
+        let mut line = serde_json::to_vec(item)?;
+        line.push(b'\n');
+        self.file.write_all(&line)?;
+        self.size += line.len() as u64;
+
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        remove_if_exists(&rotated_path(&self.path, self.retention))?;
+        for index in (1..self.retention).rev() {
+            rename_if_exists(
+                &rotated_path(&self.path, index),
+                &rotated_path(&self.path, index + 1),
+            )?;
+        }
+        if self.retention > 0 {
+            fs::rename(&self.path, rotated_path(&self.path, 1))?;
+        } else {
+            fs::remove_file(&self.path)?;
+        }
+
+        (self.file, self.size, self.started_at) = open(&self.path)?;
+        Ok(())
+    }
+}
+
+/// Reads the items of a log file and of its rotated files, oldest first.
+/// Lines that can't be read (e.g., cut short by a crash) are skipped.
+pub fn read_log_file(path: &Path) -> io::Result<Vec<LogItem>> {
+    let mut paths = vec![path.to_path_buf()];
+    for index in 1.. {
+        let rotated = rotated_path(path, index);
+        if !rotated.exists() {
+            break;
+        }
+        paths.push(rotated);
+    }
+
+    let mut items = vec![];
+    for path in paths.iter().rev() {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for line in BufReader::new(file).lines() {
+            if let Ok(item) = serde_json::from_str(&line?) {
+                items.push(item);
+            }
+        }
+    }
+
+    Ok(items)
+}
+
+/// Reads the files newest first, until one has an item of the channel.
+fn last_sequence(path: &Path, channel: &str) -> io::Result<u64> {
+    for index in 0.. {
+        let path = match index {
+            0 => path.to_path_buf(),
+            _ => rotated_path(path, index),
+        };
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => break,
+            Err(e) => return Err(e),
+        };
+
+        let mut last = None;
+        for line in BufReader::new(file).lines() {
+            if let Ok(item) = serde_json::from_str::<LogItem>(&line?)
+                && item.channel == channel
+            {
+                last = Some(item.sequence);
+            }
+        }
+        if let Some(sequence) = last {
+            return Ok(sequence);
+        }
+    }
+
+    Ok(0)
+}
+
+fn open(path: &Path) -> io::Result<(File, u64, SystemTime)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let metadata = file.metadata()?;
+    let started_at = metadata
+        .created()
+        .or_else(|_| metadata.modified())
+        .unwrap_or_else(|_| SystemTime::now());
+
+    Ok((file, metadata.len(), started_at))
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{index}"));
+    PathBuf::from(rotated)
+}
+
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn rename_if_exists(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::rename(from, to) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(line: &str) -> LogItem {
+        LogItem {
+            channel: "test::stdout".into(),
+            line: line.into(),
+            timestamp: 0,
+            timestamp_nanos: 0,
+            sequence: 0,
+        }
+    }
+
+    #[test]
+    fn log_file_should_rotate_and_keep_retention() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = LogFileConfig {
+            // Rotates before each write, once the file isn't empty
+            max_size: 1,
+            retention: 2,
+            ..LogFileConfig::new(dir.path().to_path_buf())
+        };
+        let path = config.path_for("test");
+
+        let file = LogFile::open(path.clone(), &config).expect("open");
+        for line in ["1", "2", "3", "4"] {
+            file.write(&item(line));
+        }
+
+        assert!(!rotated_path(&path, 3).exists());
+        let lines: Vec<String> = read_log_file(&path)
+            .expect("read")
+            .into_iter()
+            .map(|x| x.line)
+            .collect();
+        assert_eq!(lines, ["2", "3", "4"]);
+
+        // Reopening appends to the existing file
+        drop(file);
+        let file = LogFile::open(
+            path.clone(),
+            &LogFileConfig::new(dir.path().to_path_buf()),
+        )
+        .expect("reopen");
+        file.write(&item("5"));
+        let lines: Vec<String> = read_log_file(&path)
+            .expect("read")
+            .into_iter()
+            .map(|x| x.line)
+            .collect();
+        assert_eq!(lines, ["2", "3", "4", "5"]);
+    }
+}
@@ -22,14 +22,19 @@ use std::time::SystemTime;
 /// LogChannel provides channels between Log producers and log consumers
 pub mod log_channel;
 
-/// Implements Log trait. Used to add grpc API to log targets for rust internal logging
+/// Durable, rotated log files, written by log channels
+pub mod log_file;
+
+/// Implements a tracing Layer. Used to add grpc API to log targets for rust
+/// internal logging
 pub mod stream_logger;
 
-/// Get UNIX timestamp in seconds for logging
-pub fn get_timestamp_sec() -> i64 {
+/// Get UNIX timestamp in seconds, and the nanoseconds within the second, for
+/// logging
+pub fn get_timestamp() -> (i64, i32) {
     let unix_ts = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .expect("System Clock went backwards");
 
-    unix_ts.as_secs() as i64
+    (unix_ts.as_secs() as i64, unix_ts.subsec_nanos() as i32)
 }
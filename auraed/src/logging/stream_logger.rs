@@ -13,44 +13,89 @@
  * SPDX-License-Identifier: Apache-2.0                                        *
 \* -------------------------------------------------------------------------- */
 
-use log::Log;
-use proto::observe::LogItem;
-use tokio::sync::broadcast::Sender;
+use super::log_channel::LogChannel;
+use std::fmt::{Debug, Write};
+use std::sync::OnceLock;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
 
-/// Sends log messages generated in rust code to the logging channel
+/// The channel the [StreamLogger] sends to, once auraed created it
+static CHANNEL: OnceLock<LogChannel> = OnceLock::new();
+
+/// The targets whose events are not sent to the channel, as they are traced
+/// while writing its items, and a failing write would log in a loop
+const IGNORED_TARGETS: [&str; 2] =
+    ["auraed::logging::log_channel", "auraed::logging::log_file"];
+
+/// Sends the events traced in rust code to the logging channel.
 /// The logging channel is consumed by the observe API
-#[derive(Debug)]
-pub struct StreamLogger {
-    /// Channel used to send log messages to grpc API
-    pub producer: Sender<LogItem>,
-}
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreamLogger;
 
 impl StreamLogger {
-    #[allow(unused)]
-    /// Constructor requires channel between api and logger
-    pub fn new(producer: Sender<LogItem>) -> StreamLogger {
-        StreamLogger { producer }
+    /// Sets the channel the events are sent to. The events traced before are
+    /// not sent, and only the first channel set is used.
+    pub fn set_channel(channel: LogChannel) {
+        let _ = CHANNEL.set(channel);
     }
 }
 
-impl Log for StreamLogger {
-    fn enabled(&self, _metadata: &log::Metadata) -> bool {
-        true
+impl<S: Subscriber> Layer<S> for StreamLogger {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let Some(channel) = CHANNEL.get() else {
+            return;
+        };
+
+        let metadata = event.metadata();
+        if IGNORED_TARGETS.iter().any(|x| metadata.target().starts_with(x)) {
+            return;
+        }
+
+        let mut line =
+            format!("{}:{} -- ", metadata.level(), metadata.target());
+        event.record(&mut LineVisitor(&mut line));
+        channel.send(line);
     }
+}
 
-    fn log(&self, record: &log::Record) {
-        // send returns an Err if there are no receivers. We ignore that.
-        let _ = self.producer.send(LogItem {
-            channel: "rust-logs".to_string(),
-            line: format!(
-                "{}:{} -- {}",
-                record.level(),
-                record.target(),
-                record.args()
-            ),
-            timestamp: 0,
-        });
+/// Appends the message, then the other fields, of an event to a line
+struct LineVisitor<'a>(&'a mut String);
+
+impl Visit for LineVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        let _ = match field.name() {
+            "message" => write!(self.0, "{value:?}"),
+            name => write!(self.0, " {name}={value:?}"),
+        };
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logging::log_channel::LogReplay;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[tokio::test]
+    async fn stream_logger_should_send_events_to_the_channel() {
+        let channel = LogChannel::new("auraed".into());
+        StreamLogger::set_channel(channel.clone());
 
-    fn flush(&self) {}
+        let subscriber = tracing_subscriber::registry().with(StreamLogger);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(pid = 42, "started");
+            tracing::warn!(target: "auraed::logging::log_file", "failed");
+        });
+
+        let (items, _) = channel.subscribe_from(LogReplay::FromBeginning).await;
+        let lines: Vec<_> = items.into_iter().map(|x| x.line).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "INFO:auraed::logging::stream_logger::tests -- started pid=42"
+            ]
+        );
+    }
 }
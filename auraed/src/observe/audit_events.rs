@@ -0,0 +1,272 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use nix::errno::Errno;
+use nix::libc;
+use nix::sys::socket::{
+    AddressFamily, MsgFlags, NetlinkAddr, SockFlag, SockProtocol, SockType,
+    bind, recv, socket,
+};
+use proto::observe::AuditEvent;
+use std::collections::HashMap;
+use std::os::fd::AsRawFd;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tracing::warn;
+
+/// The multicast group of the audit netlink socket, which receives a copy of
+/// the audit records (AUDIT_NLGRP_READLOG).
+/// Docs: https://github.com/torvalds/linux/blob/master/include/uapi/linux/audit.h
+const AUDIT_NLGRP_READLOG: u32 = 1;
+
+/// The type of the audit records of seccomp (AUDIT_SECCOMP).
+const AUDIT_SECCOMP: u16 = 1326;
+
+/// The size of the header of a netlink message.
+const NLMSG_HDRLEN: usize = 16;
+
+/// Larger than the longest audit record (MAX_AUDIT_MESSAGE_LENGTH).
+const BUFFER_SIZE: usize = 16 * 1024;
+
+/// Reads the seccomp records of the kernel (e.g., for syscalls matched by a
+/// rule with the SCMP_ACT_LOG action), and broadcasts them as [AuditEvent].
+/// Reading the records requires CAP_AUDIT_READ in the initial user and
+/// network namespaces, so they are only available to the host auraed.
+#[derive(Debug, Clone)]
+pub(crate) struct AuditEvents {
+    tx: Sender<AuditEvent>,
+    /// Whether the records are being read. It is reset if reading fails
+    /// (e.g., the socket could not be bound), to retry on the next
+    /// subscription.
+    reading: Arc<AtomicBool>,
+}
+
+impl AuditEvents {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(64);
+        Self { tx, reading: Default::default() }
+    }
+
+    /// Subscribes to the events, which are read from the kernel from the
+    /// first subscription on. Reading is started again by the next
+    /// subscription if it failed.
+    pub fn subscribe(&self) -> Receiver<AuditEvent> {
+        let rx = self.tx.subscribe();
+
+        if self
+            .reading
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return rx;
+        }
+
+        let tx = self.tx.clone();
+        let reading = self.reading.clone();
+        let spawned = std::thread::Builder::new()
+            .name(String::from("audit-events"))
+            .spawn(move || {
+                if let Err(e) = read_events(&tx) {
+                    warn!("failed to read audit events: {e}");
+                }
+                reading.store(false, Ordering::Release);
+            });
+        if let Err(e) = spawned {
+            warn!("failed to read audit events: {e}");
+            self.reading.store(false, Ordering::Release);
+        }
+
+        rx
+    }
+}
+
+fn read_events(tx: &Sender<AuditEvent>) -> nix::Result<()> {
+    let fd = socket(
+        AddressFamily::Netlink,
+        SockType::Raw,
+        SockFlag::SOCK_CLOEXEC,
+        SockProtocol::NetlinkAudit,
+    )?;
+    bind(fd.as_raw_fd(), &NetlinkAddr::new(0, AUDIT_NLGRP_READLOG))?;
+
+    let mut buf = vec![0u8; BUFFER_SIZE];
+    loop {
+        let len = match recv(fd.as_raw_fd(), &mut buf, MsgFlags::empty()) {
+            Ok(len) => len,
+            Err(Errno::EINTR) => continue,
+            Err(Errno::ENOBUFS) => {
+                warn!(
+                    "audit events were dropped, as they were not read in time"
+                );
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+
+        for (kind, payload) in netlink_messages(&buf[..len]) {
+            if kind != AUDIT_SECCOMP {
+                continue;
+            }
+
+            let record = String::from_utf8_lossy(payload);
+            let Some(mut event) =
+                parse_seccomp_record(record.trim_end_matches('\0'))
+            else {
+                continue;
+            };
+            event.cell_name = cell_name(event.pid).unwrap_or_default();
+
+            // there may be no subscribers, which is fine
+            let _ = tx.send(event);
+        }
+    }
+}
+
+/// Returns the type and payload of the netlink messages in the buffer.
+fn netlink_messages(mut buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    std::iter::from_fn(move || {
+        if buf.len() < NLMSG_HDRLEN {
+            return None;
+        }
+
+        let len = u32::from_ne_bytes(buf[0..4].try_into().ok()?) as usize;
+        let kind = u16::from_ne_bytes(buf[4..6].try_into().ok()?);
+        let len = len.clamp(NLMSG_HDRLEN, buf.len());
+        let payload = &buf[NLMSG_HDRLEN..len];
+
+        // messages are aligned to 4 bytes
+        buf = &buf[len.next_multiple_of(4).min(buf.len())..];
+        Some((kind, payload))
+    })
+}
+
+/// Parses a seccomp audit record, such as:
+/// audit(1700000000.123:42): auid=4294967295 uid=0 gid=0 ses=4294967295
+/// pid=1234 comm="sleep" exe="/usr/bin/sleep" sig=0 arch=c000003e
+/// syscall=35 compat=0 ip=0x7f0e5c8e5b3a code=0x7ffc0000
+fn parse_seccomp_record(record: &str) -> Option<AuditEvent> {
+    let (_, fields) = record.split_once("): ")?;
+    let fields: HashMap<&str, &str> =
+        fields.split_whitespace().filter_map(|x| x.split_once('=')).collect();
+
+    let code = fields.get("code")?;
+    let code = u32::from_str_radix(code.trim_start_matches("0x"), 16).ok()?;
+
+    Some(AuditEvent {
+        cell_name: String::new(),
+        pid: fields.get("pid")?.parse().ok()?,
+        comm: fields.get("comm").map(|x| decode(x)).unwrap_or_default(),
+        exe: fields.get("exe").map(|x| decode(x)).unwrap_or_default(),
+        syscall: fields.get("syscall")?.parse().ok()?,
+        arch: fields.get("arch").map(|x| x.to_string()).unwrap_or_default(),
+        action: action(code).to_string(),
+    })
+}
+
+/// Decodes a value the process controls (e.g., comm), which is quoted, or
+/// hex encoded if it has spaces or quotes.
+fn decode(value: &str) -> String {
+    if let Some(value) =
+        value.strip_prefix('"').and_then(|x| x.strip_suffix('"'))
+    {
+        return value.to_string();
+    }
+
+    let bytes: Option<Vec<u8>> = (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value.get(i..i + 2).and_then(|x| u8::from_str_radix(x, 16).ok())
+        })
+        .collect();
+
+    match bytes {
+        Some(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+        // e.g., "(null)"
+        None => value.to_string(),
+    }
+}
+
+/// Returns the name of the action of the seccomp return value, as in the
+/// OCI runtime spec.
+fn action(code: u32) -> &'static str {
+    match code & libc::SECCOMP_RET_ACTION_FULL {
+        libc::SECCOMP_RET_KILL_PROCESS => "SCMP_ACT_KILL_PROCESS",
+        libc::SECCOMP_RET_KILL_THREAD => "SCMP_ACT_KILL_THREAD",
+        libc::SECCOMP_RET_TRAP => "SCMP_ACT_TRAP",
+        libc::SECCOMP_RET_ERRNO => "SCMP_ACT_ERRNO",
+        libc::SECCOMP_RET_USER_NOTIF => "SCMP_ACT_NOTIFY",
+        libc::SECCOMP_RET_TRACE => "SCMP_ACT_TRACE",
+        libc::SECCOMP_RET_LOG => "SCMP_ACT_LOG",
+        libc::SECCOMP_RET_ALLOW => "SCMP_ACT_ALLOW",
+        _ => "unknown",
+    }
+}
+
+/// Returns the name of the cell of the process, from the path of its cgroup
+/// (e.g., "0::/parent/child/_" for the cell "parent/child").
+fn cell_name(pid: i32) -> Option<String> {
+    let cgroup = std::fs::read_to_string(format!("/proc/{pid}/cgroup")).ok()?;
+    cgroup
+        .lines()
+        .find_map(|line| line.strip_prefix("0::/"))
+        .and_then(|path| path.strip_suffix("/_"))
+        .map(|x| x.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_seccomp_record_must_parse_logged_syscall() {
+        let event = parse_seccomp_record(
+            "audit(1700000000.123:42): auid=4294967295 uid=0 gid=0 \
+             ses=4294967295 subj=unconfined pid=1234 comm=\"sleep\" \
+             exe=2F746D702F6D7920736C656570 sig=0 arch=c000003e \
+             syscall=35 compat=0 ip=0x7f0e5c8e5b3a code=0x7ffc0000",
+        )
+        .expect("event");
+
+        assert_eq!(
+            event,
+            AuditEvent {
+                cell_name: String::new(),
+                pid: 1234,
+                comm: String::from("sleep"),
+                exe: String::from("/tmp/my sleep"),
+                syscall: 35,
+                arch: String::from("c000003e"),
+                action: String::from("SCMP_ACT_LOG"),
+            }
+        );
+    }
+
+    #[test]
+    fn netlink_messages_must_split_aligned_messages() {
+        let mut buf = vec![];
+        for (kind, payload) in [(1326u16, &b"abc"[..]), (1300, &b"defg"[..])] {
+            let len = (NLMSG_HDRLEN + payload.len()) as u32;
+            buf.extend_from_slice(&len.to_ne_bytes());
+            buf.extend_from_slice(&kind.to_ne_bytes());
+            buf.extend_from_slice(&[0; 10]);
+            buf.extend_from_slice(payload);
+            buf.resize(buf.len().next_multiple_of(4), 0);
+        }
+
+        let messages: Vec<_> = netlink_messages(&buf).collect();
+        assert_eq!(messages, vec![(1326, &b"abc"[..]), (1300, &b"defg"[..])]);
+    }
+}
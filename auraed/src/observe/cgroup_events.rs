@@ -0,0 +1,229 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+
+use proto::observe::{CellEvent, CellEventCounter};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+
+/// The cgroup interface files with event counters that are watched, and the
+/// counters of the file that are reported (all counters if [None]).
+/// Docs:
+///  - https://docs.kernel.org/admin-guide/cgroup-v2.html#pid-interface-files
+///  - https://docs.kernel.org/admin-guide/cgroup-v2.html#memory-interface-files
+const EVENTS_FILES: &[(&str, Option<&[&str]>)] = &[
+    ("pids.events", None),
+    ("memory.events", Some(&["high", "max", "oom", "oom_kill"])),
+];
+
+/// Interval in which the event files are read.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Watches the event counters (e.g., pids.events, memory.events) of the cgroups of cells,
+/// and broadcasts a [CellEvent] when any of the counters increase.
+#[derive(Debug, Clone)]
+pub(crate) struct CgroupEvents {
+    root: PathBuf,
+    tx: Sender<CellEvent>,
+}
+
+impl CgroupEvents {
+    pub fn new(root: PathBuf) -> Self {
+        let (tx, _) = broadcast::channel(64);
+        Self { root, tx }
+    }
+
+    pub fn subscribe(&self) -> Receiver<CellEvent> {
+        self.tx.subscribe()
+    }
+
+    /// Starts watching the cgroup of the cell.
+    /// The watch ends when the cgroup is removed (i.e., the cell is freed).
+    pub fn watch(&self, cell_name: String) {
+        let path = self.root.join(&cell_name);
+        let tx = self.tx.clone();
+
+        let _ignored = tokio::spawn(async move {
+            let mut counters = HashMap::new();
+            for (file, names) in EVENTS_FILES {
+                let _ = counters
+                    .insert(*file, read_counters(&path, file, *names).await);
+            }
+
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                let _instant = interval.tick().await;
+
+                if !path.exists() {
+                    // cgroup is gone
+                    break;
+                }
+
+                for (file, names) in EVENTS_FILES {
+                    let current = read_counters(&path, file, *names).await;
+                    let previous = counters
+                        .insert(*file, current.clone())
+                        .unwrap_or_default();
+
+                    let deltas = get_deltas(&previous, &current);
+                    if deltas.is_empty() {
+                        continue;
+                    }
+
+                    // there may be no subscribers, which is fine
+                    let _ = tx.send(CellEvent {
+                        cell_name: cell_name.clone(),
+                        file: file.to_string(),
+                        counters: deltas,
+                    });
+                }
+            }
+        });
+    }
+}
+
+async fn read_counters(
+    path: &Path,
+    file: &str,
+    names: Option<&[&str]>,
+) -> HashMap<String, u64> {
+    // The file does not exist if the controller is not enabled for the cgroup
+    let mut counters = tokio::fs::read_to_string(path.join(file))
+        .await
+        .map(|content| parse_counters(&content))
+        .unwrap_or_default();
+
+    if let Some(names) = names {
+        counters.retain(|name, _| names.contains(&name.as_str()));
+    }
+
+    counters
+}
+
+/// Parses the flat keyed format ("<key> <value>" per line) of cgroup files.
+fn parse_counters(content: &str) -> HashMap<String, u64> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key.to_string(), value.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+fn get_deltas(
+    previous: &HashMap<String, u64>,
+    current: &HashMap<String, u64>,
+) -> Vec<CellEventCounter> {
+    let mut deltas: Vec<_> = current
+        .iter()
+        .filter_map(|(name, value)| {
+            let previous = previous.get(name).copied().unwrap_or_default();
+            (*value > previous).then(|| CellEventCounter {
+                name: name.clone(),
+                delta: value - previous,
+                value: *value,
+            })
+        })
+        .collect();
+
+    deltas.sort_by(|a, b| a.name.cmp(&b.name));
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_counters_must_parse_flat_keyed_file() {
+        let counters = parse_counters("max 3\nmax.imposed 0\n");
+
+        assert_eq!(counters.len(), 2);
+        assert_eq!(counters.get("max"), Some(&3));
+        assert_eq!(counters.get("max.imposed"), Some(&0));
+    }
+
+    #[test]
+    fn get_deltas_must_only_return_increased_counters() {
+        let previous = parse_counters("max 1\nmax.imposed 2\n");
+        let current = parse_counters("max 4\nmax.imposed 2\n");
+
+        let deltas = get_deltas(&previous, &current);
+
+        assert_eq!(
+            deltas,
+            vec![CellEventCounter { name: "max".into(), delta: 3, value: 4 }]
+        );
+    }
+
+    #[tokio::test]
+    async fn watch_must_broadcast_when_counter_increases() {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let cell_name = "ae-test".to_string();
+        let path = root.join(&cell_name);
+        std::fs::create_dir_all(&path).expect("create cgroup dir");
+        std::fs::write(path.join("pids.events"), "max 0\n")
+            .expect("write pids.events");
+
+        let events = CgroupEvents::new(root.clone());
+        let mut rx = events.subscribe();
+        events.watch(cell_name.clone());
+
+        // give the watch time to read the initial counters
+        tokio::time::sleep(POLL_INTERVAL / 2).await;
+        std::fs::write(path.join("pids.events"), "max 2\n")
+            .expect("write pids.events");
+
+        let event = tokio::time::timeout(POLL_INTERVAL * 3, rx.recv())
+            .await
+            .expect("event before timeout")
+            .expect("event");
+
+        assert_eq!(event.cell_name, cell_name);
+        assert_eq!(event.file, "pids.events");
+        assert_eq!(
+            event.counters,
+            vec![CellEventCounter { name: "max".into(), delta: 2, value: 2 }]
+        );
+
+        std::fs::remove_dir_all(root).expect("remove cgroup dir");
+    }
+
+    #[tokio::test]
+    async fn read_counters_must_only_return_watched_memory_counters() {
+        let path = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&path).expect("create cgroup dir");
+        std::fs::write(
+            path.join("memory.events"),
+            "low 5\nhigh 4\nmax 3\noom 2\noom_kill 1\noom_group_kill 0\n",
+        )
+        .expect("write memory.events");
+
+        let (file, names) = EVENTS_FILES
+            .iter()
+            .find(|(file, _)| *file == "memory.events")
+            .expect("memory.events is watched");
+        let counters = read_counters(&path, file, *names).await;
+
+        let mut names = counters.keys().map(String::as_str).collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(names, vec!["high", "max", "oom", "oom_kill"]);
+        assert_eq!(counters.get("oom_kill"), Some(&1));
+
+        std::fs::remove_dir_all(path).expect("remove cgroup dir");
+    }
+}
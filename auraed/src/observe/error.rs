@@ -28,6 +28,14 @@ pub enum ObserveServiceError {
     ChannelNotRegistered { pid: i32, channel_type: LogChannelType },
     #[error("{channel_type} is not a valid LogChannelType")]
     InvalidLogChannelType { channel_type: i32 },
+    #[error("Only one of the options to replay log items can be set")]
+    ConflictingLogReplay,
+    #[error("Failed to find a log file for executable {executable_name}")]
+    LogFileNotFound { executable_name: String },
+    #[error("Failed to read the log file of executable {executable_name}")]
+    FailedToReadLogFile { executable_name: String, source: std::io::Error },
+    #[error("Log files are not available")]
+    LogFilesUnavailable,
 }
 
 impl From<ObserveServiceError> for Status {
@@ -35,14 +43,20 @@ impl From<ObserveServiceError> for Status {
         let msg = err.to_string();
         error!("{msg}");
         match err {
-            ObserveServiceError::ChannelAlreadyRegistered { .. } => {
+            ObserveServiceError::ChannelAlreadyRegistered { .. }
+            | ObserveServiceError::FailedToReadLogFile { .. } => {
                 Status::internal(msg)
             }
+            ObserveServiceError::LogFilesUnavailable => {
+                Status::unavailable(msg)
+            }
             ObserveServiceError::NoChannelsForPid { .. }
-            | ObserveServiceError::ChannelNotRegistered { .. } => {
+            | ObserveServiceError::ChannelNotRegistered { .. }
+            | ObserveServiceError::LogFileNotFound { .. } => {
                 Status::not_found(msg)
             }
-            ObserveServiceError::InvalidLogChannelType { .. } => {
+            ObserveServiceError::InvalidLogChannelType { .. }
+            | ObserveServiceError::ConflictingLogReplay => {
                 Status::invalid_argument(msg)
             }
         }
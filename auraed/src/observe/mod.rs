@@ -16,7 +16,9 @@
 pub(crate) use error::ObserveServiceError;
 pub(crate) use observe_service::ObserveService;
 
+mod audit_events;
 mod cgroup_cache;
+mod cgroup_events;
 mod error;
 mod observe_service;
 mod observed_event_stream;
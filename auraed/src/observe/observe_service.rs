@@ -16,33 +16,48 @@
 // @todo @krisnova remove this once logging is further along
 #![allow(dead_code)]
 
+use super::audit_events::AuditEvents;
 use super::cgroup_cache;
+use super::cgroup_events::CgroupEvents;
 use super::error::ObserveServiceError;
 use super::observed_event_stream::ObservedEventStream;
 use super::proc_cache::{ProcCache, ProcfsProcessInfo};
+use crate::AURAED_RUNTIME;
+use crate::cells::{CellName, ExecutableName};
 use crate::ebpf::tracepoint::PerfEventBroadcast;
-use crate::logging::log_channel::LogChannel;
+use crate::logging::log_channel::{LogChannel, LogReplay, wait_for_writes};
+use crate::logging::log_file::{log_file_path, read_log_file};
 use aurae_ebpf_shared::{ForkedProcess, ProcessExit, Signal};
 use cgroup_cache::CgroupCache;
 use proto::observe::{
-    GetAuraeDaemonLogStreamRequest, GetAuraeDaemonLogStreamResponse,
-    GetPosixSignalsStreamRequest, GetPosixSignalsStreamResponse,
-    GetSubProcessStreamRequest, GetSubProcessStreamResponse, LogChannelType,
-    LogItem, Signal as PosixSignal, WorkloadType, observe_service_server,
+    AuditEvent, CellEvent, GetAuditEventsStreamRequest,
+    GetAuditEventsStreamResponse, GetAuraeDaemonLogStreamRequest,
+    GetAuraeDaemonLogStreamResponse, GetCellEventsStreamRequest,
+    GetCellEventsStreamResponse, GetExecutableLogStreamRequest,
+    GetExecutableLogStreamResponse, GetPosixSignalsStreamRequest,
+    GetPosixSignalsStreamResponse, GetSubProcessStreamRequest,
+    GetSubProcessStreamResponse, LogChannelType, LogGap, LogItem,
+    Signal as PosixSignal, WorkloadType, observe_service_server,
 };
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tokio::sync::{Mutex, broadcast::Receiver};
+use tokio::sync::{
+    Mutex,
+    broadcast::{Receiver, error::RecvError},
+};
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 use tracing::{info, instrument};
+use validation::ValidatedField;
 
 #[derive(Debug, Clone)]
 pub struct ObserveService {
     aurae_logger: LogChannel,
     cgroup_cache: CgroupCache,
+    cgroup_events: CgroupEvents,
+    audit_events: AuditEvents,
     proc_cache: Option<ProcCache>,
     posix_signals: Option<PerfEventBroadcast<Signal>>,
     sub_process_consumer_list:
@@ -70,6 +85,8 @@ impl ObserveService {
         Self {
             aurae_logger,
             cgroup_cache: CgroupCache::new("/sys/fs/cgroup".into()),
+            cgroup_events: CgroupEvents::new("/sys/fs/cgroup".into()),
+            audit_events: AuditEvents::new(),
             proc_cache,
             posix_signals: perf_events.2,
             sub_process_consumer_list: Arc::new(Mutex::new(HashMap::new())),
@@ -125,10 +142,90 @@ impl ObserveService {
         Ok(())
     }
 
+    /// Starts watching the cgroup events of an allocated cell, which can be
+    /// streamed with GetCellEventsStream.
+    pub fn watch_cell_events(&self, cell_name: String) {
+        self.cgroup_events.watch(cell_name)
+    }
+
     fn get_aurae_daemon_log_stream(&self) -> Receiver<LogItem> {
         self.aurae_logger.subscribe()
     }
 
+    async fn get_sub_process_log_stream(
+        &self,
+        pid: i32,
+        channel_type: LogChannelType,
+        replay: LogReplay,
+    ) -> Result<(Vec<LogItem>, Receiver<LogItem>), ObserveServiceError> {
+        let channel = self
+            .sub_process_consumer_list
+            .lock()
+            .await
+            .get(&pid)
+            .ok_or(ObserveServiceError::NoChannelsForPid { pid })?
+            .get(&channel_type)
+            .ok_or(ObserveServiceError::ChannelNotRegistered {
+                pid,
+                channel_type,
+            })?
+            .clone();
+
+        Ok(channel.subscribe_from(replay).await)
+    }
+
+    /// Reads the lines of the log file of an executable, oldest first.
+    async fn read_executable_log_file(
+        &self,
+        cell_name: Option<CellName>,
+        executable_name: ExecutableName,
+        channel_type: LogChannelType,
+    ) -> Result<Vec<LogItem>, ObserveServiceError> {
+        let runtime = AURAED_RUNTIME
+            .get()
+            .ok_or(ObserveServiceError::LogFilesUnavailable)?;
+
+        let dir = match cell_name {
+            Some(cell_name) => runtime.cell_logs_dir(&cell_name),
+            None => runtime.logs_dir.clone(),
+        };
+        let executable_name = executable_name.to_string();
+        let path = log_file_path(&dir, &executable_name);
+        if !path.exists() {
+            return Err(ObserveServiceError::LogFileNotFound {
+                executable_name,
+            });
+        }
+
+        let items = tokio::task::spawn_blocking(move || {
+            // The last lines sent may not have been written yet
+            wait_for_writes();
+            read_log_file(&path)
+        })
+        .await
+        .expect("read log file")
+        .map_err(|e| ObserveServiceError::FailedToReadLogFile {
+            executable_name: executable_name.clone(),
+            source: e,
+        })?;
+
+        // stdout and stderr share the log file
+        let channel = match channel_type {
+            LogChannelType::Unspecified => None,
+            LogChannelType::Stdout => {
+                Some(format!("{executable_name}::stdout"))
+            }
+            LogChannelType::Stderr => {
+                Some(format!("{executable_name}::stderr"))
+            }
+        };
+
+        Ok(items
+            .into_iter()
+            .filter(|x| channel.as_ref().is_none_or(|c| c.eq(&x.channel)))
+            .collect())
+    }
+
     #[instrument(skip(self))]
     fn get_posix_signals_stream(
         &self,
@@ -144,6 +241,147 @@ impl ObserveService {
 
         ReceiverStream::new(events)
     }
+
+    #[instrument(skip(self))]
+    fn get_cell_events_stream(
+        &self,
+        filter: Option<(WorkloadType, String)>,
+    ) -> ReceiverStream<Result<GetCellEventsStreamResponse, Status>> {
+        let (tx, rx) =
+            mpsc::channel::<Result<GetCellEventsStreamResponse, Status>>(4);
+
+        let cell_name = match filter {
+            Some((WorkloadType::Cell, id)) => Some(id),
+            _ => None,
+        };
+        let mut events = self.cgroup_events.subscribe();
+
+        let _ignored = tokio::spawn(async move {
+            loop {
+                let event: CellEvent = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+
+                let accept =
+                    cell_name.as_ref().is_none_or(|x| x.eq(&event.cell_name));
+                if !accept {
+                    continue;
+                }
+
+                let resp = GetCellEventsStreamResponse { event: Some(event) };
+                if tx.send(Ok(resp)).await.is_err() {
+                    // receiver is gone
+                    break;
+                }
+            }
+        });
+
+        ReceiverStream::new(rx)
+    }
+
+    #[instrument(skip(self))]
+    fn get_audit_events_stream(
+        &self,
+        filter: Option<(WorkloadType, String)>,
+    ) -> ReceiverStream<Result<GetAuditEventsStreamResponse, Status>> {
+        let (tx, rx) =
+            mpsc::channel::<Result<GetAuditEventsStreamResponse, Status>>(4);
+
+        let cell_name = match filter {
+            Some((WorkloadType::Cell, id)) => Some(id),
+            _ => None,
+        };
+        let mut events = self.audit_events.subscribe();
+
+        let _ignored = tokio::spawn(async move {
+            loop {
+                let event: AuditEvent = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+
+                let accept =
+                    cell_name.as_ref().is_none_or(|x| x.eq(&event.cell_name));
+                if !accept {
+                    continue;
+                }
+
+                let resp = GetAuditEventsStreamResponse { event: Some(event) };
+                if tx.send(Ok(resp)).await.is_err() {
+                    // receiver is gone
+                    break;
+                }
+            }
+        });
+
+        ReceiverStream::new(rx)
+    }
+}
+
+/// The response of a stream of log items.
+trait LogStreamResponse: Send + 'static {
+    fn item(item: LogItem) -> Self;
+    fn gap(gap: LogGap) -> Self;
+}
+
+impl LogStreamResponse for GetAuraeDaemonLogStreamResponse {
+    fn item(item: LogItem) -> Self {
+        Self { item: Some(item), gap: None }
+    }
+
+    fn gap(gap: LogGap) -> Self {
+        Self { item: None, gap: Some(gap) }
+    }
+}
+
+impl LogStreamResponse for GetSubProcessStreamResponse {
+    fn item(item: LogItem) -> Self {
+        Self { item: Some(item), gap: None }
+    }
+
+    fn gap(gap: LogGap) -> Self {
+        Self { item: None, gap: Some(gap) }
+    }
+}
+
+/// Streams the replayed log items, followed by the ones received until the
+/// log channel is closed.
+fn stream_log_items<T: LogStreamResponse>(
+    replayed: Vec<LogItem>,
+    mut log_consumer: Receiver<LogItem>,
+) -> ReceiverStream<Result<T, Status>> {
+    let (tx, rx) = mpsc::channel::<Result<T, Status>>(4);
+
+    // TODO: error handling. Warning: recursively logging if error message is also send to this grpc api endpoint
+    //  .. thus disabled logging here.
+    let _ignored = tokio::spawn(async move {
+        for log_item in replayed {
+            if tx.send(Ok(T::item(log_item))).await.is_err() {
+                // receiver is gone
+                return;
+            }
+        }
+
+        loop {
+            let resp = match log_consumer.recv().await {
+                Ok(log_item) => T::item(log_item),
+                // The missed items are gone, so let the receiver know
+                // rather than ending the stream
+                Err(RecvError::Lagged(missed)) => T::gap(LogGap { missed }),
+                // the producer is closed (no more logs)
+                Err(RecvError::Closed) => break,
+            };
+            if tx.send(Ok(resp)).await.is_err() {
+                // receiver is gone
+                break;
+            }
+        }
+    });
+
+    ReceiverStream::new(rx)
 }
 
 fn map_get_posix_signals_stream_response(
@@ -179,27 +417,8 @@ impl observe_service_server::ObserveService for ObserveService {
         &self,
         _request: Request<GetAuraeDaemonLogStreamRequest>,
     ) -> Result<Response<Self::GetAuraeDaemonLogStreamStream>, Status> {
-        let (tx, rx) =
-            mpsc::channel::<Result<GetAuraeDaemonLogStreamResponse, Status>>(4);
-        let mut log_consumer = self.get_aurae_daemon_log_stream();
-
-        // TODO: error handling. Warning: recursively logging if error message is also send to this grpc api endpoint
-        //  .. thus disabled logging here.
-        let _ignored = tokio::spawn(async move {
-            // Log consumer will error if:
-            //  the producer is closed (no more logs)
-            //  the receiver is lagging
-            while let Ok(log_item) = log_consumer.recv().await {
-                let resp =
-                    GetAuraeDaemonLogStreamResponse { item: Some(log_item) };
-                if tx.send(Ok(resp)).await.is_err() {
-                    // receiver is gone
-                    break;
-                }
-            }
-        });
-
-        Ok(Response::new(ReceiverStream::new(rx)))
+        let log_consumer = self.get_aurae_daemon_log_stream();
+        Ok(Response::new(stream_log_items(vec![], log_consumer)))
     }
 
     type GetSubProcessStreamStream =
@@ -209,40 +428,84 @@ impl observe_service_server::ObserveService for ObserveService {
         &self,
         request: Request<GetSubProcessStreamRequest>,
     ) -> Result<Response<Self::GetSubProcessStreamStream>, Status> {
-        let GetSubProcessStreamRequest { process_id: pid, channel_type } =
-            request.into_inner();
+        let GetSubProcessStreamRequest {
+            process_id: pid,
+            channel_type,
+            from_beginning,
+            tail,
+            since_timestamp,
+            since_timestamp_nanos,
+        } = request.into_inner();
         let channel = LogChannelType::try_from(channel_type).map_err(|_| {
             ObserveServiceError::InvalidLogChannelType { channel_type }
         })?;
+        let replay = match (from_beginning, tail, since_timestamp) {
+            (false, None, None) => LogReplay::Live,
+            (true, None, None) => LogReplay::FromBeginning,
+            (false, Some(tail), None) => LogReplay::Tail(tail as usize),
+            (false, None, Some(since)) => LogReplay::Since(
+                since,
+                since_timestamp_nanos.unwrap_or_default(),
+            ),
+            _ => return Err(ObserveServiceError::ConflictingLogReplay.into()),
+        };
 
         println!("Requested Channel {channel:?}");
         println!("Requested Process ID {pid}");
 
-        let mut log_consumer = {
-            let mut consumer_list = self.sub_process_consumer_list.lock().await;
-            consumer_list
-                .get_mut(&pid)
-                .ok_or(ObserveServiceError::NoChannelsForPid { pid })?
-                .get_mut(&channel)
-                .ok_or(ObserveServiceError::ChannelNotRegistered {
-                    pid,
-                    channel_type: channel,
-                })?
-                .clone()
-        }
-        .subscribe();
+        let (replayed, log_consumer) =
+            self.get_sub_process_log_stream(pid, channel, replay).await?;
+
+        Ok(Response::new(stream_log_items(replayed, log_consumer)))
+    }
+
+    type GetExecutableLogStreamStream =
+        ReceiverStream<Result<GetExecutableLogStreamResponse, Status>>;
+
+    async fn get_executable_log_stream(
+        &self,
+        request: Request<GetExecutableLogStreamRequest>,
+    ) -> Result<Response<Self::GetExecutableLogStreamStream>, Status> {
+        let GetExecutableLogStreamRequest {
+            cell_name,
+            executable_name,
+            channel_type,
+            tail,
+            since_timestamp,
+            since_timestamp_nanos,
+        } = request.into_inner();
+        let cell_name = cell_name
+            .map(|x| CellName::validate(Some(x), "cell_name", None))
+            .transpose()?;
+        let executable_name = ExecutableName::validate(
+            Some(executable_name),
+            "executable_name",
+            None,
+        )?;
+        let channel = LogChannelType::try_from(channel_type).map_err(|_| {
+            ObserveServiceError::InvalidLogChannelType { channel_type }
+        })?;
+        let replay = match (tail, since_timestamp) {
+            (None, None) => LogReplay::FromBeginning,
+            (Some(tail), None) => LogReplay::Tail(tail as usize),
+            (None, Some(since)) => LogReplay::Since(
+                since,
+                since_timestamp_nanos.unwrap_or_default(),
+            ),
+            _ => return Err(ObserveServiceError::ConflictingLogReplay.into()),
+        };
+
+        let items = self
+            .read_executable_log_file(cell_name, executable_name, channel)
+            .await?;
 
         let (tx, rx) =
-            mpsc::channel::<Result<GetSubProcessStreamResponse, Status>>(4);
+            mpsc::channel::<Result<GetExecutableLogStreamResponse, Status>>(4);
 
-        // TODO: error handling. Warning: recursively logging if error message is also send to this grpc api endpoint
-        //  .. thus disabled logging here.
         let _ignored = tokio::spawn(async move {
-            // Log consumer will error if:
-            //  the producer is closed (no more logs)
-            //  the receiver is lagging
-            while let Ok(log_item) = log_consumer.recv().await {
-                let resp = GetSubProcessStreamResponse { item: Some(log_item) };
+            for log_item in replay.apply(items) {
+                let resp =
+                    GetExecutableLogStreamResponse { item: Some(log_item) };
                 if tx.send(Ok(resp)).await.is_err() {
                     // receiver is gone
                     break;
@@ -270,13 +533,40 @@ impl observe_service_server::ObserveService for ObserveService {
             request.into_inner().workload.map(|w| (w.workload_type(), w.id)),
         )))
     }
+
+    type GetCellEventsStreamStream =
+        ReceiverStream<Result<GetCellEventsStreamResponse, Status>>;
+
+    async fn get_cell_events_stream(
+        &self,
+        request: Request<GetCellEventsStreamRequest>,
+    ) -> Result<Response<Self::GetCellEventsStreamStream>, Status> {
+        Ok(Response::new(self.get_cell_events_stream(
+            request.into_inner().workload.map(|w| (w.workload_type(), w.id)),
+        )))
+    }
+
+    type GetAuditEventsStreamStream =
+        ReceiverStream<Result<GetAuditEventsStreamResponse, Status>>;
+
+    async fn get_audit_events_stream(
+        &self,
+        request: Request<GetAuditEventsStreamRequest>,
+    ) -> Result<Response<Self::GetAuditEventsStreamStream>, Status> {
+        Ok(Response::new(self.get_audit_events_stream(
+            request.into_inner().workload.map(|w| (w.workload_type(), w.id)),
+        )))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::ObserveService;
-    use crate::logging::log_channel::LogChannel;
-    use proto::observe::LogChannelType;
+    use super::{ObserveService, stream_log_items};
+    use crate::logging::log_channel::{
+        LogChannel, LogChannelConfig, LogReplay,
+    };
+    use proto::observe::{GetSubProcessStreamResponse, LogChannelType, LogGap};
+    use tokio_stream::StreamExt;
 
     #[tokio::test]
     async fn test_register_sub_process_channel_success() {
@@ -387,4 +677,30 @@ mod tests {
 
         svc.sub_process_consumer_list.lock().await.clear();
     }
+
+    #[tokio::test]
+    async fn test_log_stream_reports_gap_when_lagging() {
+        let channel = LogChannel::with_config(
+            String::from("foo"),
+            &LogChannelConfig { capacity: 1, ..Default::default() },
+        );
+        let (replayed, log_consumer) =
+            channel.subscribe_from(LogReplay::Live).await;
+
+        channel.send(String::from("1"));
+        channel.send(String::from("2"));
+        channel.send(String::from("3"));
+
+        let mut stream = stream_log_items::<GetSubProcessStreamResponse>(
+            replayed,
+            log_consumer,
+        );
+
+        let resp = stream.next().await.expect("response").expect("gap");
+        assert_eq!(resp.gap, Some(LogGap { missed: 2 }));
+        assert!(resp.item.is_none());
+
+        let resp = stream.next().await.expect("response").expect("item");
+        assert_eq!(resp.item.expect("item").line, "3");
+    }
 }
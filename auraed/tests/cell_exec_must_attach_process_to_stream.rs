@@ -0,0 +1,84 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+use client::cells::cell_service::CellServiceClient;
+use common::cells::CellServiceAllocateRequestBuilder;
+use futures_util::StreamExt;
+use proto::cells::{
+    CellServiceExecRequest, CellServiceExecResponse, Executable,
+    ExecutableExitStatus,
+};
+use test_helpers::*;
+
+mod common;
+
+#[test_helpers_macros::shared_runtime_test]
+async fn cell_exec_must_attach_process_to_stream() {
+    skip_if_not_root!("cell_exec_must_attach_process_to_stream");
+    skip_if_seccomp!("cell_exec_must_attach_process_to_stream");
+
+    let client = common::auraed_client().await;
+
+    // Allocate a cell
+    let cell_name = retry!(
+        client.allocate(CellServiceAllocateRequestBuilder::new().build()).await
+    )
+    .unwrap()
+    .into_inner()
+    .cell_name;
+
+    // Exec a process that echoes its stdin, and write to its stdin in the
+    // following request
+    let requests = vec![
+        CellServiceExecRequest {
+            cell_name: Some(cell_name.clone()),
+            executable: Some(Executable {
+                name: format!("ae-exec-{}", uuid::Uuid::new_v4()),
+                command: "cat; exit 3".to_string(),
+                ..Default::default()
+            }),
+            interactive: true,
+            ..Default::default()
+        },
+        CellServiceExecRequest {
+            stdin: b"hello from the client\n".to_vec(),
+            close_stdin: true,
+            ..Default::default()
+        },
+    ];
+
+    let mut responses = retry!(
+        client
+            .exec(Box::pin(futures_util::stream::iter(requests.clone())))
+            .await
+    )
+    .unwrap()
+    .into_inner();
+
+    let mut stdout = vec![];
+    let mut exit_status = None;
+    while let Some(res) = responses.next().await {
+        let CellServiceExecResponse {
+            stdout: out, exit_status: status, ..
+        } = res.unwrap();
+        stdout.extend(out);
+        exit_status = status.or(exit_status);
+    }
+
+    assert_eq!(String::from_utf8_lossy(&stdout), "hello from the client\n");
+    assert_eq!(
+        exit_status,
+        Some(ExecutableExitStatus { code: Some(3), signal: None })
+    );
+}
@@ -17,6 +17,7 @@ use common::cells::CellServiceAllocateRequestBuilder;
 use pretty_assertions::assert_eq;
 use proto::cells::{
     Cell, CellGraphNode, CellServiceListRequest, CellServiceListResponse,
+    CellState,
 };
 use test_helpers::*;
 
@@ -87,9 +88,20 @@ async fn cells_list_must_list_allocated_cells_recursively() {
                     cpu: None,
                     cpuset: None,
                     memory: None,
+                    io: None,
+                    pids: None,
                     isolate_process: false,
                     isolate_network: false,
+                    isolate_user: false,
+                    uid_mappings: vec![],
+                    gid_mappings: vec![],
+                    rootfs: None,
+                    mounts: vec![],
+                    seccomp_profile: None,
                 }),
+                state: CellState::Allocated as i32,
+                executables: vec![],
+                ip_addresses: vec![],
                 children: vec![],
             },
             CellGraphNode {
@@ -98,32 +110,66 @@ async fn cells_list_must_list_allocated_cells_recursively() {
                     cpu: None,
                     cpuset: None,
                     memory: None,
+                    io: None,
+                    pids: None,
                     isolate_process: false,
                     isolate_network: false,
+                    isolate_user: false,
+                    uid_mappings: vec![],
+                    gid_mappings: vec![],
+                    rootfs: None,
+                    mounts: vec![],
+                    seccomp_profile: None,
                 }),
+                state: CellState::Allocated as i32,
+                executables: vec![],
+                ip_addresses: vec![],
                 children: vec![CellGraphNode {
                     cell: Some(Cell {
                         name: nested_cell_name,
                         cpu: None,
                         cpuset: None,
                         memory: None,
+                        io: None,
+                        pids: None,
                         isolate_process: false,
                         isolate_network: false,
+                        isolate_user: false,
+                        uid_mappings: vec![],
+                        gid_mappings: vec![],
+                        rootfs: None,
+                        mounts: vec![],
+                        seccomp_profile: None,
                     }),
+                    state: CellState::Allocated as i32,
+                    executables: vec![],
+                    ip_addresses: vec![],
                     children: vec![CellGraphNode {
                         cell: Some(Cell {
                             name: double_nested_cell_name,
                             cpu: None,
                             cpuset: None,
                             memory: None,
+                            io: None,
+                            pids: None,
                             isolate_process: false,
                             isolate_network: false,
+                            isolate_user: false,
+                            uid_mappings: vec![],
+                            gid_mappings: vec![],
+                            rootfs: None,
+                            mounts: vec![],
+                            seccomp_profile: None,
                         }),
+                        state: CellState::Allocated as i32,
+                        executables: vec![],
+                        ip_addresses: vec![],
                         children: vec![],
                     }],
                 }],
             },
         ],
+        executables: vec![],
     };
 
     // Assert that the actual response matches the expected
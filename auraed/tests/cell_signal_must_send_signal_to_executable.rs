@@ -0,0 +1,120 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+use client::cells::cell_service::CellServiceClient;
+use common::{
+    cells::{
+        CellServiceAllocateRequestBuilder, CellServiceStartRequestBuilder,
+    },
+    observe::{
+        GetPosixSignalsStreamRequestBuilder, intercept_posix_signals_stream,
+    },
+};
+use proto::{
+    cells::{CellServiceSignalRequest, CellServiceStopRequest},
+    observe::Signal,
+};
+use std::time::Duration;
+use test_helpers::*;
+
+mod common;
+
+#[test_helpers_macros::shared_runtime_test]
+#[ignore = "we can not run eBPF tests in Github actions"]
+async fn cell_signal_must_send_signal_to_executable() {
+    skip_if_not_root!("cell_signal_must_send_signal_to_executable");
+    skip_if_seccomp!("cell_signal_must_send_signal_to_executable");
+
+    let client = common::auraed_client().await;
+
+    // Allocate a cell
+    let cell_name = retry!(
+        client.allocate(CellServiceAllocateRequestBuilder::new().build()).await
+    )
+    .unwrap()
+    .into_inner()
+    .cell_name;
+
+    // Start an executable
+    let exe_name = format!("ae-e2e-{}", uuid::Uuid::new_v4());
+    let pid = retry!(
+        client
+            .start(
+                CellServiceStartRequestBuilder::new()
+                    .cell_name(cell_name.clone())
+                    .executable_name(exe_name.clone())
+                    .build(),
+            )
+            .await
+    )
+    .unwrap()
+    .into_inner()
+    .pid;
+
+    // Start intercepting signals for the cell
+    let intercepted_signals = intercept_posix_signals_stream(
+        &client,
+        GetPosixSignalsStreamRequestBuilder::new()
+            .cell_workload(cell_name.clone())
+            .build(),
+    )
+    .await;
+
+    // Signals outside of the allow-list are rejected
+    let status = client
+        .signal(CellServiceSignalRequest {
+            cell_name: Some(cell_name.clone()),
+            executable_name: exe_name.clone(),
+            signal: 11,
+        })
+        .await
+        .expect_err("SIGSEGV is not allowed");
+    assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+
+    // Send SIGSTOP, as it does not terminate the executable
+    let _ = retry!(
+        client
+            .signal(CellServiceSignalRequest {
+                cell_name: Some(cell_name.clone()),
+                executable_name: exe_name.clone(),
+                signal: 19,
+            })
+            .await
+    )
+    .unwrap();
+
+    // Wait for a little for the signal to arrive
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    {
+        let guard = intercepted_signals.lock().await;
+        let expected = Signal { process_id: pid, signal: 19 };
+        assert!(
+            guard.contains(&expected),
+            "signal not found\nexpected: {expected:#?}\nintercepted: {guard:#?}",
+        );
+    }
+
+    // Stop the executable
+    let _ = retry!(
+        client
+            .stop(CellServiceStopRequest {
+                cell_name: Some(cell_name.clone()),
+                executable_name: exe_name.clone(),
+                signal: Some(9),
+                grace_period_ms: None,
+            })
+            .await
+    );
+}
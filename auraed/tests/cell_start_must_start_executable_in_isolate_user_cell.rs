@@ -0,0 +1,78 @@
+/* -------------------------------------------------------------------------- *\
+ *                |   █████╗ ██╗   ██╗██████╗  █████╗ ███████╗ |              *
+ *                |  ██╔══██╗██║   ██║██╔══██╗██╔══██╗██╔════╝ |              *
+ *                |  ███████║██║   ██║██████╔╝███████║█████╗   |              *
+ *                |  ██╔══██║██║   ██║██╔══██╗██╔══██║██╔══╝   |              *
+ *                |  ██║  ██║╚██████╔╝██║  ██║██║  ██║███████╗ |              *
+ *                |  ╚═╝  ╚═╝ ╚═════╝ ╚═╝  ╚═╝╚═╝  ╚═╝╚══════╝ |              *
+ *                +--------------------------------------------+              *
+ *                                                                            *
+ *                         Distributed Systems Runtime                        *
+ * -------------------------------------------------------------------------- *
+ * Copyright 2022 - 2024, the aurae contributors                              *
+ * SPDX-License-Identifier: Apache-2.0                                        *
+\* -------------------------------------------------------------------------- */
+use client::cells::cell_service::CellServiceClient;
+use common::cells::{
+    CellServiceAllocateRequestBuilder, CellServiceStartRequestBuilder,
+};
+use proto::cells::{CellServiceFreeRequest, CellServiceStopRequest};
+use test_helpers::*;
+
+mod common;
+
+#[test_helpers_macros::shared_runtime_test]
+async fn cell_start_must_start_executable_in_isolate_user_cell() {
+    skip_if_not_root!("cell_start_must_start_executable_in_isolate_user_cell");
+    skip_if_seccomp!("cell_start_must_start_executable_in_isolate_user_cell");
+
+    let client = common::auraed_client().await;
+
+    // Allocate a cell whose nested auraed runs as an unprivileged host user
+    let cell_name = retry!(
+        client
+            .allocate(
+                CellServiceAllocateRequestBuilder::new().isolate_user().build()
+            )
+            .await
+    )
+    .unwrap()
+    .into_inner()
+    .cell_name;
+
+    // The nested auraed must have bound its socket to accept the request
+    let exe_name = format!("ae-e2e-{}", uuid::Uuid::new_v4());
+    let pid = retry!(
+        client
+            .start(
+                CellServiceStartRequestBuilder::new()
+                    .cell_name(cell_name.clone())
+                    .executable_name(exe_name.clone())
+                    .build(),
+            )
+            .await
+    )
+    .unwrap()
+    .into_inner()
+    .pid;
+    assert!(pid > 0);
+
+    let _ = retry!(
+        client
+            .stop(CellServiceStopRequest {
+                cell_name: Some(cell_name.clone()),
+                executable_name: exe_name.clone(),
+                signal: Some(9),
+                grace_period_ms: None,
+            })
+            .await
+    )
+    .unwrap();
+
+    let _ = retry!(
+        client
+            .free(CellServiceFreeRequest { cell_name: cell_name.clone() })
+            .await
+    )
+    .unwrap();
+}
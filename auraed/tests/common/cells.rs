@@ -16,6 +16,7 @@
 
 use proto::cells::{
     Cell, CellServiceAllocateRequest, CellServiceStartRequest, Executable,
+    IdMapping,
 };
 
 fn generate_cell_name(parent_name: Option<&str>) -> String {
@@ -26,14 +27,20 @@ fn generate_cell_name(parent_name: Option<&str>) -> String {
     }
 }
 
+/// The range of host ids the users and groups of a cell isolating its users
+/// are mapped to.
+const HOST_ID_MAPPING: IdMapping =
+    IdMapping { container_id: 0, host_id: 100000, size: 65536 };
+
 struct CellBuilder {
     parent: Option<String>,
     isolate_process: bool,
+    isolate_user: bool,
 }
 
 impl CellBuilder {
     pub fn new() -> Self {
-        Self { parent: None, isolate_process: false }
+        Self { parent: None, isolate_process: false, isolate_user: false }
     }
 
     pub fn parent_cell_name(&mut self, parent_cell_name: String) -> &mut Self {
@@ -46,15 +53,30 @@ impl CellBuilder {
         self
     }
 
+    pub fn isolate_user(&mut self) -> &mut Self {
+        self.isolate_user = true;
+        self
+    }
+
     pub fn build(&self) -> Cell {
+        let mappings =
+            if self.isolate_user { vec![HOST_ID_MAPPING] } else { vec![] };
         let cell_name = generate_cell_name(self.parent.as_deref());
         Cell {
             name: cell_name,
             cpu: None,
             cpuset: None,
             memory: None,
+            io: None,
+            pids: None,
             isolate_network: false,
             isolate_process: self.isolate_process,
+            isolate_user: self.isolate_user,
+            uid_mappings: mappings.clone(),
+            gid_mappings: mappings,
+            rootfs: None,
+            mounts: vec![],
+            seccomp_profile: None,
         }
     }
 }
@@ -78,6 +100,11 @@ impl CellServiceAllocateRequestBuilder {
         self
     }
 
+    pub fn isolate_user(&mut self) -> &mut Self {
+        let _ = self.cell_builder.isolate_user();
+        self
+    }
+
     pub fn build(&self) -> CellServiceAllocateRequest {
         CellServiceAllocateRequest { cell: Some(self.cell_builder.build()) }
     }
@@ -108,6 +135,18 @@ impl ExecutableBuilder {
             name: self.name.clone(),
             command: self.command.clone(),
             description: self.description.clone(),
+            args: vec![],
+            env: vec![],
+            clear_env: false,
+            working_dir: None,
+            restart_policy: 0,
+            max_restarts: None,
+            restart_backoff_ms: None,
+            max_restart_backoff_ms: None,
+            seccomp_profile: None,
+            capabilities: None,
+            rlimits: vec![],
+            command_mode: 0,
         }
     }
 }
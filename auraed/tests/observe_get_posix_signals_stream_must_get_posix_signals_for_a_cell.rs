@@ -98,6 +98,8 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_a_cell() {
             .stop(CellServiceStopRequest {
                 cell_name: Some(cell1_name.clone()),
                 executable_name: exe1_name.clone(),
+                signal: None,
+                grace_period_ms: None,
             })
             .await
     );
@@ -108,6 +110,8 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_a_cell() {
             .stop(CellServiceStopRequest {
                 cell_name: Some(cell2_name.clone()),
                 executable_name: exe2_name.clone(),
+                signal: None,
+                grace_period_ms: None,
             })
             .await
     );
@@ -118,14 +122,14 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_a_cell() {
     let guard = intercepted_signals.lock().await;
 
     // Assert we intercepted the signal for the executable in the first cell
-    let expected = Signal { process_id: pid1, signal: 9 };
+    let expected = Signal { process_id: pid1, signal: 15 };
     assert!(
         guard.contains(&expected),
         "signal not found\nexpected: {expected:#?}\nintercepted: {guard:#?}",
     );
     // Assert we did NOT intercept the signal for the executable in the second cell
     assert!(
-        !guard.contains(&Signal { process_id: pid2, signal: 9 }),
+        !guard.contains(&Signal { process_id: pid2, signal: 15 }),
         "unexpected signal intercepted"
     );
 }
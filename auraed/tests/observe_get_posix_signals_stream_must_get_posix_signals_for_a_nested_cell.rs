@@ -129,6 +129,8 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_a_nested_cel
             .stop(CellServiceStopRequest {
                 cell_name: Some(cell1_name.clone()),
                 executable_name: exe1_name.clone(),
+                signal: None,
+                grace_period_ms: None,
             })
             .await
     );
@@ -139,6 +141,8 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_a_nested_cel
             .stop(CellServiceStopRequest {
                 cell_name: Some(nested_cell_name.clone()),
                 executable_name: nested_exe_name.clone(),
+                signal: None,
+                grace_period_ms: None,
             })
             .await
     );
@@ -149,6 +153,8 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_a_nested_cel
             .stop(CellServiceStopRequest {
                 cell_name: Some(cell2_name.clone()),
                 executable_name: exe2_name.clone(),
+                signal: None,
+                grace_period_ms: None,
             })
             .await
     );
@@ -159,19 +165,19 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_a_nested_cel
     let guard = intercepted_signals.lock().await;
 
     // Assert we intercepted the signal for the executable in the nested cell
-    let expected = Signal { process_id: nested_pid, signal: 9 };
+    let expected = Signal { process_id: nested_pid, signal: 15 };
     assert!(
         guard.contains(&expected),
         "signal not found\nexpected: {expected:#?}\nintercepted: {guard:#?}",
     );
     // Assert we did NOT intercept the signal for the executable in the first (parent) cell
     assert!(
-        !guard.contains(&Signal { process_id: pid1, signal: 9 }),
+        !guard.contains(&Signal { process_id: pid1, signal: 15 }),
         "unexpected signal intercepted"
     );
     // Assert we did NOT intercept the signal for the executable in the second cell
     assert!(
-        !guard.contains(&Signal { process_id: pid2, signal: 9 }),
+        !guard.contains(&Signal { process_id: pid2, signal: 15 }),
         "unexpected signal intercepted"
     );
 }
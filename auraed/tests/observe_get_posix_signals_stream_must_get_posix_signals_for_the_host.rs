@@ -66,12 +66,14 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_the_host() {
     )
     .await;
 
-    // Stop the executable (should trigger SIGKILL)
+    // Stop the executable (should trigger SIGTERM)
     let _ = retry!(
         client
             .stop(CellServiceStopRequest {
                 cell_name: Some(cell_name.clone()),
                 executable_name: exe_name.clone(),
+                signal: None,
+                grace_period_ms: None,
             })
             .await
     );
@@ -81,7 +83,7 @@ async fn observe_get_posix_signal_stream_must_get_posix_signals_for_the_host() {
 
     // Assert we intercepted the signal
     let guard = intercepted_signals.lock().await;
-    let expected = Signal { process_id: pid, signal: 9 };
+    let expected = Signal { process_id: pid, signal: 15 };
     assert!(
         guard.contains(&expected),
         "signal not found\nexpected: {expected:#?}\nintercepted: {guard:#?}",
@@ -73,12 +73,14 @@ async fn observe_get_posix_signal_stream_must_map_host_pids_to_namespace_pids()
     )
     .await;
 
-    // Stop the executable (should trigger SIGKILL)
+    // Stop the executable (should trigger SIGTERM)
     let _ = retry!(
         client
             .stop(CellServiceStopRequest {
                 cell_name: Some(cell_name.clone()),
                 executable_name: exe_name.clone(),
+                signal: None,
+                grace_period_ms: None,
             })
             .await
     );
@@ -88,7 +90,7 @@ async fn observe_get_posix_signal_stream_must_map_host_pids_to_namespace_pids()
 
     // Assert we intercepted the signal
     let guard = intercepted_signals.lock().await;
-    let expected = Signal { process_id: nspid, signal: 9 };
+    let expected = Signal { process_id: nspid, signal: 15 };
     assert!(
         guard.contains(&expected),
         "signal not found\nexpected: {expected:#?}\nintercepted: {guard:#?}",
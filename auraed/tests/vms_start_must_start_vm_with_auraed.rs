@@ -130,6 +130,8 @@ async fn vms_with_auraed() {
         CellServiceStopRequest {
             cell_name: Some(cell_name.clone()),
             executable_name: "sleeper".into(),
+            signal: None,
+            grace_period_ms: None,
         },
     )
     .await
@@ -74,7 +74,21 @@ pub(crate) fn service(input: TokenStream) -> TokenStream {
 
             match (m.client_streaming.unwrap_or(false), m.server_streaming.unwrap_or(false)) {
                 (true, true) => {
-                    todo!("bidirectional streaming")
+                    quote! {
+                        async fn #name(
+                            &self,
+                            req: ::std::pin::Pin<Box<
+                                dyn ::futures_util::Stream<
+                                    Item = ::proto::#module::#input_type
+                                > + Send + 'static
+                            >>
+                        ) -> Result<
+                            ::tonic::Response<
+                                ::tonic::Streaming<::proto::#module::#output_type>
+                            >,
+                            ::tonic::Status
+                        >
+                    }
                 }
                 (true, false) => {
                     todo!("client streaming")